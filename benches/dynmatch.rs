@@ -0,0 +1,97 @@
+//! Compares `dynmatch!` dispatch with the `downcast_ref` chain it originally expanded to,
+//! and layers of `dynmatch!` over a plain and a `CachedError`.\
+//! the original chain doesnt see through wrappers, so it finds no match for the attached error.
+//!
+//! run with `cargo bench`.
+
+use dynerr::*;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use std::{error, fmt};
+
+macro_rules! errors {
+    ($($name:ident),*) => {$(
+        #[derive(Debug)]
+        struct $name(u32);
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}: {}", stringify!($name), self.0)
+            }
+        }
+
+        impl error::Error for $name {}
+    )*};
+}
+
+errors!(E1, E2, E3, E4, E5, E6, E7, E8, E9, E10, E11, E12);
+
+///matches [e] against twelve types with `dynmatch!`
+fn dispatch(e: &DynError) -> u32 {
+    dynmatch!(e,
+        type E1 {arm E1(i) => *i + 1, _ => 0},
+        type E2 {arm E2(i) => *i + 2, _ => 0},
+        type E3 {arm E3(i) => *i + 3, _ => 0},
+        type E4 {arm E4(i) => *i + 4, _ => 0},
+        type E5 {arm E5(i) => *i + 5, _ => 0},
+        type E6 {arm E6(i) => *i + 6, _ => 0},
+        type E7 {arm E7(i) => *i + 7, _ => 0},
+        type E8 {arm E8(i) => *i + 8, _ => 0},
+        type E9 {arm E9(i) => *i + 9, _ => 0},
+        type E10 {arm E10(i) => *i + 10, _ => 0},
+        type E11 {arm E11(i) => *i + 11, _ => 0},
+        type E12 {arm E12(i) => *i + 12, _ => 0},
+        _ => 0
+    )
+}
+
+///the same match written the way `dynmatch!` originally expanded it
+fn baseline(e: &DynError) -> u32 {
+    macro_rules! chain {
+        ($($ty:ident => $n:expr),*) => {
+            $(if let Some($ty(i)) = e.downcast_ref::<$ty>() {*i + $n} else)* {0}
+        };
+    }
+    chain!(E1 => 1, E2 => 2, E3 => 3, E4 => 4, E5 => 5, E6 => 6, E7 => 7, E8 => 8, E9 => 9, E10 => 10, E11 => 11, E12 => 12)
+}
+
+///runs [f] over [errors] until a second has passed and returns the average time per match.
+///the clock is only read every thousand rounds so reading it doesnt outweigh a match
+fn measure(errors: &[DynError], f: fn(&DynError) -> u32) -> Duration {
+    let start = Instant::now();
+    let mut matches = 0u32;
+    while start.elapsed() < Duration::from_secs(1) {
+        for _ in 0..1000 {
+            for e in errors {
+                black_box(f(black_box(e)));
+            }
+        }
+        matches += errors.len() as u32 * 1000;
+    }
+    start.elapsed() / matches
+}
+
+fn main() {
+    let cases: Vec<(&str, Vec<DynError>)> = vec![
+        ("last type", vec![Box::new(E12(0))]),
+        ("attached last type", vec![(Box::new(E12(0)) as DynError).attach(())]),
+        ("no match", vec![DynError::from("none of them")]),
+        ("every type", vec![
+            Box::new(E1(0)), Box::new(E2(0)), Box::new(E3(0)), Box::new(E4(0)), Box::new(E5(0)), Box::new(E6(0)),
+            Box::new(E7(0)), Box::new(E8(0)), Box::new(E9(0)), Box::new(E10(0)), Box::new(E11(0)), Box::new(E12(0)),
+        ]),
+    ];
+    for (name, errors) in &cases {
+        println!("{:<20} dynmatch {:>10?}    baseline {:>10?}", name, measure(errors, dispatch), measure(errors, baseline));
+    }
+    let attached = || (Box::new(E12(0)) as DynError).attach(());
+    let layered = [("attached last type", attached()), ("cached", Box::new(CachedError::new(attached())) as DynError)];
+    for (name, e) in layered {
+        println!("{:<20} 4 layers {:>10?}", name, measure(&[e], layers));
+    }
+}
+
+///matches [e] the way four layers of middleware would, each with its own `dynmatch!`
+fn layers(e: &DynError) -> u32 {
+    (0..4).map(|_| dispatch(e)).sum()
+}
@@ -0,0 +1,64 @@
+//! Compares the allocations and time of a parser rejecting input with `DynResult` and with `SmallResult`.
+//!
+//! run with `cargo bench --bench small_error`.
+
+use dynerr::*;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+///the system allocator, counting every allocation
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+fn parse_dyn(text: &str) -> DynResult<u32> {
+    Ok(text.parse()?)
+}
+
+fn parse_small(text: &str) -> SmallResult<u32> {
+    Ok(text.parse()?)
+}
+
+///runs [f] over [inputs] until a second has passed and returns the average time and allocations per parse
+fn measure<E>(inputs: &[&str], f: fn(&str) -> Result<u32, E>) -> (Duration, f64) {
+    let start = Instant::now();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let mut parses = 0u32;
+    while start.elapsed() < Duration::from_secs(1) {
+        for input in inputs {
+            let _ = black_box(f(black_box(input)));
+        }
+        parses += inputs.len() as u32;
+    }
+    let elapsed = start.elapsed();
+    (elapsed / parses, (ALLOCATIONS.load(Ordering::Relaxed) - allocations) as f64 / parses as f64)
+}
+
+fn main() {
+    let cases: Vec<(&str, Vec<&str>)> = vec![
+        ("all rejected", vec!["x", "", "-", "12a", "99999999999"]),
+        ("half rejected", vec!["1", "x", "22", "", "333", "-"]),
+    ];
+    for (name, inputs) in &cases {
+        let (dyn_time, dyn_allocations) = measure(inputs, parse_dyn);
+        let (small_time, small_allocations) = measure(inputs, parse_small);
+        println!("{:<15} DynResult {:>8?} {:.2} allocs    SmallResult {:>8?} {:.2} allocs",
+            name, dyn_time, dyn_allocations, small_time, small_allocations);
+    }
+}
@@ -0,0 +1,154 @@
+//! Attribute macros for dynerr. use them through dynerr's `attributes` feature.
+//!
+//! the macros are written against `proc_macro` alone so the crate has no dependencies.
+
+use proc_macro::{Delimiter, Group, Punct, Spacing, Span, TokenStream, TokenTree};
+
+/// Logs the errors a function returning `DynResult` propagates, along with its name and arguments.
+///
+/// see the documentation of `dynerr::logged`.
+#[proc_macro_attribute]
+pub fn logged(attr: TokenStream, item: TokenStream) -> TokenStream {
+    match expand(attr, item) {
+        Ok(tokens) => tokens,
+        Err(message) => format!("compile_error!({:?});", message).parse().unwrap(),
+    }
+}
+
+///the options given to `#[logged(..)]`
+#[derive(Default)]
+struct Options {
+    entry: bool,
+    skip: Vec<String>,
+}
+
+///parses `entry` and `skip(a, b)` out of the attribute
+fn options(attr: TokenStream) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut tokens = attr.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Ident(ident) if ident.to_string() == "entry" => options.entry = true,
+            TokenTree::Ident(ident) if ident.to_string() == "skip" => match tokens.next() {
+                Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+                    options.skip.extend(group.stream().into_iter().filter_map(|t| match t {
+                        TokenTree::Ident(ident) => Some(ident.to_string()),
+                        _ => None,
+                    }));
+                }
+                _ => return Err("Dynerr: expected a list of arguments after skip, like skip(password)".to_string()),
+            },
+            TokenTree::Punct(punct) if punct.as_char() == ',' => {}
+            other => return Err(format!("Dynerr: unknown #[logged] option `{}`, expected entry or skip(..)", other)),
+        }
+    }
+    Ok(options)
+}
+
+///splits [tokens] on commas that arent inside angle brackets
+fn split_args(tokens: TokenStream) -> Vec<Vec<TokenTree>> {
+    let mut args = vec![Vec::new()];
+    let mut depth = 0i32;
+    let mut after_dash = false;
+    for token in tokens {
+        if let TokenTree::Punct(punct) = &token {
+            match punct.as_char() {
+                '<' => depth += 1,
+                '>' if !after_dash => depth -= 1,
+                ',' if depth == 0 => {
+                    args.push(Vec::new());
+                    after_dash = false;
+                    continue
+                }
+                _ => {}
+            }
+            after_dash = punct.as_char() == '-' && punct.spacing() == Spacing::Joint;
+        } else {
+            after_dash = false;
+        }
+        args.last_mut().unwrap().push(token);
+    }
+    args.retain(|arg| !arg.is_empty());
+    args
+}
+
+///the name bound by an argument like `name: T` or `mut name: T`. None for `self` and destructuring patterns
+fn arg_name(arg: &[TokenTree]) -> Option<String> {
+    let colon = arg.iter().position(|t| matches!(t, TokenTree::Punct(p) if p.as_char() == ':'))?;
+    match &arg[..colon] {
+        [TokenTree::Ident(name)] if name.to_string() != "self" => Some(name.to_string()),
+        [TokenTree::Ident(binding), TokenTree::Ident(name)] if binding.to_string() == "mut" => Some(name.to_string()),
+        _ => None,
+    }
+}
+
+fn expand(attr: TokenStream, item: TokenStream) -> Result<TokenStream, String> {
+    let options = options(attr)?;
+    let mut tokens: Vec<TokenTree> = item.into_iter().collect();
+    let body = match tokens.pop() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => group,
+        _ => return Err("Dynerr: #[logged] can only be used on functions with a body".to_string()),
+    };
+    let is_ident = |t: &TokenTree, name: &str| matches!(t, TokenTree::Ident(i) if i.to_string() == name);
+    let fn_index = tokens.iter().position(|t| is_ident(t, "fn"))
+        .ok_or("Dynerr: #[logged] can only be used on functions")?;
+    if tokens[..fn_index].iter().any(|t| is_ident(t, "async")) {
+        return Err("Dynerr: #[logged] doesnt support async functions".to_string())
+    }
+    let name = match tokens.get(fn_index + 1) {
+        Some(TokenTree::Ident(name)) => name.to_string(),
+        _ => return Err("Dynerr: expected a function name".to_string()),
+    };
+
+    //skip the generics to find the arguments, then the return type
+    let mut depth = 0i32;
+    let mut index = fn_index + 2;
+    let args = loop {
+        match tokens.get(index) {
+            Some(TokenTree::Punct(p)) if p.as_char() == '<' => depth += 1,
+            Some(TokenTree::Punct(p)) if p.as_char() == '>' && !matches!(&tokens[index - 1], TokenTree::Punct(d) if d.as_char() == '-') => depth -= 1,
+            Some(TokenTree::Group(group)) if depth == 0 && group.delimiter() == Delimiter::Parenthesis => break group.clone(),
+            Some(_) => {}
+            None => return Err("Dynerr: expected the arguments of the function".to_string()),
+        }
+        index += 1;
+    };
+    let rest = &tokens[index + 1..];
+    let returns = match rest {
+        [TokenTree::Punct(dash), TokenTree::Punct(arrow), ret @ ..] if dash.as_char() == '-' && arrow.as_char() == '>' => {
+            let end = ret.iter().position(|t| is_ident(t, "where")).unwrap_or(ret.len());
+            ret[..end].iter().cloned().collect::<TokenStream>()
+        }
+        _ => return Err("Dynerr: #[logged] functions must return a DynResult".to_string()),
+    };
+
+    let names: Vec<String> = split_args(args.stream()).iter()
+        .filter_map(|arg| arg_name(arg))
+        .filter(|name| !options.skip.contains(name))
+        .collect();
+    let format = names.iter().map(|name| format!("{}={{:?}}", name)).collect::<Vec<_>>().join(", ");
+    let mut prelude = format!("let __dynerr_args: ::std::string::String = ::std::format!({:?} {});", format,
+        names.iter().map(|name| format!(", {}", name)).collect::<String>());
+    if options.entry {
+        prelude += &format!("::dynerr::__logged_entry({:?}, &__dynerr_args);", name);
+    }
+    prelude += "let __dynerr_result: ";
+
+    let mut inner: TokenStream = prelude.parse().map_err(|_| "Dynerr: couldnt expand #[logged]")?;
+    inner.extend(returns.clone());
+    let mut closure: TokenStream = "move ||".parse().unwrap();
+    closure.extend(vec![TokenTree::Punct(Punct::new('-', Spacing::Joint)), TokenTree::Punct(Punct::new('>', Spacing::Alone))]);
+    closure.extend(returns);
+    closure.extend(vec![TokenTree::Group(body)]);
+    inner.extend(vec![
+        TokenTree::Punct(Punct::new('=', Spacing::Alone)),
+        TokenTree::Group(Group::new(Delimiter::Parenthesis, closure)),
+    ]);
+    inner.extend(format!("(); ::dynerr::__logged_result(__dynerr_result, {:?}, __dynerr_args)", name).parse::<TokenStream>().unwrap());
+
+    let mut out: TokenStream = tokens.into_iter().collect();
+    let mut block = Group::new(Delimiter::Brace, inner);
+    block.set_span(Span::call_site());
+    out.extend(vec![TokenTree::Group(block)]);
+    Ok(out)
+}
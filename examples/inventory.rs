@@ -0,0 +1,88 @@
+//! A small inventory tool showing dynerr end to end: custom errors, recovering with `dynmatch!`,
+//! logging configuration and the panic hook.
+//!
+//! run with `cargo run --example inventory`. it logs to "inventory.log" and prints warnings to stderr.\
+//! dynerr doesnt rotate logs, so the log is capped at 1 MiB instead.
+
+use dynerr::*;
+use std::{error, fmt};
+
+///where the example logs to
+const LOG: &str = "inventory.log";
+
+///the ways a line of the inventory can be wrong
+#[derive(Debug)]
+enum InventoryError {
+    MissingCount(String),
+    NegativeCount(String, i64),
+}
+
+impl fmt::Display for InventoryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InventoryError::MissingCount(item) => write!(f, "{} has no count", item),
+            InventoryError::NegativeCount(item, count) => write!(f, "{} has a negative count of {}", item, count),
+        }
+    }
+}
+
+impl error::Error for InventoryError {}
+
+///parses an `item=count` line
+fn parse_line(line: &str) -> DynResult<(String, i64)> {
+    let Some((item, count)) = line.split_once('=') else {
+        dynerr!(InventoryError::MissingCount(line.trim().to_string()))
+    };
+    let count: i64 = count.trim().parse()?;
+    if count < 0 {
+        dynerr!(InventoryError::NegativeCount(item.trim().to_string(), count))
+    }
+    Ok((item.trim().to_string(), count))
+}
+
+///parses every line, recovering from the errors that have a sensible default and giving up on the rest
+fn load(text: &str) -> DynResult<Vec<(String, i64)>> {
+    let mut items = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let _line = scope!("reading line {}", number + 1);
+        match parse_line(line) {
+            Ok(item) => items.push(item),
+            Err(e) => dynmatch!(e,
+                type InventoryError {
+                    arm InventoryError::MissingCount(item) => {
+                        log_warn!(format!("{}, counting it as 0", e), LOG);
+                        items.push((item.clone(), 0));
+                    },
+                    _ => {log_warn!(format!("skipping line {}: {}", number + 1, e), LOG);}
+                },
+                type std::num::ParseIntError {
+                    arm _ => return Err(e),
+                    _ => return Err(e)
+                },
+                _ => return Err(e)
+            ),
+        }
+    }
+    Ok(items)
+}
+
+fn main() {
+    Logger::builder()
+        .stderr_level(Level::Warn)
+        .size_cap(1024 * 1024, CapAction::RingBufferOnly)
+        .init();
+    install_panic_hook();
+    log_startup_info!(LOG);
+
+    let text = "apples = 4\npears\nplums = -2\ncherries = 30";
+    let items = check!(load(text), LOG);
+    let total: i64 = items.iter().map(|(_, count)| count).sum();
+    log!(format!("loaded {} items, {} in stock", items.len(), total), LOG);
+    println!("{} items, {} in stock", items.len(), total);
+
+    //a count that isnt a number cant be recovered from, so check! logs it and panics.
+    //the panic is caught to finish the example, a real tool would let it end the program
+    let result = std::panic::catch_unwind(|| check!(load("figs = lots"), LOG));
+    assert!(result.is_err());
+    println!("see {} for the log", LOG);
+}
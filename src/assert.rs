@@ -0,0 +1,151 @@
+//! Assertions that return errors through `DynResult` instead of panicking.
+
+use std::error::Error;
+use std::fmt;
+
+/// The error returned by `dyn_assert!`, `dyn_assert_eq!` and `dyn_assert_ne!`.
+/// 
+/// carries the asserted expression, the Debug formatted values being compared and where the assertion is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssertionError {
+    pub expression: String,
+    pub left: Option<String>,
+    pub right: Option<String>,
+    pub message: Option<String>,
+    pub file: &'static str,
+    pub line: u32,
+}
+
+impl fmt::Display for AssertionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "assertion failed: `{}`", self.expression)?;
+        if let (Some(left), Some(right)) = (&self.left, &self.right) {
+            write!(f, " (left: `{}`, right: `{}`)", left, right)?;
+        }
+        if let Some(message) = &self.message {
+            write!(f, ": {}", message)?;
+        }
+        write!(f, " at {}:{}", self.file, self.line)
+    }
+}
+
+impl Error for AssertionError {}
+
+/// Returns an `AssertionError` from the enclosing function if [cond] is false.
+/// 
+/// accepts an optional message in the same form as `format!`.
+/// 
+///# Example
+/// ```rust
+///# use dynerr::*;
+/// fn validate(width: u32) -> DynResult<u32> {
+///     dyn_assert!(width > 0);
+///     dyn_assert!(width <= 4096, "width {} is too large", width);
+///     Ok(width)
+/// }
+///# fn main() {
+/// assert!(validate(10).is_ok());
+/// let e = validate(5000).unwrap_err();
+/// assert!(e.downcast_ref::<AssertionError>().is_some());
+///# }
+/// ```
+#[macro_export]
+macro_rules! dyn_assert {
+    ($cond:expr $(,)?) => {
+        if !$cond {
+            $crate::dynerr!($crate::AssertionError {
+                expression: stringify!($cond).to_string(),
+                left: None,
+                right: None,
+                message: None,
+                file: file!(),
+                line: line!(),
+            })
+        }
+    };
+    ($cond:expr, $($arg:tt)+) => {
+        if !$cond {
+            $crate::dynerr!($crate::AssertionError {
+                expression: stringify!($cond).to_string(),
+                left: None,
+                right: None,
+                message: Some(format!($($arg)+)),
+                file: file!(),
+                line: line!(),
+            })
+        }
+    };
+}
+
+/// Returns an `AssertionError` from the enclosing function if [left] != [right].
+/// 
+/// accepts an optional message in the same form as `format!`.\
+/// both values must implement Debug.
+/// 
+///# Example
+/// ```rust
+///# use dynerr::*;
+/// fn check_header(magic: &[u8]) -> DynResult<()> {
+///     dyn_assert_eq!(magic, b"SAVE", "not a save file");
+///     Ok(())
+/// }
+///# fn main() {
+/// let e = check_header(b"JUNK").unwrap_err();
+/// assert!(e.to_string().contains("not a save file"));
+///# }
+/// ```
+#[macro_export]
+macro_rules! dyn_assert_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::__dyn_assert_cmp!(==, $left, $right, None)
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        $crate::__dyn_assert_cmp!(==, $left, $right, Some(format!($($arg)+)))
+    };
+}
+
+/// Returns an `AssertionError` from the enclosing function if [left] == [right].
+/// 
+/// accepts an optional message in the same form as `format!`.\
+/// both values must implement Debug.
+/// 
+///# Example
+/// ```rust
+///# use dynerr::*;
+/// fn rename(old: &str, new: &str) -> DynResult<()> {
+///     dyn_assert_ne!(old, new);
+///     Ok(())
+/// }
+///# fn main() {
+/// assert!(rename("a", "a").is_err());
+///# }
+/// ```
+#[macro_export]
+macro_rules! dyn_assert_ne {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::__dyn_assert_cmp!(!=, $left, $right, None)
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        $crate::__dyn_assert_cmp!(!=, $left, $right, Some(format!($($arg)+)))
+    };
+}
+
+///shared expansion of `dyn_assert_eq!` and `dyn_assert_ne!`
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __dyn_assert_cmp {
+    ($op:tt, $left:expr, $right:expr, $message:expr) => {
+        match (&$left, &$right) {
+            (left, right) => if !(*left $op *right) {
+                $crate::dynerr!($crate::AssertionError {
+                    expression: concat!(stringify!($left), " ", stringify!($op), " ", stringify!($right)).to_string(),
+                    left: Some(format!("{:?}", left)),
+                    right: Some(format!("{:?}", right)),
+                    message: $message,
+                    file: file!(),
+                    line: line!(),
+                })
+            }
+        }
+    };
+}
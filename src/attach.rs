@@ -0,0 +1,268 @@
+//! Typed values carried along with an error.
+
+use std::any::Any;
+use std::error::Error;
+use std::fmt;
+
+use crate::{CachedError, DynError};
+
+/// An error with attached values.
+/// 
+/// created by `Attach::attach`. displays, debugs and sources exactly like the wrapped error,
+/// and `dynmatch!` matches the wrapped error as if it wasnt wrapped.
+pub struct Attached {
+    error: DynError,
+    attachments: Vec<Box<dyn Any>>,
+}
+
+impl Attached {
+    ///the wrapped error
+    pub fn inner(&self) -> &(dyn Error + 'static) {
+        &*self.error
+    }
+
+    ///unwraps the error, dropping the attachments
+    pub fn into_inner(self) -> DynError {
+        self.error
+    }
+
+    ///returns the most recently attached value of type [A]
+    pub fn attachment<A: Any>(&self) -> Option<&A> {
+        self.attachments.iter()
+            .rev()
+            .find_map(|a| a.downcast_ref::<A>())
+    }
+}
+
+impl fmt::Debug for Attached {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.error, f)
+    }
+}
+
+impl fmt::Display for Attached {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.error, f)
+    }
+}
+
+impl Error for Attached {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.error.source()
+    }
+}
+
+/// Attaches typed values to a `DynError`.
+/// 
+///# Example
+/// ```rust
+///# use dynerr::*;
+/// #[derive(Debug, PartialEq)]
+/// struct RequestId(u32);
+/// 
+/// fn handle() -> DynResult<()> {
+///     std::fs::File::open("none").map_err(|e| DynError::from(e).attach(RequestId(42)))?;
+///     Ok(())
+/// }
+///# fn main() {
+/// let e = handle().unwrap_err();
+/// assert_eq!(e.get_attached::<RequestId>(), Some(&RequestId(42)));
+/// let found = dynmatch!(e,
+///     type std::io::Error {
+///         arm _ => true,
+///         _ => true
+///     },
+///     _ => false
+/// );
+/// assert!(found);
+///# }
+/// ```
+pub trait Attach {
+    ///attaches [value], wrapping the error in `Attached` if it isnt already
+    fn attach<A: Any>(self, value: A) -> DynError;
+    ///returns the most recently attached value of type [A]
+    fn get_attached<A: Any>(&self) -> Option<&A>;
+}
+
+impl Attach for DynError {
+    fn attach<A: Any>(mut self, value: A) -> DynError {
+        if let Some(attached) = self.downcast_mut::<Attached>() {
+            attached.attachments.push(Box::new(value));
+            return self
+        }
+        let attached = Attached {error: self, attachments: vec![Box::new(value)]};
+        crate::error_stats::track(std::any::type_name::<Attached>(), std::mem::size_of::<Attached>(), &attached);
+        Box::new(attached)
+    }
+
+    fn get_attached<A: Any>(&self) -> Option<&A> {
+        self.downcast_ref::<Attached>()?.attachment()
+    }
+}
+
+///downcasts through any crate provided wrappers. used by `dynmatch!`
+#[doc(hidden)]
+pub trait __DowncastThrough {
+    fn __downcast_through<T: Error + 'static>(&self) -> Option<&T>;
+    ///the error itself and, if it is wrapped, the innermost wrapped error
+    fn __peel(&self) -> (&(dyn Error + 'static), __Peeled<'_>);
+}
+
+///the innermost error wrapped by the error a `dynmatch!` matches, and the downcast cache of a `CachedError`. used by `dynmatch!`
+#[doc(hidden)]
+#[derive(Clone, Copy)]
+pub struct __Peeled<'a> {
+    error: Option<&'a (dyn Error + 'static)>,
+    ///whether there are wrappers between the error and the innermost one, which have to be probed too
+    layered: bool,
+    cache: Option<&'a CachedError>,
+}
+
+impl<'a> __Peeled<'a> {
+    ///whether [outer] or an error it wraps is a [T]. compares `TypeId`s without downcasting, skipping the comparisons
+    ///a cache already knows the answer to. used by `dynmatch!`\
+    ///stable rust only exposes the `TypeId` of a `dyn Error` through `is`, so each error compared costs one `is` call
+    #[inline]
+    pub fn __is<T: Error + 'static>(&self, outer: &'a (dyn Error + 'static)) -> bool {
+        match (self.cache, self.layered) {
+            (None, false) => outer.is::<T>() || self.error.is_some_and(|e| e.is::<T>()),
+            _ => self.is_wrapped::<T>(outer),
+        }
+    }
+
+    ///[__is] for an error that is cached or wrapped more than once, kept out of line so the usual case stays small
+    #[inline(never)]
+    fn is_wrapped<T: Error + 'static>(&self, outer: &'a (dyn Error + 'static)) -> bool {
+        let wrapped = || match self.layered {
+            true => self.layers(outer).any(|e| e.is::<T>()),
+            false => self.error.is_some_and(|e| e.is::<T>()),
+        };
+        match self.cache {
+            Some(cache) => cache.is::<T>(outer, wrapped, !self.layered),
+            None => outer.is::<T>() || wrapped(),
+        }
+    }
+
+    ///every error wrapped by [outer], outermost first
+    fn layers(self, outer: &'a (dyn Error + 'static)) -> impl Iterator<Item = &'a (dyn Error + 'static)> {
+        let first = match self.layered {
+            true => unwrap_once(outer),
+            false => self.error,
+        };
+        let layered = self.layered;
+        std::iter::successors(first, move |e: &&'a (dyn Error + 'static)| if layered {unwrap_once(*e)} else {None})
+    }
+}
+
+///the error wrapped by [e], if [e] is one of the crate provided wrappers
+fn unwrap_once<'a>(e: &'a (dyn Error + 'static)) -> Option<&'a (dyn Error + 'static)> {
+    #[cfg(feature = "ffi")]
+    if let Some(shared) = e.downcast_ref::<crate::ffi::SharedHandleError>() {
+        return Some(shared.inner())
+    }
+    if let Some(shared) = e.downcast_ref::<std::sync::Arc<dyn Error>>() {
+        return Some(&**shared)
+    }
+    if let Some(shared) = e.downcast_ref::<std::sync::Arc<dyn Error + Send + Sync>>() {
+        return Some(&**shared)
+    }
+    if let Some(escalated) = e.downcast_ref::<crate::Escalated>() {
+        return Some(escalated.inner())
+    }
+    if let Some(cached) = e.downcast_ref::<CachedError>() {
+        return Some(cached.inner())
+    }
+    Some(e.downcast_ref::<Attached>()?.inner())
+}
+
+impl __DowncastThrough for dyn Error + 'static {
+    fn __downcast_through<T: Error + 'static>(&self) -> Option<&T> {
+        if let Some(found) = self.downcast_ref::<T>() {return Some(found)}
+        unwrap_once(self)?.__downcast_through::<T>()
+    }
+
+    fn __peel(&self) -> (&(dyn Error + 'static), __Peeled<'_>) {
+        if let Some(cached) = self.downcast_ref::<CachedError>() {
+            let (outer, peeled) = cached.inner().__peel();
+            return (outer, __Peeled {cache: Some(cached), ..peeled})
+        }
+        let mut inner = unwrap_once(self);
+        let mut layered = false;
+        while let Some(next) = inner.and_then(unwrap_once) {
+            inner = Some(next);
+            layered = true;
+        }
+        (self, __Peeled {error: inner, layered, cache: None})
+    }
+}
+
+impl __DowncastThrough for dyn Error + Send + 'static {
+    fn __downcast_through<T: Error + 'static>(&self) -> Option<&T> {
+        (self as &(dyn Error + 'static)).__downcast_through::<T>()
+    }
+
+    fn __peel(&self) -> (&(dyn Error + 'static), __Peeled<'_>) {
+        (self as &(dyn Error + 'static)).__peel()
+    }
+}
+
+impl __DowncastThrough for dyn Error + Sync + 'static {
+    fn __downcast_through<T: Error + 'static>(&self) -> Option<&T> {
+        (self as &(dyn Error + 'static)).__downcast_through::<T>()
+    }
+
+    fn __peel(&self) -> (&(dyn Error + 'static), __Peeled<'_>) {
+        (self as &(dyn Error + 'static)).__peel()
+    }
+}
+
+impl __DowncastThrough for dyn Error + Send + Sync + 'static {
+    fn __downcast_through<T: Error + 'static>(&self) -> Option<&T> {
+        (self as &(dyn Error + 'static)).__downcast_through::<T>()
+    }
+
+    fn __peel(&self) -> (&(dyn Error + 'static), __Peeled<'_>) {
+        (self as &(dyn Error + 'static)).__peel()
+    }
+}
+
+///downcasts the error, then every error it wraps, going through the cache if there is one.\
+///an error wrapped once, the usual case, is probed without walking the wrappers again
+fn downcast_peeled<'a, T: Error + 'static>(outer: &'a (dyn Error + 'static), inner: __Peeled<'a>) -> Option<&'a T> {
+    let wrapped = || match inner.layered {
+        true => unwrap_once(outer)?.__downcast_through::<T>(),
+        false => inner.error?.downcast_ref::<T>(),
+    };
+    match inner.cache {
+        Some(cache) => cache.downcast::<T>(outer, wrapped, !inner.layered),
+        None => outer.downcast_ref::<T>().or_else(wrapped),
+    }
+}
+
+///downcasts the error a `dynmatch!` block already found to be a [T] with `__Peeled::__is`. used by `dynmatch!`
+#[doc(hidden)]
+pub fn __downcast_matched<'a, T: Error + 'static>(outer: &'a (dyn Error + 'static), inner: __Peeled<'a>) -> &'a T {
+    downcast_peeled::<T>(outer, inner).expect("Dynerr: dynmatch! couldnt downcast an error it found to be the type")
+}
+
+///the first error of type [T] in the source chain of [outer], not counting [outer] itself. used by `dynmatch!`
+#[doc(hidden)]
+pub fn __downcast_source<'a, T: Error + 'static>(outer: &'a (dyn Error + 'static)) -> Option<&'a T> {
+    let mut current = outer.source();
+    while let Some(e) = current {
+        if let Some(found) = e.__downcast_through::<T>() {return Some(found)}
+        current = e.source();
+    }
+    None
+}
+
+///whether the text of the error, or of any error it wraps, contains any of the `|` separated alternatives in [pattern],
+///ignoring case. used by `dynmatch!`
+#[doc(hidden)]
+pub fn __text_matches(outer: &(dyn Error + 'static), inner: __Peeled<'_>, pattern: &str) -> bool {
+    let matches = |e: &(dyn Error + 'static)| {
+        let text = e.to_string().to_lowercase();
+        pattern.split('|').any(|alternative| text.contains(&alternative.to_lowercase()))
+    };
+    matches(outer) || inner.layers(outer).any(matches)
+}
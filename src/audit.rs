@@ -0,0 +1,231 @@
+//! Tamper evident log files where every record carries a hash chained to the record before it.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs::{metadata, read_to_string};
+use std::io;
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::{DynResult, LogRecord};
+use crate::config::Config;
+
+///the field audited records keep their hash in
+pub(crate) const CHAIN_FIELD: &str = "chain";
+
+///the hash the first record of an audit log is chained to
+const GENESIS: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+///the hash of the last record written to each audit log during this run
+static CHAINS: Mutex<BTreeMap<String, String>> = Mutex::new(BTreeMap::new());
+
+///formats [record] with its chained hash and hands the line, ending in a newline, to [write]
+pub(crate) fn append_chained<W>(config: &Config, record: &LogRecord, log_file: &str, write: W) -> io::Result<()>
+where W: FnOnce(&str) -> io::Result<()> {
+    let mut chains = CHAINS.lock().unwrap_or_else(|e| e.into_inner());
+    let previous = match metadata(log_file).map(|m| m.len()).unwrap_or(0) {
+        0 => GENESIS.to_string(),
+        _ => match chains.get(log_file) {
+            Some(hash) => hash.clone(),
+            None => last_hash(log_file)?,
+        },
+    };
+    let mut chained = record.clone();
+    chained.fields.insert(0, (CHAIN_FIELD.to_string(), GENESIS.to_string()));
+    let text = config.text_format.format(&chained);
+    let hash = chain_hash(&previous, &text);
+    let line = text.replacen(&placeholder(), &format!("{}={}", CHAIN_FIELD, hash), 1);
+    write(&(line + "\n"))?;
+    chains.insert(log_file.to_string(), hash);
+    Ok(())
+}
+
+///forgets the chain of [log_file] after it was cleaned
+pub(crate) fn forget(log_file: &str) {
+    CHAINS.lock().unwrap_or_else(|e| e.into_inner()).remove(log_file);
+}
+
+///the hash of the last record in an existing audit log, so a new run continues its chain
+fn last_hash(log_file: &str) -> io::Result<String> {
+    let text = read_to_string(log_file)?;
+    Ok(text.lines().rev().find_map(chain_of).unwrap_or_else(|| GENESIS.to_string()))
+}
+
+///the chain field of [line] if it starts an audited record
+fn chain_of(line: &str) -> Option<String> {
+    let record = line.parse::<LogRecord>().ok()?;
+    let (_, hash) = record.fields.into_iter().find(|(key, _)| key == CHAIN_FIELD)?;
+    Some(hash).filter(|h| h.len() == GENESIS.len() && h.bytes().all(|b| b.is_ascii_hexdigit()))
+}
+
+///the chain field as it is written before the hash is known
+fn placeholder() -> String {
+    format!("{}={}", CHAIN_FIELD, GENESIS)
+}
+
+///the hash of the record [text] chained to [previous]
+fn chain_hash(previous: &str, text: &str) -> String {
+    let mut data = Vec::with_capacity(previous.len() + text.len());
+    data.extend_from_slice(previous.as_bytes());
+    data.extend_from_slice(text.as_bytes());
+    sha256(&data).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// The reason `verify_log` rejected an audit log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditError {
+    ///the 1 based line the problem was found on
+    pub line: usize,
+    ///what was wrong
+    pub reason: &'static str,
+}
+
+impl fmt::Display for AuditError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "audit log check failed on line {}: {}", self.line, self.reason)
+    }
+}
+
+impl Error for AuditError {}
+
+/// Checks the hash chain of an audit log and returns how many records it holds.
+///
+/// fails with an `AuditError` naming the first line that was edited, removed, reordered or inserted.\
+/// records are written to an audit log by marking the file with `LoggerBuilder::audit`.
+/// only one process should write to an audit log at a time.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+///# clean!("verified.log");
+/// Logger::builder().audit("verified.log").init();
+/// log_info!("sale 1042 total 19.99", "verified.log");
+/// log_info!("refund 1042", "verified.log");
+/// assert_eq!(verify_log("verified.log").unwrap(), 2);
+///
+/// let text = std::fs::read_to_string("verified.log").unwrap();
+/// std::fs::write("verified.log", text.replace("19.99", "1.99")).unwrap();
+/// assert!(verify_log("verified.log").is_err());
+///# clean!("verified.log");
+///# init();
+///# }
+/// ```
+pub fn verify_log<P: AsRef<Path>>(path: P) -> DynResult<usize> {
+    let text = read_to_string(path)?;
+    if text.is_empty() {return Ok(0)}
+    let mut previous = GENESIS.to_string();
+    let mut count = 0;
+    //the start line, hash and text of the record being collected
+    let mut pending: Option<(usize, String, String)> = None;
+    let mut check = |pending: Option<(usize, String, String)>, previous: &mut String| -> Result<(), AuditError> {
+        if let Some((line, hash, text)) = pending {
+            if chain_hash(previous, &text) != hash {
+                return Err(AuditError {line, reason: "record does not match its hash"})
+            }
+            *previous = hash;
+            count += 1;
+        }
+        Ok(())
+    };
+    for (i, line) in text.strip_suffix('\n').unwrap_or(&text).split('\n').enumerate() {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        match chain_of(line) {
+            Some(hash) => {
+                check(pending.take(), &mut previous)?;
+                let zeroed = line.replacen(&format!("{}={}", CHAIN_FIELD, hash), &placeholder(), 1);
+                pending = Some((i + 1, hash, zeroed));
+            }
+            None => match &mut pending {
+                Some((_, _, text)) => {
+                    text.push('\n');
+                    text.push_str(line);
+                }
+                None => return Err(AuditError {line: i + 1, reason: "line is not an audited record"}.into()),
+            },
+        }
+    }
+    check(pending, &mut previous)?;
+    Ok(count)
+}
+
+///the SHA-256 round constants
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+///the SHA-256 digest of [data]
+fn sha256(data: &[u8]) -> [u8; 32] {
+    let mut state: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+    ];
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+    for block in message.chunks(64) {
+        let mut w = [0u32; 64];
+        for (i, word) in block.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h.wrapping_add(s1).wrapping_add(ch).wrapping_add(K[i]).wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+        for (s, v) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *s = s.wrapping_add(v);
+        }
+    }
+    let mut digest = [0; 32];
+    for (out, word) in digest.chunks_mut(4).zip(state) {
+        out.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(data: &[u8]) -> String {
+        sha256(data).iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn sha256_vectors() {
+        assert_eq!(hex(b""), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+        assert_eq!(hex(b"abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(
+            hex(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+        );
+    }
+}
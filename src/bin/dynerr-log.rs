@@ -0,0 +1,202 @@
+//! dynerr-log: inspect log files written by dynerr.
+//!
+//! built with `cargo install dynerr --features cli`.
+
+use dynerr::*;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Seek, SeekFrom, Write};
+use std::time::Duration;
+use std::{env, error, fmt, thread};
+
+const USAGE: &str = "\
+usage: dynerr-log [show|tail|stats] [options] <file>
+
+commands:
+    show                  print the records of a log file (default)
+    tail                  print the last records then follow the file for new ones
+    stats                 print record counts per level and target
+
+options:
+    --level <LEVEL>       only records at LEVEL or more severe
+    --target <TARGET>     only records whose target field is TARGET or below it
+    --field <KEY=VALUE>   only records with that field value
+    --json                print records as JSON lines
+    -n <N>                how many records tail prints before following (default 10)";
+
+///a bad command line
+#[derive(Debug)]
+struct UsageError(String);
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}\n\n{}", self.0, USAGE)
+    }
+}
+
+impl error::Error for UsageError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Command {
+    Show,
+    Tail,
+    Stats,
+}
+
+///parsed command line options
+#[derive(Debug)]
+struct Options {
+    command: Command,
+    level: Option<Level>,
+    target: Option<String>,
+    fields: Vec<(String, String)>,
+    json: bool,
+    count: usize,
+    file: String,
+}
+
+impl Options {
+    ///parses the process arguments
+    fn parse() -> DynResult<Self> {
+        let mut args = env::args().skip(1).peekable();
+        let command = match args.peek().map(String::as_str) {
+            Some("show")    => {args.next(); Command::Show}
+            Some("tail")    => {args.next(); Command::Tail}
+            Some("stats")   => {args.next(); Command::Stats}
+            _               => Command::Show,
+        };
+        let mut options = Options {command, level: None, target: None, fields: Vec::new(), json: false, count: 10, file: String::new()};
+        let value = |args: &mut dyn Iterator<Item = String>, flag: &str| {
+            args.next().ok_or_else(|| UsageError(format!("{} needs a value", flag)))
+        };
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--level"   => options.level = Some(value(&mut args, &arg)?.parse()?),
+                "--target"  => options.target = Some(value(&mut args, &arg)?),
+                "--field"   => {
+                    let field = value(&mut args, &arg)?;
+                    let (k, v) = field.split_once('=').ok_or_else(|| UsageError(format!("invalid field filter: {}", field)))?;
+                    options.fields.push((k.to_string(), v.to_string()));
+                }
+                "--json"    => options.json = true,
+                "-n"        => options.count = value(&mut args, &arg)?.parse()?,
+                "-h" | "--help" => {
+                    println!("{}", USAGE);
+                    std::process::exit(0);
+                }
+                _ if arg.starts_with('-') => dynerr!(UsageError(format!("unknown option: {}", arg))),
+                _ if options.file.is_empty() => options.file = arg,
+                _ => dynerr!(UsageError(format!("unexpected argument: {}", arg))),
+            }
+        }
+        if options.file.is_empty() {dynerr!(UsageError("no log file supplied".to_string()))}
+        Ok(options)
+    }
+
+    ///whether [record] passes every filter
+    fn accepts(&self, record: &LogRecord) -> bool {
+        self.level.is_none_or(|level| record.level <= level)
+        && self.target.as_ref().is_none_or(|target| {
+            record.field("target").is_some_and(|t| t == target || t.starts_with(&format!("{}::", target)))
+        })
+        && self.fields.iter().all(|(k, v)| record.field(k) == Some(v.as_str()))
+    }
+
+    ///prints [record] in the selected format
+    fn print(&self, out: &mut impl Write, record: &LogRecord) -> io::Result<()> {
+        if self.json {writeln!(out, "{}", JsonFormat::new().format(record))}
+        else {writeln!(out, "{}", record)}
+    }
+}
+
+///prints every accepted record
+fn show(options: &Options) -> DynResult<()> {
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    for record in LogReader::open(&options.file)? {
+        let record = record?;
+        if options.accepts(&record) {options.print(&mut out, &record)?}
+    }
+    Ok(())
+}
+
+///prints the last accepted records then polls the file for new ones
+fn tail(options: &Options) -> DynResult<()> {
+    let mut last = std::collections::VecDeque::new();
+    for record in LogReader::open(&options.file)?.flatten() {
+        if options.accepts(&record) {
+            if last.len() == options.count {last.pop_front();}
+            last.push_back(record);
+        }
+    }
+    let stdout = io::stdout();
+    for record in &last {options.print(&mut stdout.lock(), record)?}
+
+    let mut file = BufReader::new(File::open(&options.file)?);
+    let mut position = file.seek(SeekFrom::End(0))?;
+    let mut printing = false;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if file.read_line(&mut line)? == 0 {
+            if file.get_ref().metadata()?.len() < position {
+                file.seek(SeekFrom::Start(0))?;
+                position = 0;
+            }
+            thread::sleep(Duration::from_millis(250));
+            continue
+        }
+        position += line.len() as u64;
+        let text = line.trim_end_matches(&['\r', '\n'][..]);
+        match text.parse::<LogRecord>() {
+            Ok(record) => {
+                printing = options.accepts(&record);
+                if printing {options.print(&mut stdout.lock(), &record)?}
+            }
+            Err(_) if printing && !options.json => writeln!(stdout.lock(), "{}", text)?,
+            Err(_) => (),
+        }
+    }
+}
+
+///prints record counts per level and target
+fn stats(options: &Options) -> DynResult<()> {
+    let mut levels = BTreeMap::new();
+    let mut targets = BTreeMap::new();
+    let (mut total, mut invalid) = (0, 0);
+    let (mut first, mut last) = (None, None);
+    for record in LogReader::open(&options.file)? {
+        let record = match record {
+            Ok(record) => record,
+            Err(_) => {invalid += 1; continue}
+        };
+        if !options.accepts(&record) {continue}
+        total += 1;
+        *levels.entry(record.level).or_insert(0) += 1;
+        *targets.entry(record.field("target").unwrap_or("-").to_string()).or_insert(0) += 1;
+        first.get_or_insert(record.timestamp);
+        last = Some(record.timestamp);
+    }
+    println!("records: {}", total);
+    if invalid > 0 {println!("unparsable lines: {}", invalid)}
+    if let (Some(first), Some(last)) = (first, last) {
+        println!("span: {:?}", last.duration_since(first).unwrap_or_default());
+    }
+    println!("\nby level:");
+    for (level, count) in &levels {println!("    {:<5} {}", level, count)}
+    println!("\nby target:");
+    for (target, count) in &targets {println!("    {} {}", target, count)}
+    Ok(())
+}
+
+fn main() {
+    let result = Options::parse().and_then(|options| match options.command {
+        Command::Show    => show(&options),
+        Command::Tail    => tail(&options),
+        Command::Stats   => stats(&options),
+    });
+    if let Err(e) = result {
+        eprintln!("dynerr-log: {}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,63 @@
+//! Errors that borrow from their inputs and so cant be `'static`.
+//!
+//! `DynErrorRef<'a>` is `DynError` without the `'static` bound. raise errors into it with `dynerr!(ref e)`, and `?` works with it the same way.\
+//! rust can only downcast `'static` errors, so `dynmatch!` cant look for their type.
+//! instead a `Classify` type sorts them, and `dynmatch!` matches on that with `class` blocks.
+//!
+//!# Example
+//! ```rust
+//!# use dynerr::*;
+//!# use std::{error::Error, fmt};
+//! #[derive(Debug)]
+//! struct UnknownKey<'a>(&'a str);
+//! impl fmt::Display for UnknownKey<'_> {
+//!     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+//!         write!(f, "unknown key {}", self.0)
+//!     }
+//! }
+//! impl Error for UnknownKey<'_> {}
+//!
+//! fn lookup(key: &str) -> DynResultRef<'_, u32> {
+//!     match key {
+//!         "width" => Ok(80),
+//!         _ => dynerr!(ref UnknownKey(key)),
+//!     }
+//! }
+//!
+//! #[derive(Debug, PartialEq)]
+//! enum ConfigClass {UnknownKey, Other}
+//! impl Classify for ConfigClass {
+//!     fn classify(e: &(dyn Error + '_)) -> Option<Self> {
+//!         match e.to_string().starts_with("unknown key") {
+//!             true => Some(ConfigClass::UnknownKey),
+//!             false => Some(ConfigClass::Other),
+//!         }
+//!     }
+//! }
+//!# fn main() {
+//! let input = String::from("height");
+//! let e = lookup(&input).unwrap_err();
+//! let fallback = dynmatch!(e,
+//!     class ConfigClass {
+//!         arm ConfigClass::UnknownKey => 0,
+//!         _ => 1
+//!     },
+//!     _ => 2
+//! );
+//! assert_eq!(fallback, 0);
+//!# }
+//! ```
+
+use std::error::Error;
+
+/// `DynError` for errors that borrow data for `'a`.
+pub type DynErrorRef<'a> = Box<dyn Error + 'a>;
+
+/// `DynResult` for errors that borrow data for `'a`.
+pub type DynResultRef<'a, T> = Result<T, DynErrorRef<'a>>;
+
+/// Sorts errors that cant be downcast into a type `dynmatch!` can match on with a `class` block.
+pub trait Classify {
+    ///the class of [e], or `None` to let the next block try
+    fn classify(e: &(dyn Error + '_)) -> Option<Self> where Self: Sized;
+}
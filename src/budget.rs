@@ -0,0 +1,101 @@
+//! Tolerating a limited number of errors before giving up.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::{DynError, Level, LogFiles};
+
+/// How many errors are tolerated within a time window. used by `check_budget!`
+///
+/// errors within the budget are logged at `Level::Warn`. the first one over it does a logged panic.\
+/// errors older than the window no longer count. can be shared between threads.
+#[derive(Debug)]
+pub struct ErrorBudget {
+    limit: usize,
+    window: Duration,
+    errors: Mutex<VecDeque<Instant>>,
+}
+
+impl ErrorBudget {
+    ///tolerates up to [limit] errors within any [window]
+    pub fn new(limit: usize, window: Duration) -> Self {
+        Self {limit, window, errors: Mutex::new(VecDeque::new())}
+    }
+
+    ///how many errors are counted against the budget right now
+    pub fn spent(&self) -> usize {
+        let mut errors = self.errors.lock().unwrap_or_else(|e| e.into_inner());
+        self.expire(&mut errors, Instant::now());
+        errors.len()
+    }
+
+    ///whether the next error would exceed the budget
+    pub fn is_exhausted(&self) -> bool {
+        self.spent() >= self.limit
+    }
+
+    /// Counts [e] against the budget, logging it to [log_file] if the budget allows it and doing a logged panic if not.
+    ///
+    /// not meant to be used on its own. use `check_budget!` instead
+    #[track_caller]
+    pub fn spend<L: LogFiles>(&self, e: DynError, log_file: L) {
+        crate::hook::report(&*e);
+        let now = Instant::now();
+        let spent = {
+            let mut errors = self.errors.lock().unwrap_or_else(|e| e.into_inner());
+            self.expire(&mut errors, now);
+            errors.push_back(now);
+            errors.len()
+        };
+        if spent > self.limit {
+            crate::log_and_panic(format_args!("error budget of {} per {:?} exhausted: {}", self.limit, self.window, e), log_file)
+        }
+        let record = crate::log_display::error_record(Level::Warn, &*e).with_field("budget", format_args!("{}/{}", spent, self.limit));
+        crate::write_record(&record, log_file);
+    }
+
+    ///drops errors that fell out of the window
+    fn expire(&self, errors: &mut VecDeque<Instant>, now: Instant) {
+        while errors.front().is_some_and(|&at| now.duration_since(at) >= self.window) {
+            errors.pop_front();
+        }
+    }
+}
+
+/// Returns `Some` with the value of a result, or logs its error and returns `None` until an `ErrorBudget` runs out.
+///
+/// the error that exhausts the budget does a logged panic instead.\
+/// If no file supplied then defaults to `default_log()` (normally "event.log").
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# use std::time::Duration;
+///# fn main() {
+/// let budget = ErrorBudget::new(2, Duration::from_secs(60));
+/// let rows = ["1", "2", "x", "4", "y"];
+/// let mut total = 0;
+/// for row in rows {
+///     let Some(value) = check_budget!(row.parse::<u32>(), budget, "test.log") else {continue};
+///     total += value;
+/// }
+/// assert_eq!((total, budget.spent()), (7, 2));
+/// assert!(std::panic::catch_unwind(|| check_budget!("z".parse::<u32>(), budget, "test.log")).is_err());
+///# }
+/// ```
+#[macro_export]
+macro_rules! check_budget {
+    ($x:expr, $budget:expr) => {
+        $crate::check_budget!($x, $budget, &$crate::default_log())
+    };
+    ($x:expr, $budget:expr, $log:expr) => {
+        match $x {
+            Ok(v) => Some(v),
+            Err(e) => {
+                $crate::ErrorBudget::spend(&$budget, e.into(), $log);
+                None
+            }
+        }
+    };
+}
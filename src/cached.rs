@@ -0,0 +1,117 @@
+//! Remembering which type an error downcast to, so every `dynmatch!` after the first compares the remembered `TypeId`s instead of asking the error.
+
+use std::any::TypeId;
+use std::cell::Cell;
+use std::error::Error;
+use std::fmt;
+
+use crate::{Attached, DynError, Escalated};
+
+/// An error that remembers the types `dynmatch!` found it and the error it wraps to be.
+///
+/// the first `dynmatch!` over it compares each type block with the error as usual. every later one compares the
+/// remembered `TypeId`s instead, which skips the `is` call on each error for every block before the matching one.
+/// the saving is largest for attached or escalated errors, whose wrappers are compared on every block.\
+/// when the wrapped error wraps another wrapped error, like an attached `Escalated`, only the outer type is remembered
+/// and the errors inside are probed every time.
+/// meant for errors that pass through several layers of `dynmatch!` before being handled.\
+/// displays, debugs and sources exactly like the wrapped error, and `dynmatch!` matches the wrapped error as if it wasnt wrapped.\
+/// wrap an error after attaching values to it. like `Escalated`, it hides the attachments of the error inside from `get_attached`.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# use std::num::ParseIntError;
+/// fn retry(e: &DynError) -> bool {
+///     dynmatch!(e,
+///         type std::io::Error {arm _ => true, _ => true},
+///         _ => false
+///     )
+/// }
+///
+/// fn status(e: &DynError) -> u16 {
+///     dynmatch!(e,
+///         type std::io::Error {arm _ => 503, _ => 503},
+///         type ParseIntError {arm _ => 400, _ => 400},
+///         _ => 500
+///     )
+/// }
+///# fn main() {
+/// let e: DynError = "x".parse::<u32>().unwrap_err().into();
+/// let e: DynError = Box::new(CachedError::new(e.attach(7u32)));
+/// assert!(!retry(&e));
+/// assert_eq!(status(&e), 400); //remembers the error is a ParseIntError
+/// assert_eq!(status(&e), 400); //only downcasts in the ParseIntError block
+/// assert_eq!(e.to_string(), "invalid digit found in string");
+///# }
+/// ```
+pub struct CachedError {
+    error: DynError,
+    outer: Cell<Option<TypeId>>,
+    inner: Cell<Option<TypeId>>,
+}
+
+impl CachedError {
+    ///wraps [error] with an empty cache. the type of a crate provided wrapper is known up front
+    pub fn new(error: DynError) -> Self {
+        let outer = if error.is::<Attached>() {Some(TypeId::of::<Attached>())}
+            else if error.is::<Escalated>() {Some(TypeId::of::<Escalated>())}
+            else {None};
+        Self {error, outer: Cell::new(outer), inner: Cell::new(None)}
+    }
+
+    ///the wrapped error
+    pub fn inner(&self) -> &(dyn Error + 'static) {
+        &*self.error
+    }
+
+    ///unwraps the error, dropping the cache
+    pub fn into_inner(self) -> DynError {
+        self.error
+    }
+
+    ///downcasts [outer], then the errors it wraps with [wrapped], skipping either once it is known to be another type.
+    ///the type of the wrapped errors is only remembered if there is just one of them, so it is [single]
+    pub(crate) fn downcast<'a, T: Error + 'static>(&self, outer: &'a (dyn Error + 'static), wrapped: impl FnOnce() -> Option<&'a T>, single: bool) -> Option<&'a T> {
+        probe(&self.outer, || outer.downcast_ref::<T>()).or_else(|| match single {
+            true => probe(&self.inner, wrapped),
+            false => wrapped(),
+        })
+    }
+
+    ///whether [outer], then the errors it wraps with [wrapped], are a [T], comparing the remembered types instead once they are known.
+    ///the type of the wrapped errors is only remembered if there is just one of them, so it is [single]
+    pub(crate) fn is<T: Error + 'static>(&self, outer: &(dyn Error + 'static), wrapped: impl FnOnce() -> bool, single: bool) -> bool {
+        let known = |id: Option<TypeId>| id.map(|id| id == TypeId::of::<T>());
+        known(self.outer.get()).unwrap_or_else(|| outer.is::<T>()) || match single {
+            true => known(self.inner.get()).unwrap_or_else(wrapped),
+            false => wrapped(),
+        }
+    }
+}
+
+///runs [downcast] unless [known] says the error is another type, remembering its type once found
+fn probe<'a, T: Error + 'static>(known: &Cell<Option<TypeId>>, downcast: impl FnOnce() -> Option<&'a T>) -> Option<&'a T> {
+    if known.get().is_some_and(|id| id != TypeId::of::<T>()) {return None}
+    let found = downcast()?;
+    known.set(Some(TypeId::of::<T>()));
+    Some(found)
+}
+
+impl fmt::Debug for CachedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.error, f)
+    }
+}
+
+impl fmt::Display for CachedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.error, f)
+    }
+}
+
+impl Error for CachedError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.error.source()
+    }
+}
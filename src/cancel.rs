@@ -0,0 +1,119 @@
+//! Cooperative cancellation, returning `errors::Cancelled` from long running work once it is asked to stop.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Implemented by the flags `check_cancel!` can check.
+pub trait Cancellation {
+    ///whether cancelling was requested
+    fn is_cancelled(&self) -> bool;
+}
+
+impl Cancellation for AtomicBool {
+    fn is_cancelled(&self) -> bool {
+        self.load(Ordering::Relaxed)
+    }
+}
+
+impl<C: Cancellation + ?Sized> Cancellation for &C {
+    fn is_cancelled(&self) -> bool {
+        (**self).is_cancelled()
+    }
+}
+
+impl<C: Cancellation + ?Sized> Cancellation for Arc<C> {
+    fn is_cancelled(&self) -> bool {
+        (**self).is_cancelled()
+    }
+}
+
+/// A cancellation flag that can be cloned and shared with the work it cancels.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    ///a token that hasnt been cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///asks the work checking this token, or any clone of it, to stop
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Cancellation for CancelToken {
+    fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Returns `errors::Cancelled` from the function if [token] was cancelled.
+///
+/// [token] can be a `CancelToken`, an `AtomicBool`, or a reference or `Arc` of either.
+/// the error says what was cancelled if given, and "operation" otherwise.\
+/// `Cancelled` is `Severity::Ignorable` and has the kind `cancelled`, so handlers can tell it apart from failures.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+/// fn export(rows: &[u32], token: &CancelToken) -> DynResult<u32> {
+///     let mut sum = 0;
+///     for row in rows {
+///         check_cancel!(token, "export");
+///         sum += row;
+///     }
+///     Ok(sum)
+/// }
+///# fn main() {
+/// let token = CancelToken::new();
+/// assert_eq!(export(&[1, 2, 3], &token).unwrap(), 6);
+/// token.cancel();
+/// let e = export(&[1, 2, 3], &token).unwrap_err();
+/// assert_eq!(e.to_string(), "export was cancelled");
+/// assert_eq!(severity(&*e), Severity::Ignorable);
+/// let shown = dynmatch!(e,
+///     type errors::Cancelled {
+///         arm _ => false,
+///         _ => false
+///     },
+///     _ => true
+/// );
+/// assert!(!shown);
+///# }
+/// ```
+#[macro_export]
+macro_rules! check_cancel {
+    ($token:expr) => {
+        $crate::check_cancel!($token, "operation")
+    };
+    ($token:expr, $what:expr) => {
+        if $crate::Cancellation::is_cancelled(&$token) {
+            return ::std::result::Result::Err(::std::convert::From::from($crate::errors::Cancelled::new($what)))
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DynResult;
+
+    fn step<C: Cancellation>(token: C) -> DynResult<()> {
+        check_cancel!(token, "step");
+        Ok(())
+    }
+
+    #[test]
+    fn every_flag_cancels() {
+        let (token, flag) = (CancelToken::new(), Arc::new(AtomicBool::new(false)));
+        assert!(step(&token).is_ok() && step(flag.clone()).is_ok());
+        token.clone().cancel();
+        flag.store(true, Ordering::Relaxed);
+        for e in [step(&token).unwrap_err(), step(&*flag).unwrap_err(), step(flag).unwrap_err()] {
+            assert_eq!(e.to_string(), "step was cancelled");
+            assert_eq!(crate::error_kind(&*e), Some("cancelled"));
+        }
+    }
+}
@@ -0,0 +1,148 @@
+//! Bug report bundles: a window of log events plus environment info, packed into a tar file.
+
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::record::Timestamp;
+
+///the capture in progress
+struct Active {
+    ///tells the capture apart from the ones it replaced
+    id: u64,
+    until: Instant,
+    lines: Vec<String>,
+}
+
+///the capture in progress, if any
+static ACTIVE: Mutex<Option<Active>> = Mutex::new(None);
+
+///the id of the last capture started
+static LAST_ID: AtomicU64 = AtomicU64::new(0);
+
+///records [line] if a capture is running
+pub(crate) fn observe(line: &str) {
+    let mut active = ACTIVE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(capture) = active.as_mut().filter(|c| Instant::now() < c.until) {
+        capture.lines.push(line.to_string());
+    }
+}
+
+/// Records every logged event for [window] then writes a bug report bundle to [path].
+/// 
+/// the bundle is a tar archive containing `events.log` (every record written during the window)
+/// and `environment.txt` (OS, architecture, dynerr version, arguments and working directory).\
+/// returns immediately. join the returned handle to get the path of the bundle.\
+/// starting a new capture cancels one in progress, whose handle then returns an `Interrupted` error and writes no bundle.
+/// 
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# use std::time::Duration;
+///# fn main() {
+/// let bundle = capture_bundle(Duration::from_millis(100), "report.tar");
+/// log!("something odd happened", "test.log");
+/// let path = bundle.join().unwrap().unwrap();
+///# std::fs::remove_file(path).unwrap();
+///# }
+/// ```
+pub fn capture_bundle<P: Into<PathBuf>>(window: Duration, path: P) -> thread::JoinHandle<io::Result<PathBuf>> {
+    let path = path.into();
+    let id = LAST_ID.fetch_add(1, Ordering::Relaxed) + 1;
+    *ACTIVE.lock().unwrap_or_else(|e| e.into_inner()) = Some(Active {id, until: Instant::now() + window, lines: Vec::new()});
+    thread::spawn(move || {
+        thread::sleep(window);
+        let lines = {
+            let mut active = ACTIVE.lock().unwrap_or_else(|e| e.into_inner());
+            match active.take_if(|c| c.id == id) {
+                Some(capture) => capture.lines,
+                None => return Err(io::Error::new(io::ErrorKind::Interrupted, "Dynerr: capture cancelled by a newer capture or a panic")),
+            }
+        };
+        write_bundle(&path, &lines)?;
+        Ok(path)
+    })
+}
+
+///writes a bundle of the running capture, or of the recent events if nothing is being captured. used by the panic hook
+pub(crate) fn panic_bundle(dir: &Path) -> io::Result<PathBuf> {
+    let lines = ACTIVE.lock().unwrap_or_else(|e| e.into_inner())
+        .take()
+        .map(|c| c.lines)
+        .unwrap_or_else(crate::writer::recent_lines);
+    let stamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    let path = dir.join(format!("crash-{}.tar", stamp));
+    write_bundle(&path, &lines)?;
+    Ok(path)
+}
+
+///describes the running program
+fn environment() -> String {
+    let cwd = std::env::current_dir().map(|d| d.display().to_string()).unwrap_or_else(|e| format!("unknown ({})", e));
+    format!("time: {}\nos: {}\nfamily: {}\narch: {}\ndynerr: {}\nargs: {:?}\ncwd: {}\n",
+        Timestamp(SystemTime::now()),
+        std::env::consts::OS,
+        std::env::consts::FAMILY,
+        std::env::consts::ARCH,
+        env!("CARGO_PKG_VERSION"),
+        std::env::args().collect::<Vec<_>>(),
+        cwd,
+    )
+}
+
+///writes the tar archive
+fn write_bundle(path: &Path, lines: &[String]) -> io::Result<()> {
+    let mut events = lines.join("\n");
+    if !events.is_empty() {events.push('\n')}
+    let mut file = File::create(path)?;
+    write_tar_entry(&mut file, "events.log", events.as_bytes())?;
+    write_tar_entry(&mut file, "environment.txt", environment().as_bytes())?;
+    file.write_all(&[0; 1024])?;
+    file.sync_all()
+}
+
+///writes one ustar file entry
+fn write_tar_entry<W: Write>(out: &mut W, name: &str, data: &[u8]) -> io::Result<()> {
+    let mut header = [0u8; 512];
+    let mtime = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    header[..name.len()].copy_from_slice(name.as_bytes());
+    header[100..108].copy_from_slice(b"0000644\0");
+    header[108..116].copy_from_slice(b"0000000\0");
+    header[116..124].copy_from_slice(b"0000000\0");
+    header[124..136].copy_from_slice(format!("{:011o}\0", data.len()).as_bytes());
+    header[136..148].copy_from_slice(format!("{:011o}\0", mtime).as_bytes());
+    header[148..156].copy_from_slice(b"        ");
+    header[156] = b'0';
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+    let checksum: u32 = header.iter().map(|b| *b as u32).sum();
+    header[148..156].copy_from_slice(format!("{:06o}\0 ", checksum).as_bytes());
+    out.write_all(&header)?;
+    out.write_all(data)?;
+    out.write_all(&vec![0; (512 - data.len() % 512) % 512])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn newer_capture_cancels_older() {
+        let _config = crate::config::TEST_CONFIG.lock().unwrap_or_else(|e| e.into_inner());
+        let dir = std::env::temp_dir();
+        let _ = std::fs::remove_file(dir.join("dynerr_older_capture.tar"));
+        let older = capture_bundle(Duration::from_millis(200), dir.join("dynerr_older_capture.tar"));
+        let newer = capture_bundle(Duration::from_millis(50), dir.join("dynerr_newer_capture.tar"));
+        observe("captured by the newer one");
+        let newer = newer.join().unwrap().unwrap();
+        assert_eq!(older.join().unwrap().unwrap_err().kind(), io::ErrorKind::Interrupted);
+        assert!(!dir.join("dynerr_older_capture.tar").exists());
+        let bundle = std::fs::read(&newer).unwrap();
+        std::fs::remove_file(newer).unwrap();
+        assert!(String::from_utf8_lossy(&bundle).contains("captured by the newer one"));
+    }
+}
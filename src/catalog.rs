@@ -0,0 +1,162 @@
+//! Error messages with stable ids and docs links, defined in one place with `error_catalog!`.
+
+use std::error::Error;
+use std::fmt;
+
+/// A message template with a stable id and an optional docs link. defined with `error_catalog!`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CatalogEntry {
+    pub id: &'static str,
+    pub template: &'static str,
+    pub docs: Option<&'static str>,
+}
+
+impl CatalogEntry {
+    ///an entry with [id] whose messages are made from [template], linking to [docs]
+    pub const fn new(id: &'static str, template: &'static str, docs: Option<&'static str>) -> Self {
+        Self {id, template, docs}
+    }
+
+    ///an error with the template as its message
+    pub fn error(&self) -> CatalogError {
+        CatalogError {entry: *self, message: self.template.to_string()}
+    }
+
+    ///an error with each `{}` in the template replaced by the next of [args]. extra `{}`s are left as they are
+    pub fn format(&self, args: &[&dyn fmt::Display]) -> CatalogError {
+        let mut parts = self.template.split("{}");
+        let mut message = parts.next().unwrap_or_default().to_string();
+        let mut args = args.iter();
+        for part in parts {
+            match args.next() {
+                Some(arg) => message.push_str(&arg.to_string()),
+                None => message.push_str("{}"),
+            }
+            message.push_str(part);
+        }
+        CatalogError {entry: *self, message}
+    }
+}
+
+/// An error made from a `CatalogEntry`. displays like `E0042: invalid save file slot3.sav (see https://..)`.
+///
+/// `error_id()` returns the id of its entry, so it is logged with an `error_id` field like any error with a registered id.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CatalogError {
+    entry: CatalogEntry,
+    message: String,
+}
+
+impl CatalogError {
+    ///the stable id of its entry
+    pub fn id(&self) -> &'static str {
+        self.entry.id
+    }
+
+    ///the docs link of its entry
+    pub fn docs(&self) -> Option<&'static str> {
+        self.entry.docs
+    }
+
+    ///the entry it was made from
+    pub fn entry(&self) -> &CatalogEntry {
+        &self.entry
+    }
+
+    ///the message without the id or docs link
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl PartialEq<CatalogEntry> for CatalogError {
+    ///whether the error was made from [entry]
+    fn eq(&self, entry: &CatalogEntry) -> bool {
+        self.entry.id == entry.id
+    }
+}
+
+impl fmt::Display for CatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.entry.id, self.message)?;
+        match self.entry.docs {
+            Some(docs) => write!(f, " (see {})", docs),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Error for CatalogError {}
+
+/// Defines a catalog of error messages with stable ids and optional docs links, as `CatalogEntry` constants.
+///
+/// `ENTRY.error()` makes an error with the entry's message, and `ENTRY.format(&[&a, &b])` fills its `{}`s in.
+/// the errors are `CatalogError`s, which `dynmatch!` can match by entry and `error_id()` returns the id of.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+/// error_catalog! {
+///     /// the save file couldnt be parsed
+///     pub INVALID_SAVE = "E0042": "invalid save file {}", docs: "https://example.com/errors/E0042";
+///     MISSING_ASSET = "E0043": "missing asset {}";
+/// }
+///
+/// fn load(slot: &str) -> DynResult<()> {
+///     dynerr!(INVALID_SAVE.format(&[&slot]))
+/// }
+///# fn main() {
+/// let e = load("slot3.sav").unwrap_err();
+/// assert_eq!(e.to_string(), "E0042: invalid save file slot3.sav (see https://example.com/errors/E0042)");
+/// assert_eq!(error_id(&*e), Some("E0042"));
+/// let retry = dynmatch!(e,
+///     type CatalogError {
+///         arm c if *c == INVALID_SAVE => false,
+///         _ => true
+///     },
+///     _ => true
+/// );
+/// assert!(!retry);
+/// assert_eq!(MISSING_ASSET.error().to_string(), "E0043: missing asset {}");
+///# }
+/// ```
+#[macro_export]
+macro_rules! error_catalog {
+    ($($(#[$meta:meta])* $vis:vis $name:ident = $id:literal: $template:literal $(, docs: $docs:literal)?;)*) => {
+        $(
+            $(#[$meta])*
+            $vis const $name: $crate::CatalogEntry = $crate::CatalogEntry::new($id, $template, $crate::__catalog_docs!($($docs)?));
+        )*
+    };
+}
+
+///the docs link of an `error_catalog!` entry, if it has one
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __catalog_docs {
+    () => {::std::option::Option::None};
+    ($docs:literal) => {::std::option::Option::Some($docs)};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENTRY: CatalogEntry = CatalogEntry::new("E0001", "{} of {} failed", None);
+
+    #[test]
+    fn fills_placeholders_in_order() {
+        assert_eq!(ENTRY.format(&[&1, &3]).message(), "1 of 3 failed");
+        assert_eq!(ENTRY.format(&[&1]).message(), "1 of {} failed");
+        assert_eq!(ENTRY.format(&[&1, &3, &"extra"]).message(), "1 of 3 failed");
+        assert_eq!(ENTRY.format(&[&"{}", &2]).message(), "{} of 2 failed");
+        assert_eq!(ENTRY.error().to_string(), "E0001: {} of {} failed");
+    }
+
+    #[test]
+    fn id_survives_wrapping() {
+        use crate::Attach;
+        let e: crate::DynError = Box::new(ENTRY.error());
+        assert_eq!(crate::error_id(&*e.attach(5u32)), Some("E0001"));
+    }
+}
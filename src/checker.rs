@@ -0,0 +1,123 @@
+//! The machinery behind `check!`, for building project specific check macros.
+
+use std::fmt;
+
+use crate::{CheckFailure, DynError, Level, Severity};
+
+/// Preset settings for handling failed checks, the building block for custom `check!`-like macros.
+///
+/// every setting left unset behaves like `check!`: failures are logged at `Level::Error` to `default_log()`
+/// and handled as set by `LoggerBuilder::check_failure`.\
+/// the constructor and setters are `const` so a checker can live in a `const` or `static`.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+/// const DB_CHECK: Checker = Checker::new()
+///     .log_file("db.log")
+///     .failure(CheckFailure::Panic);
+///
+/// macro_rules! db_check {
+///     ($x:expr) => {DB_CHECK.check($x)};
+///     ($x:expr, or: $default:expr) => {DB_CHECK.check_or($x, $default)};
+///     ($x:expr, hint: $hint:expr) => {DB_CHECK.check_hint($x, $hint)};
+/// }
+///# fn main() {
+/// let rows: u32 = db_check!("12".parse::<u32>());
+/// let missing = db_check!(Err::<u32, _>(std::io::Error::from(std::io::ErrorKind::NotFound)), or: 0);
+/// assert_eq!(rows + missing, 12);
+/// let result = std::panic::catch_unwind(|| db_check!("x".parse::<u32>(), hint: "rows must be numeric"));
+/// assert!(result.is_err());
+///# clean!("db.log");
+///# }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checker {
+    log_file: Option<&'static str>,
+    level: Level,
+    failure: Option<CheckFailure>,
+}
+
+impl Checker {
+    ///creates a checker that behaves like `check!`
+    pub const fn new() -> Self {
+        Self {log_file: None, level: Level::Error, failure: None}
+    }
+
+    ///logs to [log_file] instead of `default_log()`
+    pub const fn log_file(mut self, log_file: &'static str) -> Self {
+        self.log_file = Some(log_file);
+        self
+    }
+
+    ///logs failures at [level] instead of `Level::Error`
+    pub const fn level(mut self, level: Level) -> Self {
+        self.level = level;
+        self
+    }
+
+    ///handles failures with [failure] instead of the configured `CheckFailure`
+    pub const fn failure(mut self, failure: CheckFailure) -> Self {
+        self.failure = Some(failure);
+        self
+    }
+
+    ///the log file the checker writes to
+    pub fn target(&self) -> String {
+        self.log_file.map_or_else(crate::default_log, str::to_string)
+    }
+
+    ///returns the value of [result] or fails with its error, like `check!(x)`
+    #[track_caller]
+    pub fn check<T, E: Into<DynError>>(&self, result: Result<T, E>) -> T {
+        result.unwrap_or_else(|e| self.fail(e.into(), None))
+    }
+
+    ///returns the value of [result] or fails with its error and [hint], like `check!(x, hint: ..)`
+    #[track_caller]
+    pub fn check_hint<T, E: Into<DynError>>(&self, result: Result<T, E>, hint: &str) -> T {
+        result.unwrap_or_else(|e| self.fail(e.into(), Some(hint)))
+    }
+
+    ///returns the value of [result], or [default] unless its error is `Severity::Fatal`, like `check!(x, or: ..)`
+    #[track_caller]
+    pub fn check_or<T, E: Into<DynError>>(&self, result: Result<T, E>, default: T) -> T {
+        match result {
+            Ok(v) => v,
+            Err(e) => {
+                self.recover(e.into());
+                default
+            }
+        }
+    }
+
+    ///fails on `Severity::Fatal` errors, logs `Severity::Recoverable` ones at `Level::Warn` and ignores the rest
+    #[track_caller]
+    pub fn recover(&self, e: DynError) {
+        match crate::severity(&*e) {
+            Severity::Fatal       => self.fail(e, None),
+            Severity::Recoverable => {
+                crate::hook::report(&*e);
+                crate::dedup::log_at_call_site(&*e, Level::Warn, self.target())
+            }
+            Severity::Ignorable   => crate::hook::report(&*e),
+        }
+    }
+
+    ///logs [event] at [level] to the checker's log file
+    pub fn log<T: fmt::Display>(&self, event: T, level: Level) {
+        crate::log_level(event, level, self.target());
+    }
+
+    ///logs [e] with [hint] (or its `Help`) then panics or exits
+    #[track_caller]
+    pub fn fail(&self, e: DynError, hint: Option<&str>) -> ! {
+        crate::panic::fail_hinted(e, hint, self.level, self.failure, self.target())
+    }
+}
+
+impl Default for Checker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
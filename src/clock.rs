@@ -0,0 +1,67 @@
+//! The source of time used to timestamp records.
+//! 
+//! swap in a `FakeClock` with `Logger::builder().clock(..)` to make timestamps deterministic in tests.
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+/// Tells the logger what time it is.
+pub trait Clock: fmt::Debug + Send + Sync {
+    ///the current time
+    fn now(&self) -> SystemTime;
+}
+
+/// The wall clock. used by default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// A clock that only moves when told to.
+/// 
+/// clones share the same time, so keep one to advance after handing another to the logger.
+/// 
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# use std::time::{Duration, UNIX_EPOCH};
+///# fn main() {
+/// let clock = FakeClock::new(UNIX_EPOCH);
+/// Logger::builder().clock(clock.clone()).init();
+/// clock.advance(Duration::from_millis(1500));
+/// let record = LogRecord::new(Level::Info, "tick");
+/// assert!(record.to_string().starts_with("1970-01-01T00:00:01.500Z"));
+///# }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FakeClock {
+    now: Arc<Mutex<SystemTime>>,
+}
+
+impl FakeClock {
+    ///creates a clock stopped at [start]
+    pub fn new(start: SystemTime) -> Self {
+        Self {now: Arc::new(Mutex::new(start))}
+    }
+
+    ///moves the clock to [time]
+    pub fn set(&self, time: SystemTime) {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner()) = time;
+    }
+
+    ///moves the clock forward by [by]
+    pub fn advance(&self, by: Duration) {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner()) += by;
+    }
+}
+
+impl Clock for FakeClock {
+    fn now(&self) -> SystemTime {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
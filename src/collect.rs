@@ -0,0 +1,148 @@
+//! Collecting failed `check!`s instead of panicking, for jobs that shouldnt stop at one bad item.
+
+use std::cell::RefCell;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::{DynError, LogFiles, LogRecord, MultiError};
+
+thread_local! {
+    ///the errors collected by each `collect_checks!` running on this thread, innermost last
+    static COLLECTORS: RefCell<Vec<MultiError>> = const { RefCell::new(Vec::new()) };
+}
+
+///the payload unwound from a failed check inside `collect_checks!`. never reaches the panic hook
+struct SkippedCheck;
+
+///whether a `collect_checks!` is running on this thread
+pub(crate) fn collecting() -> bool {
+    COLLECTORS.with(|c| !c.borrow().is_empty())
+}
+
+///logs [record], collects [e] into the innermost `collect_checks!` and unwinds to it
+pub(crate) fn skip<L: LogFiles>(record: LogRecord, e: DynError, log_file: L) -> ! {
+    crate::write_record(&record, log_file);
+    COLLECTORS.with(|c| if let Some(errors) = c.borrow_mut().last_mut() {
+        errors.push(e)
+    });
+    panic::resume_unwind(Box::new(SkippedCheck))
+}
+
+/// Runs the steps of a `collect_checks!`, catching the checks that fail in them.
+///
+/// not meant to be used on its own. use `collect_checks!` instead
+#[doc(hidden)]
+pub struct __CheckCollector(());
+
+impl __CheckCollector {
+    ///starts collecting the checks that fail on this thread
+    pub fn start() -> Self {
+        COLLECTORS.with(|c| c.borrow_mut().push(MultiError::new()));
+        Self(())
+    }
+
+    ///runs [step], stopping it at the first check that fails. other panics carry on unwinding
+    pub fn run<R, F: FnOnce() -> R>(&self, step: F) {
+        if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(step)) {
+            if !payload.is::<SkippedCheck>() {
+                panic::resume_unwind(payload)
+            }
+        }
+    }
+
+    ///the errors of every check that failed
+    pub fn finish(self) -> MultiError {
+        let errors = COLLECTORS.with(|c| c.borrow_mut().pop()).unwrap_or_default();
+        //already stopped collecting, so drop mustnt pop again
+        std::mem::forget(self);
+        errors
+    }
+}
+
+impl Drop for __CheckCollector {
+    ///stops collecting if a panic unwound past `finish`
+    fn drop(&mut self) {
+        COLLECTORS.with(|c| c.borrow_mut().pop());
+    }
+}
+
+/// Runs a block or a `for` loop, logging and collecting the checks that fail in it instead of panicking.
+///
+/// in a loop, a failed check skips the rest of that item and moves on to the next.
+/// otherwise it skips the rest of the block.
+/// the macro evaluates to a `MultiError` of every failed check, in order.\
+/// covers `check!`, `check!(x, hint: ..)`, `check!(x; on_err = ..)` and `Checker`, including calls made
+/// from functions called in the block, on the same thread.\
+/// the loop body runs in a closure, so use `return` to skip to the next item. `break` and `continue` arent allowed.
+/// relies on unwinding, so has no effect with `panic = "abort"`.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+/// fn process(order: &str) -> DynResult<u32> {
+///     Ok(order.parse()?)
+/// }
+///# fn main() {
+/// let orders = vec!["12", "x", "7", "", "3"];
+/// let mut total = 0;
+/// let failed = collect_checks! {
+///     for order in &orders {
+///         total += check!(process(order), "test.log");
+///     }
+/// };
+/// assert_eq!(total, 22);
+/// assert_eq!(failed.len(), 2);
+///# }
+/// ```
+#[macro_export]
+macro_rules! collect_checks {
+    (for $pat:pat in $($rest:tt)+) => {
+        $crate::__collect_checks_loop!(($pat) () $($rest)+)
+    };
+    ($($body:tt)*) => {{
+        let __collector = $crate::__CheckCollector::start();
+        __collector.run(|| {$($body)*});
+        __collector.finish()
+    }};
+}
+
+///splits the iterator of a `collect_checks!` loop from its body
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __collect_checks_loop {
+    (($pat:pat) ($($iter:tt)+) $body:block) => {{
+        let __collector = $crate::__CheckCollector::start();
+        for $pat in $($iter)+ {
+            __collector.run(|| $body);
+        }
+        __collector.finish()
+    }};
+    (($pat:pat) ($($iter:tt)*) $next:tt $($rest:tt)+) => {
+        $crate::__collect_checks_loop!(($pat) ($($iter)* $next) $($rest)+)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ///finishes a collector of its own when dropped
+    struct FinishesOnDrop;
+
+    impl Drop for FinishesOnDrop {
+        fn drop(&mut self) {
+            __CheckCollector::start().finish();
+        }
+    }
+
+    #[test]
+    fn finish_while_unwinding_pops_once() {
+        let outer = __CheckCollector::start();
+        let _ = panic::catch_unwind(|| {
+            let _finishes = FinishesOnDrop;
+            panic::resume_unwind(Box::new(()));
+        });
+        assert!(collecting());
+        outer.finish();
+        assert!(!collecting());
+    }
+}
@@ -0,0 +1,771 @@
+//! Global logger configuration.
+//! 
+//! every setting has a sensible default so the logging macros work without any setup.\
+//! use `Logger::builder()` to change them.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock, RwLock, RwLockReadGuard};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use crate::{Clock, Encoding, KindPolicies, Level, LogFiles, LogRecord, Redactor, SystemClock, TextFormat};
+use crate::redact::{AwsKeys, BearerTokens, Emails, Redactors};
+
+/// Controls when log files are fsynced to disk.
+/// 
+/// syncing guarantees a record survives a hard crash at the cost of a slower write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Durability {
+    ///never fsync, leave buffering to the OS. the default
+    Buffered,
+    ///fsync Error level records (including everything written by `logged_panic!`)
+    SyncErrors,
+    ///fsync every record
+    SyncAll,
+}
+
+impl Durability {
+    ///whether a record at [level] should be fsynced
+    pub fn should_sync(&self, level: Level) -> bool {
+        match self {
+            Durability::Buffered    => false,
+            Durability::SyncErrors  => level == Level::Error,
+            Durability::SyncAll     => true,
+        }
+    }
+}
+
+/// The line ending written after every line of a record in log files.
+/// 
+/// the lines of a multi-line message get it too. `LogReader` and `verify_log` read either ending.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineEnding {
+    ///`\n`, the default
+    #[default]
+    Lf,
+    ///`\r\n`, for tools on Windows that dont understand `\n` alone
+    CrLf,
+    ///`CrLf` on Windows and `Lf` everywhere else
+    Native,
+}
+
+impl LineEnding {
+    ///the characters that end a line
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Lf      => "\n",
+            LineEnding::CrLf    => "\r\n",
+            LineEnding::Native  => if cfg!(windows) {"\r\n"} else {"\n"},
+        }
+    }
+
+    ///[text], whose lines end in `\n`, with this line ending instead
+    pub(crate) fn apply<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        match self.as_str() {
+            "\n" => Cow::Borrowed(text),
+            ending => Cow::Owned(text.replace('\n', ending)),
+        }
+    }
+}
+
+/// What happens once a log file reaches the size set by `LoggerBuilder::size_cap`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CapAction {
+    ///write a final record saying the cap was reached, then stop logging altogether, to every file, tee and stderr
+    Stop,
+    ///stop writing to files but keep logging to stderr, subscribers and the `recent_events()` ring buffer,
+    ///so crash dumps still have the latest records
+    RingBufferOnly,
+}
+
+/// What a failed `check!` does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckFailure {
+    ///log the error then panic
+    Panic,
+    ///log the error then exit the process with this code, without unwinding or printing a backtrace
+    Exit(i32),
+}
+
+impl Default for CheckFailure {
+    ///`Exit(1)` in release builds with the `exit-on-check` feature, otherwise `Panic`
+    fn default() -> Self {
+        if cfg!(all(feature = "exit-on-check", not(debug_assertions))) {CheckFailure::Exit(1)}
+        else {CheckFailure::Panic}
+    }
+}
+
+///an extra file that receives copies of selected records
+#[derive(Clone)]
+pub(crate) struct Tee {
+    pub path: String,
+    pub filter: Arc<dyn Fn(&LogRecord) -> bool + Send + Sync>,
+}
+
+impl fmt::Debug for Tee {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Tee").field("path", &self.path).finish_non_exhaustive()
+    }
+}
+
+///the settings used by the logger
+#[derive(Debug, Clone)]
+pub(crate) struct Config {
+    pub durability: Durability,
+    pub text_format: TextFormat,
+    pub line_ending: LineEnding,
+    pub clock: Arc<dyn Clock>,
+    pub tees: Vec<Tee>,
+    pub audited: Vec<String>,
+    pub file_mode: Option<u32>,
+    pub fallbacks: Vec<String>,
+    pub size_cap: Option<(u64, CapAction)>,
+    pub encodings: Vec<(String, Encoding)>,
+    pub redactors: Redactors,
+    pub panic_bundle_dir: Option<PathBuf>,
+    pub crash_marker: Option<PathBuf>,
+    pub crash_log: Option<String>,
+    pub kind_policies: KindPolicies,
+    pub log_dir: PathBuf,
+    pub file_level: Level,
+    pub target_levels: Vec<(String, Level)>,
+    pub call_site_window: Option<Duration>,
+    pub debug_capture: Option<usize>,
+    pub track_errors: bool,
+    pub stderr_level: Option<Level>,
+    pub check_failure: CheckFailure,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            durability: Durability::Buffered,
+            text_format: TextFormat::default(),
+            line_ending: LineEnding::Lf,
+            clock: Arc::new(SystemClock),
+            tees: Vec::new(),
+            audited: Vec::new(),
+            file_mode: None,
+            fallbacks: Vec::new(),
+            size_cap: None,
+            encodings: Vec::new(),
+            redactors: Redactors::default(),
+            panic_bundle_dir: None,
+            crash_marker: None,
+            crash_log: None,
+            kind_policies: KindPolicies::new(),
+            log_dir: PathBuf::from("."),
+            file_level: Level::Trace,
+            target_levels: Vec::new(),
+            call_site_window: None,
+            debug_capture: None,
+            track_errors: false,
+            stderr_level: None,
+            check_failure: CheckFailure::default(),
+        }
+    }
+}
+
+impl Config {
+    ///how records are encoded in [log_file]
+    pub fn encoding_for(&self, log_file: &str) -> Encoding {
+        self.encodings.iter().rev().find(|(path, _)| path == log_file).map_or(Encoding::Text, |(_, encoding)| *encoding)
+    }
+
+    ///the file [record] logged to [log_file] is written to. Error records go to the crash log if one is set
+    pub fn destination<'a>(&'a self, record: &LogRecord, log_file: &'a str) -> &'a str {
+        match &self.crash_log {
+            Some(crash_log) if record.level == Level::Error => crash_log,
+            _ => log_file,
+        }
+    }
+
+    ///the file level of [target], set by the longest `target_level` prefix covering it, or `file_level` if none do
+    pub fn file_level_for(&self, target: Option<&str>) -> Level {
+        let Some(target) = target else {return self.file_level};
+        self.target_levels.iter()
+            .filter(|(prefix, _)| target.strip_prefix(prefix.as_str()).is_some_and(|rest| rest.is_empty() || rest.starts_with("::")))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map_or(self.file_level, |(_, level)| *level)
+    }
+}
+
+///the active configuration
+fn global() -> &'static RwLock<Config> {
+    static CONFIG: OnceLock<RwLock<Config>> = OnceLock::new();
+    CONFIG.get_or_init(|| RwLock::new(Config::default()))
+}
+
+///returns the active configuration
+pub(crate) fn config() -> RwLockReadGuard<'static, Config> {
+    global().read().unwrap_or_else(|e| e.into_inner())
+}
+
+///changes the active configuration in place
+#[cfg_attr(not(all(unix, feature = "signals")), allow(dead_code))]
+pub(crate) fn update<F: FnOnce(&mut Config)>(change: F) {
+    change(&mut global().write().unwrap_or_else(|e| e.into_inner()))
+}
+
+/// Entry point for configuring dynerr's logging.
+/// 
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// Logger::builder()
+///     .durability(Durability::SyncAll)
+///     .init();
+///# }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Logger;
+
+impl Logger {
+    ///starts a builder populated with the default settings
+    pub fn builder() -> LoggerBuilder {
+        LoggerBuilder {config: Config::default()}
+    }
+
+    /// Writes a record built outside the logging macros to `default_log()`, like records forwarded from another logging system.
+    /// 
+    /// the record goes through the same levels, redactors, tees and sinks as any other. its timestamp is kept.
+    /// 
+    ///# Example
+    /// ```rust
+    ///# use dynerr::*;
+    ///# use std::time::{Duration, UNIX_EPOCH};
+    /// const BRIDGE: LogTarget = LogTarget::new("bridge", Some("bridge.log"));
+    ///# fn main() {
+    ///# clean!("bridge.log");
+    /// //a record received from another logger, with its own time and fields
+    /// let record = LogRecord::new(Level::Warn, "disk almost full")
+    ///     .with_timestamp(UNIX_EPOCH + Duration::from_secs(1_700_000_000))
+    ///     .with_field("source", "legacy");
+    /// Logger::emit(record.clone());
+    /// Logger::emit_to(record, BRIDGE);
+    ///# let text = std::fs::read_to_string("bridge.log").unwrap();
+    ///# assert!(text.starts_with("2023-11-14T22:13:20.000Z WARN  {source=legacy} disk almost full"));
+    ///# clean!("bridge.log");
+    ///# }
+    /// ```
+    pub fn emit(record: LogRecord) {
+        crate::write_record(&record, crate::default_log());
+    }
+
+    ///writes a record built outside the logging macros to [log_files], see `Logger::emit`
+    pub fn emit_to<L: LogFiles>(record: LogRecord, log_files: L) {
+        crate::write_record(&record, log_files);
+    }
+
+    /// Appends every record in [records] to [log_files] as one block, so nothing logged by another thread lands between them.
+    /// 
+    /// each file and tee gets the whole block in a single write, except audited logs which are written a record at a time.
+    /// 
+    ///# Example
+    /// ```rust
+    ///# use dynerr::*;
+    ///# fn main() {
+    ///# clean!("dump.log");
+    /// let bytes = [0xde, 0xad, 0xbe, 0xef, 0x00, 0x01, 0x02, 0x03];
+    /// let dump: Vec<LogRecord> = bytes.chunks(4)
+    ///     .enumerate()
+    ///     .map(|(i, row)| LogRecord::new(Level::Debug, format!("{:04x}: {:02x?}", i * 4, row)))
+    ///     .collect();
+    /// Logger::write_batch(&dump, "dump.log");
+    ///# let text = std::fs::read_to_string("dump.log").unwrap();
+    ///# assert!(text.lines().count() == 2 && text.contains("0004: [00, 01, 02, 03]"));
+    ///# clean!("dump.log");
+    ///# }
+    /// ```
+    pub fn write_batch<L: LogFiles>(records: &[LogRecord], log_files: L) {
+        crate::writer::write_batch(records, log_files);
+    }
+}
+
+/// Builds a logger configuration then installs it with `init()`.
+#[derive(Debug, Clone)]
+pub struct LoggerBuilder {
+    config: Config,
+}
+
+impl LoggerBuilder {
+    ///sets when log files are fsynced. defaults to `Durability::Buffered`. use `Durability::SyncErrors` to make error records survive a hard crash
+    pub fn durability(mut self, durability: Durability) -> Self {
+        self.config.durability = durability;
+        self
+    }
+
+    ///sets how records are formatted. defaults to `TextFormat::default()`
+    pub fn text_format(mut self, format: TextFormat) -> Self {
+        self.config.text_format = format;
+        self
+    }
+
+    /// Sets the line ending written to log files. defaults to `LineEnding::Lf`.
+    /// 
+    ///# Example
+    /// ```rust
+    ///# use dynerr::*;
+    ///# fn main() {
+    /// Logger::builder()
+    ///     .line_ending(LineEnding::CrLf)
+    ///     .init();
+    ///# clean!("crlf.log");
+    /// log!("first line\nsecond line", "crlf.log");
+    ///# let text = std::fs::read_to_string("crlf.log").unwrap();
+    ///# assert!(text.ends_with("first line\r\nsecond line\r\n"));
+    ///# let record = LogReader::open("crlf.log").unwrap().next().unwrap().unwrap();
+    ///# assert_eq!(record.message, "first line\nsecond line");
+    ///# clean!("crlf.log");
+    ///# init();
+    ///# }
+    /// ```
+    pub fn line_ending(mut self, ending: LineEnding) -> Self {
+        self.config.line_ending = ending;
+        self
+    }
+
+    ///sets the clock records are timestamped with. defaults to `SystemClock`
+    pub fn clock<C: Clock + 'static>(mut self, clock: C) -> Self {
+        self.config.clock = Arc::new(clock);
+        self
+    }
+
+    /// Also writes every record accepted by [filter] to [path].
+    /// 
+    /// can be called more than once to tee into several files.\
+    /// a tee that cant be written to is skipped and listed by `failed_over_logs`, without failing the log file the record went to.
+    /// 
+    ///# Example
+    /// ```rust
+    ///# use dynerr::*;
+    ///# fn main() {
+    /// Logger::builder()
+    ///     .tee("audit.log", |record| record.level <= Level::Warn)
+    ///     .init();
+    /// log_warn!("refund issued", "test.log"); //also written to audit.log
+    ///# }
+    /// ```
+    pub fn tee<F: Fn(&LogRecord) -> bool + Send + Sync + 'static>(mut self, path: &str, filter: F) -> Self {
+        self.config.tees.push(Tee {path: path.to_string(), filter: Arc::new(filter)});
+        self
+    }
+
+    /// Makes [path] a tamper evident audit log.
+    /// 
+    /// every record written to it, directly or through a tee, gets a `chain` field holding the SHA-256 hash of the record
+    /// and the hash of the record before it. `verify_log` checks the chain.\
+    /// a log that already exists continues its chain, even across runs.
+    /// 
+    ///# Example
+    /// ```rust
+    ///# use dynerr::*;
+    ///# fn main() {
+    /// Logger::builder()
+    ///     .tee("sales.log", |record| record.level <= Level::Info)
+    ///     .audit("sales.log")
+    ///     .init();
+    /// log_info!("sale 1043 total 4.50", "test.log"); //also chained into sales.log
+    /// assert!(verify_log("sales.log").is_ok());
+    ///# clean!("sales.log");
+    ///# init();
+    ///# }
+    /// ```
+    pub fn audit(mut self, path: &str) -> Self {
+        self.config.audited.push(path.to_string());
+        self
+    }
+
+    /// Creates log files with the unix permission bits [mode] and refuses to write to existing ones with looser permissions.
+    /// 
+    /// writing to a log file that grants anything outside [mode] fails with `io::ErrorKind::PermissionDenied`
+    /// instead of leaking its contents. has no effect on other platforms.
+    /// 
+    ///# Example
+    /// ```rust
+    ///# use dynerr::*;
+    ///# fn main() {
+    ///# clean!("private.log");
+    /// Logger::builder().file_mode(0o600).init();
+    /// log!("opened /home/me/secrets.db", "private.log");
+    ///# #[cfg(unix)] {
+    ///# use std::os::unix::fs::PermissionsExt;
+    ///# let mode = std::fs::metadata("private.log").unwrap().permissions().mode();
+    ///# assert_eq!(mode & 0o777, 0o600);
+    ///# std::fs::set_permissions("private.log", std::fs::Permissions::from_mode(0o644)).unwrap();
+    ///# log!("leaked", "private.log");
+    ///# assert!(!std::fs::read_to_string("private.log").unwrap().contains("leaked"));
+    ///# }
+    ///# clean!("private.log");
+    ///# init();
+    ///# }
+    /// ```
+    pub fn file_mode(mut self, mode: u32) -> Self {
+        self.config.file_mode = Some(mode);
+        self
+    }
+
+    /// Encodes the records written to the log file or tee at [path] with [encoding]. defaults to `Encoding::Text`.
+    /// 
+    /// audited logs are always written as text, since their hash chain is over the text.
+    /// 
+    ///# Example
+    /// ```rust
+    ///# use dynerr::*;
+    ///# fn main() {
+    ///# clean!(["ingest.logfmt", "ingest.csv"]);
+    /// Logger::builder()
+    ///     .encoding("ingest.logfmt", Encoding::Logfmt)
+    ///     .tee("ingest.csv", |_| true)
+    ///     .encoding("ingest.csv", Encoding::Csv)
+    ///     .init();
+    /// log_warn!("queue backed up", "ingest.logfmt");
+    ///# let logfmt = std::fs::read_to_string("ingest.logfmt").unwrap();
+    ///# assert!(logfmt.contains(r#"level=warn msg="queue backed up""#));
+    ///# let csv = std::fs::read_to_string("ingest.csv").unwrap();
+    ///# assert!(csv.starts_with("timestamp,level,message,fields,thread\n") && csv.contains(",WARN,queue backed up,"));
+    ///# clean!(["ingest.logfmt", "ingest.csv"]);
+    ///# init();
+    ///# }
+    /// ```
+    pub fn encoding(mut self, path: &str, encoding: Encoding) -> Self {
+        self.config.encodings.push((path.to_string(), encoding));
+        self
+    }
+
+    /// Writes records to [path] when the log file they were meant for cant be written to, like when the disk fills up.
+    /// 
+    /// fallbacks are tried in the order they were added, then stderr.
+    /// the first record to fail over from a log file is preceded by an error record saying why, and once the
+    /// log file can be written to again a record saying so is written to it. `failed_over_logs()` lists the log
+    /// files currently failed over. defaults to falling back to stderr alone.
+    /// 
+    ///# Example
+    /// ```rust
+    ///# use dynerr::*;
+    ///# fn main() {
+    ///# clean!("fallback.log");
+    /// Logger::builder()
+    ///     .fallback("fallback.log")
+    ///     .init();
+    /// log!("order 7 shipped", "missing_dir/orders.log"); //the directory doesnt exist
+    /// assert_eq!(failed_over_logs(), vec!["missing_dir/orders.log".to_string()]);
+    ///# let text = std::fs::read_to_string("fallback.log").unwrap();
+    ///# assert!(text.contains("couldnt write to missing_dir/orders.log") && text.contains("order 7 shipped"));
+    ///# clean!("fallback.log");
+    ///# init();
+    ///# }
+    /// ```
+    pub fn fallback(mut self, path: &str) -> Self {
+        self.config.fallbacks.push(path.to_string());
+        self
+    }
+
+    /// Stops writing to a log file once it would grow past [bytes], then does what [action] says.
+    /// 
+    /// applies to every log file, tee and fallback, and counts what they already held, so a file already past [bytes] isnt grown at all.\
+    /// calling `init()` again forgets which files reached the cap and starts logging again.
+    /// meant as a last line of defence against a runaway loop filling the disk, like on kiosk hardware.
+    /// `capped_logs()` lists the log files that reached it. defaults to no cap.
+    /// 
+    ///# Example
+    /// ```rust
+    ///# use dynerr::*;
+    ///# fn main() {
+    ///# clean!("capped.log");
+    /// Logger::builder()
+    ///     .size_cap(4096, CapAction::Stop)
+    ///     .init();
+    /// for i in 0.. {
+    ///     log!(format!("frame {} dropped", i), "capped.log");
+    ///     if !capped_logs().is_empty() {break}
+    /// }
+    /// log!("never written", "capped.log");
+    ///# let text = std::fs::read_to_string("capped.log").unwrap();
+    ///# assert!(text.lines().last().unwrap().contains("reached its size cap of 4096 bytes"));
+    ///# assert!(text.len() < 4096 + 256 && !text.contains("never written"));
+    ///# assert!(!recent_events().contains(&"never written".to_string()));
+    ///# clean!("capped.log");
+    ///# }
+    /// ```
+    pub fn size_cap(mut self, bytes: u64, action: CapAction) -> Self {
+        self.config.size_cap = Some((bytes, action));
+        self
+    }
+
+    /// Runs [redactor] over the message and field values of every record before it is written anywhere.
+    /// 
+    /// can be called more than once, redactors run in the order they were added. see the `redact` module.\
+    /// the messages of `logged_panic!`, failed `check!`s and the crash marker are redacted too.
+    pub fn redactor<R: Redactor + 'static>(mut self, redactor: R) -> Self {
+        self.config.redactors.0.push(Arc::new(redactor));
+        self
+    }
+
+    ///redacts email addresses, bearer tokens and AWS keys with the redactors in the `redact` module
+    pub fn redact_secrets(self) -> Self {
+        self.redactor(Emails).redactor(BearerTokens).redactor(AwsKeys)
+    }
+
+    ///makes the panic hook write a `capture_bundle` style bug report into [dir] on every panic
+    pub fn bundle_on_panic<P: Into<PathBuf>>(mut self, dir: P) -> Self {
+        self.config.panic_bundle_dir = Some(dir.into());
+        self
+    }
+
+    ///writes a marker to [path] when the program panics or fails a `check!`, for `previous_crash` to find on the next run
+    pub fn crash_marker<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.config.crash_marker = Some(path.into());
+        self
+    }
+
+    /// Writes every `Level::Error` record, including logged panics and failed checks, to [path] instead of the file it was logged to.
+    /// 
+    /// keeps a small file with only the fatal records while routine records stay in "event.log".
+    /// tees still get their copies. use a `tee` instead to keep the errors in both files.
+    /// 
+    ///# Example
+    /// ```rust
+    ///# use dynerr::*;
+    ///# fn main() {
+    ///# clean!("crash.log");
+    /// Logger::builder()
+    ///     .crash_log("crash.log")
+    ///     .init();
+    /// log!("loaded 3 saves", "test.log");
+    /// log_error!("save 2 is corrupt", "test.log"); //written to crash.log only
+    /// let crashes = std::fs::read_to_string("crash.log").unwrap();
+    /// assert!(crashes.contains("save 2 is corrupt"));
+    /// assert!(!crashes.contains("loaded 3 saves"));
+    ///# clean!("crash.log");
+    ///# init();
+    ///# }
+    /// ```
+    pub fn crash_log(mut self, path: &str) -> Self {
+        self.config.crash_log = Some(path.to_string());
+        self
+    }
+
+    /// Sets what happens to each registered error kind. defaults to no policies.
+    /// 
+    ///# Example
+    /// ```rust,no_run
+    ///# use dynerr::*;
+    ///# fn main() -> DynResult<()> {
+    /// Logger::builder()
+    ///     .kind_policies(KindPolicies::load("errors.toml")?)
+    ///     .init();
+    ///# Ok(())
+    ///# }
+    /// ```
+    pub fn kind_policies(mut self, policies: KindPolicies) -> Self {
+        self.config.kind_policies = policies;
+        self
+    }
+
+    /// Only writes records at [level] or more severe to log files. defaults to `Level::Trace`, writing everything.
+    /// 
+    /// tees keep their own filters.
+    /// 
+    ///# Example
+    /// ```rust
+    ///# use dynerr::*;
+    ///# fn main() {
+    /// Logger::builder()
+    ///     .file_level(Level::Debug)
+    ///     .stderr_level(Level::Warn)
+    ///     .init();
+    ///# clean!("levels.log");
+    /// log_trace!("not written anywhere", "levels.log");
+    /// log_debug!("only written to levels.log", "levels.log");
+    /// log_warn!("written to levels.log and stderr", "levels.log");
+    ///# let text = std::fs::read_to_string("levels.log").unwrap();
+    ///# assert!(!text.contains("anywhere") && text.lines().count() == 2);
+    ///# clean!("levels.log");
+    ///# init();
+    ///# }
+    /// ```
+    pub fn file_level(mut self, level: Level) -> Self {
+        self.config.file_level = level;
+        self
+    }
+
+    /// Only writes records at [level] or more severe to the `LogTarget`s named [target] or nested under it.
+    /// 
+    /// target names nest with `::`, so `app::net` also covers `app::net::tcp`. the longest matching name wins,
+    /// and targets no name covers use `file_level`. only applies to records logged to a single target.
+    /// 
+    ///# Example
+    /// ```rust
+    ///# use dynerr::*;
+    /// const TCP: LogTarget = LogTarget::new("app::net::tcp", Some("tcp.log"));
+    /// const HTTP: LogTarget = LogTarget::new("app::net::http", Some("http.log"));
+    ///# fn main() {
+    /// Logger::builder()
+    ///     .file_level(Level::Warn)
+    ///     .target_level("app::net", Level::Debug)
+    ///     .target_level("app::net::http", Level::Info)
+    ///     .init();
+    ///# clean!([TCP, HTTP]);
+    /// log_debug!("sent 12 bytes", TCP);         //written, app::net allows Debug
+    /// log_debug!("GET /index.html", HTTP);      //dropped, app::net::http only allows Info
+    /// log_debug!("cache warmed", "test.log");   //dropped, file_level only allows Warn
+    ///# assert!(std::fs::read_to_string("tcp.log").unwrap().contains("sent 12 bytes"));
+    ///# assert!(!std::path::Path::new("http.log").exists());
+    ///# clean!([TCP, HTTP]);
+    ///# init();
+    ///# }
+    /// ```
+    pub fn target_level(mut self, target: &str, level: Level) -> Self {
+        self.config.target_levels.push((target.to_string(), level));
+        self
+    }
+
+    /// Collapses errors a `check!` recovers from into one record per [window] for each place they are checked.
+    /// 
+    /// covers `check!(x, or: ..)`, the `log` policy of `check!` and `Checker::recover`. errors from the same
+    /// file and line within [window] of the last one written are dropped, however different their messages.
+    /// the next record written from there has a `suppressed` field counting them. defaults to logging every error.
+    /// 
+    ///# Example
+    /// ```rust
+    ///# use dynerr::*;
+    ///# use std::time::Duration;
+    ///# fn main() {
+    /// Logger::builder()
+    ///     .dedup_call_sites(Duration::from_secs(60))
+    ///     .init();
+    ///# clean!("dedup.log");
+    /// for attempt in 0..5 {
+    ///     let e = format!("connection refused on attempt {}", attempt);
+    ///     check!(Err::<(), _>(e), "dedup.log", or: ());
+    /// }
+    ///# let text = std::fs::read_to_string("dedup.log").unwrap();
+    ///# assert_eq!(text.lines().count(), 1);
+    ///# clean!("dedup.log");
+    ///# init();
+    ///# }
+    /// ```
+    pub fn dedup_call_sites(mut self, window: Duration) -> Self {
+        self.config.call_site_window = Some(window);
+        self
+    }
+
+    /// Adds the Debug text of logged errors to their records as a `debug` field, cut to [max_len] bytes.
+    /// 
+    /// the Display of errors from other crates often leaves out the paths and codes needed to diagnose them.
+    /// covers the errors logged as a `DynError`: failed and recovered `check!`s, `check!` policies, `Checker`,
+    /// `check_budget!` and `#[logged]`. the field is left out when the Debug text is the same as the Display text,
+    /// and for errors with a registered `LogDisplay`, whose Debug text could show what `fmt_log` masks.
+    /// defaults to off.
+    /// 
+    ///# Example
+    /// ```rust
+    ///# use dynerr::*;
+    ///# fn main() {
+    /// Logger::builder()
+    ///     .capture_debug(200)
+    ///     .init();
+    ///# clean!("debug.log");
+    /// let e = std::fs::File::open("missing.toml").unwrap_err();
+    /// check!(Err::<(), _>(e), "debug.log", or: ()); //WARN  {debug="Os { code: 2, kind: NotFound, .. }"} No such file or directory (os error 2)
+    ///# let text = std::fs::read_to_string("debug.log").unwrap();
+    ///# assert!(text.contains("debug=\"Os { code: 2"));
+    ///# clean!("debug.log");
+    ///# init();
+    ///# }
+    /// ```
+    pub fn capture_debug(mut self, max_len: usize) -> Self {
+        self.config.debug_capture = Some(max_len);
+        self
+    }
+
+    ///makes debug builds keep statistics on every error raised, for `error_stats` to report
+    pub fn track_errors(mut self) -> Self {
+        self.config.track_errors = true;
+        self
+    }
+
+    ///also prints records at [level] or more severe to stderr. defaults to printing nothing
+    pub fn stderr_level(mut self, level: Level) -> Self {
+        self.config.stderr_level = Some(level);
+        self
+    }
+
+    /// Sets what a failed `check!` does. defaults to `CheckFailure::default()`.
+    /// 
+    ///# Example
+    /// ```rust
+    ///# use dynerr::*;
+    ///# fn main() {
+    /// Logger::builder()
+    ///     .check_failure(if cfg!(debug_assertions) {CheckFailure::Panic} else {CheckFailure::Exit(2)})
+    ///     .init();
+    ///# }
+    /// ```
+    pub fn check_failure(mut self, failure: CheckFailure) -> Self {
+        self.config.check_failure = failure;
+        self
+    }
+
+    ///sets the directory `clean_matching!` is confined to. defaults to the working directory
+    pub fn log_dir<P: Into<PathBuf>>(mut self, dir: P) -> Self {
+        self.config.log_dir = dir.into();
+        self
+    }
+
+    /// Installs the configuration globally, replacing any previous one.
+    /// 
+    /// the first call also replays the first 256 records logged before it into the configured tees and stderr,
+    /// after a warning saying how many were logged with the default configuration.
+    /// 
+    ///# Example
+    /// ```rust
+    ///# use dynerr::*;
+    ///# fn main() {
+    ///# clean!(["early.log", "errors.log"]);
+    /// log_error!("plugin failed to load", "early.log");  //logged with the defaults, there are no tees yet
+    /// assert!(!is_initialized());
+    /// Logger::builder()
+    ///     .tee("errors.log", |record| record.level == Level::Error)
+    ///     .init();
+    /// assert!(is_initialized());
+    ///# let text = std::fs::read_to_string("errors.log").unwrap();
+    ///# assert!(text.contains("plugin failed to load"));
+    ///# clean!(["early.log", "errors.log"]);
+    ///# }
+    /// ```
+    pub fn init(self) {
+        crate::error_stats::set_tracking(self.config.track_errors);
+        crate::writer::reset_cap();
+        *global().write().unwrap_or_else(|e| e.into_inner()) = self.config;
+        crate::deferred::flush();
+        if !INITIALIZED.swap(true, Ordering::SeqCst) {
+            crate::writer::replay_pre_init();
+        }
+    }
+}
+
+///held by unit tests that change the global configuration or watch what is logged, so they run one at a time
+#[cfg(test)]
+pub(crate) static TEST_CONFIG: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+///set by the first `init()`
+static INITIALIZED: AtomicBool = AtomicBool::new(false);
+
+/// Whether `init()` or `LoggerBuilder::init()` has been called.
+/// 
+/// until then every setting has its default.
+pub fn is_initialized() -> bool {
+    INITIALIZED.load(Ordering::SeqCst)
+}
+
+/// Installs the default logger configuration.
+/// 
+/// shortcut for `Logger::builder().init()`.
+pub fn init() {
+    Logger::builder().init()
+}
@@ -0,0 +1,133 @@
+//! Checks that an error type implements `Display`, `Debug` and `source()` the way error reporters expect.
+
+use std::error::Error;
+
+///how deep a source chain can get before it is assumed to loop
+const MAX_DEPTH: usize = 64;
+
+/// Returns every way [e] breaks the conventions error reporters rely on. used by `error_conformance!`
+///
+/// the conventions checked are:
+/// - Display and Debug arent empty, and Display doesnt end in a newline
+/// - no error in the source chain displays the same as its source, which reporters would print twice.
+///   a wrapper that displays as its inner error should be transparent and return the inner error's source
+/// - the source chain ends
+pub fn conformance_problems(e: &(dyn Error + 'static)) -> Vec<String> {
+    let mut problems = Vec::new();
+    let mut current = Some(e);
+    let mut depth = 0;
+    while let Some(e) = current {
+        let display = e.to_string();
+        if display.is_empty() {
+            problems.push(format!("error {} in the chain has an empty Display", depth));
+        }
+        if display.ends_with('\n') {
+            problems.push(format!("the Display of error {} in the chain ends in a newline", depth));
+        }
+        if format!("{:?}", e).is_empty() {
+            problems.push(format!("error {} in the chain has an empty Debug", depth));
+        }
+        current = e.source();
+        if let Some(source) = current {
+            if source.to_string() == display {
+                problems.push(format!("error {} in the chain displays the same as its source: {}", depth, display));
+            }
+        }
+        depth += 1;
+        if depth > MAX_DEPTH && current.is_some() {
+            problems.push(format!("the source chain is more than {} errors deep, it probably loops", MAX_DEPTH));
+            break
+        }
+    }
+    problems
+}
+
+/// Asserts that an error follows the conventions listed on `conformance_problems`, panicking with every problem found.
+///
+/// meant for tests of your own error types, so chains dont get cut short or printed twice by reporters.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# use std::{error, fmt};
+/// #[derive(Debug)]
+/// struct LoadFailed(std::io::Error);
+/// impl fmt::Display for LoadFailed {
+///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///         write!(f, "couldnt load the save")
+///     }
+/// }
+/// impl error::Error for LoadFailed {
+///     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+///         Some(&self.0)
+///     }
+/// }
+///# fn main() {
+/// error_conformance!(LoadFailed(std::io::Error::from(std::io::ErrorKind::NotFound)));
+/// error_conformance!(*DynError::from("bad header").attach(42u32)); //dereference a DynError to check the error inside
+///# }
+/// ```
+#[macro_export]
+macro_rules! error_conformance {
+    ($e:expr) => {{
+        let problems = $crate::conformance_problems(&$e);
+        assert!(problems.is_empty(), "Dynerr: error doesnt conform:\n  {}", problems.join("\n  "));
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+    use std::io;
+
+    ///an error for wrappers to wrap, with a source of its own
+    fn chained() -> DynError {
+        Box::new(DetachedError::new(&vec![io::Error::from(io::ErrorKind::NotFound)].into_iter().collect::<MultiError>()))
+    }
+
+    #[test]
+    fn crate_errors_conform() {
+        let panic = std::thread::spawn(|| panic!("boom")).join().unwrap_err();
+        let errors: Vec<DynError> = vec![
+            chained().attach(1u32),
+            chained(),
+            Box::new(PanicError::new(panic, Some("worker"))),
+            Box::new(vec!["first", "second"].into_iter().collect::<MultiError>()),
+            Box::new(AssertionError {expression: "a == b".into(), left: Some("1".into()), right: Some("2".into()), message: None, file: "x.rs", line: 1}),
+            Box::new(AuditError {line: 3, reason: "record does not match its hash"}),
+            Box::new("x".parse::<LogRecord>().unwrap_err()),
+            Box::new("x".parse::<Level>().unwrap_err()),
+            Box::new("= 1".parse::<KindPolicies>().unwrap_err()),
+            Box::new(errors::NotFound::new("save")),
+            Box::new(errors::Timeout::new("connect", std::time::Duration::from_secs(1))),
+            Box::new(errors::InvalidInput::new("port", "too big")),
+            Box::new(errors::Unsupported::new("ipv6")),
+            Box::new(errors::Unavailable::new("database")),
+            Box::new(errors::Cancelled::new("export")),
+            Box::new(CachedError::new(chained().attach(2u32))),
+            Box::new(Escalated::new(errors::Unavailable::new("database"), &errors::Timeout::new("connect", std::time::Duration::from_secs(1)))),
+            Box::new(CatalogEntry::new("E0001", "invalid save file {}", Some("https://example.com")).format(&[&"slot1"])),
+        ];
+        for e in errors {
+            error_conformance!(*e);
+        }
+    }
+
+    #[test]
+    fn finds_repeated_text() {
+        #[derive(Debug)]
+        struct Repeats(io::Error);
+        impl std::fmt::Display for Repeats {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                std::fmt::Display::fmt(&self.0, f)
+            }
+        }
+        impl Error for Repeats {
+            fn source(&self) -> Option<&(dyn Error + 'static)> {
+                Some(&self.0)
+            }
+        }
+        assert_eq!(conformance_problems(&Repeats(io::Error::other("disk full"))).len(), 1);
+    }
+}
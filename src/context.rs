@@ -0,0 +1,165 @@
+//! Carrying a thread's scopes and log redirect over to the threads and tasks it starts.
+
+use std::io;
+use std::thread::JoinHandle;
+
+use crate::{RedirectGuard, ScopeGuard};
+
+/// The logging context of a thread: its active `scope!`s and its `redirect_log` file.
+///
+/// both are per thread, so work moved to another thread loses them unless the context is captured and entered there.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LogContext {
+    scopes: Vec<String>,
+    log_file: Option<String>,
+}
+
+impl LogContext {
+    ///the context of the current thread
+    pub fn capture() -> Self {
+        Self {scopes: crate::current_scopes(), log_file: crate::redirect::redirected_log()}
+    }
+
+    ///the captured scopes, outermost first
+    pub fn scopes(&self) -> &[String] {
+        &self.scopes
+    }
+
+    ///the captured redirect, if the thread was redirected
+    pub fn log_file(&self) -> Option<&str> {
+        self.log_file.as_deref()
+    }
+
+    ///re-establishes the context on the current thread, on top of its own, until the guard is dropped
+    #[must_use = "the context ends as soon as the guard is dropped"]
+    pub fn enter(&self) -> ContextGuard {
+        ContextGuard {
+            _scopes: self.scopes.iter().map(|s| crate::enter_scope(s.clone())).collect(),
+            _redirect: self.log_file.as_deref().map(crate::redirect_log),
+        }
+    }
+}
+
+/// Ends a context entered with `LogContext::enter` when dropped.
+#[derive(Debug)]
+pub struct ContextGuard {
+    _scopes: Vec<ScopeGuard>,
+    _redirect: Option<RedirectGuard>,
+}
+
+/// Spawns a thread named [name] that runs [f] with the logging context of the current thread.
+///
+/// the child starts inside the same `scope!`s and logs to the same `redirect_log` file, so records and panics
+/// logged by it carry the trail of the work that started it.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// let _request = scope!("request 7f3a");
+/// let _redirect = redirect_log("requests.log");
+/// let worker = spawn_logged("resize", || (current_scopes(), default_log())).unwrap();
+/// let (scopes, log_file) = worker.join().unwrap();
+/// assert_eq!(scopes, vec!["request 7f3a"]);
+/// assert_eq!(log_file, "requests.log");
+///# }
+/// ```
+pub fn spawn_logged<T, F>(name: &str, f: F) -> io::Result<JoinHandle<T>>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let context = LogContext::capture();
+    std::thread::Builder::new().name(name.to_string()).spawn(move || {
+        let _context = context.enter();
+        f()
+    })
+}
+
+#[cfg(feature = "async")]
+pub use self::task::{logged_task, LoggedTask};
+
+#[cfg(feature = "async")]
+mod task {
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use super::LogContext;
+
+    /// A future that is polled inside a captured `LogContext`. made by `logged_task`
+    pub struct LoggedTask<F> {
+        future: Pin<Box<F>>,
+        context: LogContext,
+    }
+
+    impl<F: Future> Future for LoggedTask<F> {
+        type Output = F::Output;
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+            let _context = self.context.enter();
+            self.future.as_mut().poll(cx)
+        }
+    }
+
+    /// Wraps [future] so every poll runs with the logging context of the current thread, for handing to an executor's spawn.
+    ///
+    /// the context is entered for each poll, so it follows the task to whichever thread polls it.
+    ///
+    ///# Example
+    /// ```rust
+    ///# use dynerr::*;
+    ///# use std::future::Future;
+    ///# use std::sync::Arc;
+    ///# use std::task::{Context, Poll, Wake, Waker};
+    ///# struct Noop;
+    ///# impl Wake for Noop {fn wake(self: Arc<Self>) {}}
+    ///# fn main() {
+    /// let task = {
+    ///     let _request = scope!("request 7f3a");
+    ///     logged_task(async {current_scopes()})
+    /// };
+    /// let polled = std::thread::spawn(move || {
+    ///     let mut task = Box::pin(task);
+    ///     let waker = Waker::from(Arc::new(Noop));
+    ///     match task.as_mut().poll(&mut Context::from_waker(&waker)) {
+    ///         Poll::Ready(scopes) => scopes,
+    ///         Poll::Pending => unreachable!(),
+    ///     }
+    /// }).join().unwrap();
+    /// assert_eq!(polled, vec!["request 7f3a"]);
+    ///# }
+    /// ```
+    pub fn logged_task<F: Future>(future: F) -> LoggedTask<F> {
+        LoggedTask {future: Box::pin(future), context: LogContext::capture()}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn context_ends_with_its_guard() {
+        let _outer = crate::enter_scope("outer".to_string());
+        let context = LogContext {scopes: vec!["captured".to_string()], log_file: Some("context.log".to_string())};
+        {
+            let _context = context.enter();
+            assert_eq!(crate::current_scopes(), ["outer", "captured"]);
+            assert_eq!(crate::default_log(), "context.log");
+        }
+        assert_eq!(crate::current_scopes(), ["outer"]);
+        assert_ne!(crate::default_log(), "context.log");
+    }
+
+    #[test]
+    fn child_doesnt_change_parent() {
+        let _outer = crate::enter_scope("parent".to_string());
+        let child = spawn_logged("child", || {
+            let _inner = crate::enter_scope("child".to_string());
+            crate::current_scopes()
+        }).unwrap();
+        assert_eq!(child.join().unwrap(), ["parent", "child"]);
+        assert_eq!(crate::current_scopes(), ["parent"]);
+    }
+}
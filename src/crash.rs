@@ -0,0 +1,94 @@
+//! A marker file left behind by a run that panicked, so the next run can tell.
+
+use std::fs::{read_to_string, remove_file, write};
+use std::sync::OnceLock;
+use std::time::SystemTime;
+
+use crate::{LogReader, LogRecord};
+
+/// What is known about a run that crashed. returned by `previous_crash`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrashInfo {
+    ///when the crash happened
+    pub timestamp: SystemTime,
+    ///the panic or check failure message
+    pub message: String,
+    ///the file, line and column of the panic, if known
+    pub location: Option<String>,
+    ///the name of the thread that panicked, if it had one
+    pub thread: Option<String>,
+    ///the process id of the crashed run
+    pub pid: Option<u32>,
+}
+
+impl From<LogRecord> for CrashInfo {
+    fn from(record: LogRecord) -> Self {
+        Self {
+            timestamp: record.timestamp,
+            location: record.field("location").map(str::to_string),
+            pid: record.field("pid").and_then(|pid| pid.parse().ok()),
+            thread: record.thread,
+            message: record.message,
+        }
+    }
+}
+
+///writes the crash marker for [record] if `LoggerBuilder::crash_marker` was set
+pub(crate) fn mark(record: &LogRecord) {
+    let config = crate::config::config();
+    if let Some(path) = config.crash_marker.clone() {
+        let record = config.redactors.apply(record).into_owned().with_field("pid", std::process::id());
+        drop(config);
+        let text = crate::TextFormat::new().thread(true).format(&record);
+        let _ = write(path, text + "\n");
+    }
+}
+
+/// Returns the crash left behind by the previous run, if it crashed.
+///
+/// the marker is written by `logged_panic!`, failed `check!`s and panics seen by `install_panic_hook`,
+/// once `LoggerBuilder::crash_marker` sets where it goes.\
+/// the first call reads and deletes the marker, so a crash is only reported to one run. later calls return the same answer.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// Logger::builder().crash_marker("app.crash").init();
+/// if let Some(crash) = previous_crash() {
+///     println!("the last run crashed: {}", crash.message); //offer to restore the autosave
+/// }
+///# let _ = std::panic::catch_unwind(|| logged_panic!("out of memory", "test.log"));
+///# assert!(std::path::Path::new("app.crash").exists());
+///# std::fs::remove_file("app.crash").unwrap();
+///# init();
+///# }
+/// ```
+pub fn previous_crash() -> Option<CrashInfo> {
+    static PREVIOUS: OnceLock<Option<CrashInfo>> = OnceLock::new();
+    PREVIOUS.get_or_init(|| {
+        let path = crate::config::config().crash_marker.clone()?;
+        let text = read_to_string(&path).ok()?;
+        let _ = remove_file(&path);
+        LogReader::new(text.as_bytes()).next()?.ok().map(CrashInfo::from)
+    }).clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Level;
+
+    #[test]
+    fn marker_round_trip() {
+        let record = LogRecord::new(Level::Error, "save was empty\nsecond line")
+            .with_field("location", "src/main.rs:4:5")
+            .with_field("pid", 42);
+        let text = crate::TextFormat::new().thread(true).format(&record);
+        let crash = CrashInfo::from(LogReader::new(text.as_bytes()).next().unwrap().unwrap());
+        assert_eq!(crash.message, "save was empty\nsecond line");
+        assert_eq!(crash.location.as_deref(), Some("src/main.rs:4:5"));
+        assert_eq!(crash.pid, Some(42));
+        assert_eq!(crash.thread, record.thread);
+    }
+}
@@ -0,0 +1,100 @@
+//! Warns about operations that succeed but take too long.
+
+use std::time::Duration;
+
+use crate::{Level, LogFiles, LogRecord};
+
+/// Logs a `Level::Warn` record for an operation at [location] that took [elapsed] against a [deadline].
+///
+/// not meant to be used on its own. use `deadline_check!` instead
+pub fn deadline_exceeded<L: LogFiles>(elapsed: Duration, deadline: Duration, location: &str, log_file: L) {
+    let record = LogRecord::new(Level::Warn, format_args!("deadline exceeded at {}: took {:?} (deadline {:?})", location, elapsed, deadline))
+        .with_field("elapsed", format_args!("{:?}", elapsed))
+        .with_field("deadline", format_args!("{:?}", deadline))
+        .with_field("location", location);
+    crate::writer::write_record(&record, log_file);
+}
+
+///parses a duration literal like `500ms` at compile time
+#[doc(hidden)]
+pub const fn __parse_duration(text: &str) -> Duration {
+    let bytes = text.as_bytes();
+    let mut value: u64 = 0;
+    let mut i = 0;
+    while i < bytes.len() && bytes[i].is_ascii_digit() {
+        value = value * 10 + (bytes[i] - b'0') as u64;
+        i += 1;
+    }
+    if i == 0 {panic!("Dynerr: deadline must start with a number, like 500ms")}
+    let unit = bytes.len() - i;
+    match (unit, if unit > 0 {bytes[i]} else {0}, if unit > 1 {bytes[i + 1]} else {0}) {
+        (2, b'n', b's') => Duration::from_nanos(value),
+        (2, b'u', b's') => Duration::from_micros(value),
+        (2, b'm', b's') => Duration::from_millis(value),
+        (1, b's', _) => Duration::from_secs(value),
+        (1, b'm', _) => Duration::from_secs(value * 60),
+        _ => panic!("Dynerr: deadline unit must be one of ns, us, ms, s or m"),
+    }
+}
+
+/// Runs an expression and logs a warning if it took longer than a deadline, then returns its value.
+///
+/// the deadline is either a literal with a unit (`ns`, `us`, `ms`, `s` or `m`), checked at compile time, or a `Duration`.\
+/// the warning is a `Level::Warn` record with `elapsed`, `deadline` and `location` fields. it is logged whether the operation failed or not.\
+/// If no file supplied then defaults to `default_log()` (normally "event.log").
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# use std::time::Duration;
+/// fn load() -> DynResult<u32> {
+///     std::thread::sleep(Duration::from_millis(20));
+///     Ok(5)
+/// }
+///# fn main() -> DynResult<()> {
+///# clean!("slow.log");
+/// let value = deadline_check!(load(), 5ms, "slow.log")?;
+/// assert_eq!(value, 5);
+/// let fast = deadline_check!(Ok::<_, DynError>(6), Duration::from_secs(1), "slow.log")?;
+/// assert_eq!(fast, 6);
+/// let records: Vec<_> = LogReader::open("slow.log")?.collect::<DynResult<_>>()?;
+/// assert_eq!(records.len(), 1);
+/// assert_eq!(records[0].level, Level::Warn);
+///# clean!("slow.log");
+///# Ok(())
+///# }
+/// ```
+///
+/// an unknown unit is a compile error:
+/// ```rust,compile_fail
+///# use dynerr::*;
+///# fn main() {
+/// let value = deadline_check!(5, 2h);
+///# }
+/// ```
+#[macro_export]
+macro_rules! deadline_check {
+    (@run $x:expr, $deadline:expr, $log:expr) => {{
+        let deadline: ::std::time::Duration = $deadline;
+        let started = ::std::time::Instant::now();
+        let result = $x;
+        let elapsed = started.elapsed();
+        if elapsed > deadline {
+            $crate::deadline_exceeded(elapsed, deadline, concat!(file!(), ":", line!()), $log);
+        }
+        result
+    }};
+    ($x:expr, $deadline:literal) => {
+        $crate::deadline_check!($x, $deadline, &$crate::default_log())
+    };
+    ($x:expr, $deadline:expr) => {
+        $crate::deadline_check!($x, $deadline, &$crate::default_log())
+    };
+    ($x:expr, $deadline:literal, $log:expr) => {{
+        const DEADLINE: ::std::time::Duration = $crate::__parse_duration(stringify!($deadline));
+        $crate::deadline_check!(@run $x, DEADLINE, $log)
+    }};
+    ($x:expr, $deadline:expr, $log:expr) => {
+        $crate::deadline_check!(@run $x, $deadline, $log)
+    };
+}
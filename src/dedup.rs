@@ -0,0 +1,42 @@
+//! Collapsing repeated errors from the same `check!`.
+
+use std::collections::BTreeMap;
+use std::collections::btree_map::Entry;
+use std::error::Error;
+use std::panic::Location;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::{Level, LogFiles};
+
+///when each call site last wrote a record, and how many it has dropped since
+static SITES: Mutex<BTreeMap<&'static Location<'static>, (Instant, u64)>> = Mutex::new(BTreeMap::new());
+
+///logs [e] at [level] unless the caller already logged within `LoggerBuilder::dedup_call_sites`.
+///the first record after a quiet window carries how many were dropped in a `suppressed` field
+#[track_caller]
+pub(crate) fn log_at_call_site<L: LogFiles>(e: &(dyn Error + 'static), level: Level, log_file: L) {
+    crate::display_hint::check(e, &log_file);
+    let record = || crate::log_display::error_record(level, e);
+    let window = crate::config::config().call_site_window;
+    let Some(window) = window else {return crate::write_record(&record(), log_file)};
+    let now = Instant::now();
+    let suppressed = {
+        let mut sites = SITES.lock().unwrap_or_else(|e| e.into_inner());
+        match sites.entry(Location::caller()) {
+            Entry::Vacant(site) => *site.insert((now, 0)),
+            Entry::Occupied(mut site) => {
+                let (since, dropped) = site.get_mut();
+                if now.duration_since(*since) < window {
+                    *dropped += 1;
+                    return
+                }
+                site.insert((now, 0))
+            }
+        }.1
+    };
+    match suppressed {
+        0 => crate::write_record(&record(), log_file),
+        n => crate::write_record(&record().with_field("suppressed", n), log_file),
+    }
+}
@@ -0,0 +1,116 @@
+//! Records queued from contexts that cant log yet, like code that runs before `main`.
+
+use std::ptr;
+use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::{Level, LogRecord};
+
+/// A record written by `log_deferred!`. not meant to be used on its own
+///
+/// each `log_deferred!` owns one in a static, so queueing never allocates or locks.
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct Deferred {
+    level: Level,
+    message: &'static str,
+    log_file: Option<&'static str>,
+    ///milliseconds since the unix epoch when it was queued
+    queued_at: AtomicU64,
+    queued: AtomicBool,
+    next: AtomicPtr<Deferred>,
+}
+
+///the most recently queued record. each points at the one queued before it
+static QUEUE: AtomicPtr<Deferred> = AtomicPtr::new(ptr::null_mut());
+
+impl Deferred {
+    ///a record of [message] at [level] for [log_file], or for `default_log()` when flushed if None
+    pub const fn new(level: Level, message: &'static str, log_file: Option<&'static str>) -> Self {
+        Self {
+            level,
+            message,
+            log_file,
+            queued_at: AtomicU64::new(0),
+            queued: AtomicBool::new(false),
+            next: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    /// Queues the record until the next record is logged normally or `init()` is called.
+    ///
+    /// a record that is already queued isnt queued again.\
+    /// not meant to be used on its own. use `log_deferred!` instead
+    pub fn queue(&'static self) {
+        if self.queued.swap(true, Ordering::AcqRel) {return}
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        self.queued_at.store(now.as_millis() as u64, Ordering::Relaxed);
+        let node = self as *const Deferred as *mut Deferred;
+        let mut head = QUEUE.load(Ordering::Acquire);
+        loop {
+            self.next.store(head, Ordering::Relaxed);
+            match QUEUE.compare_exchange_weak(head, node, Ordering::AcqRel, Ordering::Acquire) {
+                Ok(_) => return,
+                Err(current) => head = current,
+            }
+        }
+    }
+}
+
+///writes every queued record, oldest first
+pub(crate) fn flush() {
+    if QUEUE.load(Ordering::Acquire).is_null() {return}
+    let mut node = QUEUE.swap(ptr::null_mut(), Ordering::AcqRel);
+    let mut queued = Vec::new();
+    while !node.is_null() {
+        //nodes only ever point at statics, and a node isnt queued again until it was taken off the list below
+        let deferred: &'static Deferred = unsafe {&*node};
+        node = deferred.next.load(Ordering::Relaxed);
+        queued.push(deferred);
+    }
+    for deferred in queued.into_iter().rev() {
+        let mut record = LogRecord::new(deferred.level, deferred.message);
+        record.timestamp = UNIX_EPOCH + Duration::from_millis(deferred.queued_at.load(Ordering::Relaxed));
+        deferred.queued.store(false, Ordering::Release);
+        match deferred.log_file {
+            Some(log_file) => crate::write_record(&record, log_file),
+            None => crate::write_record(&record, crate::default_log()),
+        }
+    }
+}
+
+/// Queues a message to be logged later, for code that runs before logging can, like constructors run before `main`.
+///
+/// the message must be a string literal. queueing doesnt allocate or take any locks.
+/// queued messages are written, with the time they were queued, the next time anything is logged or `init()` is called.\
+/// logs at `Level::Info` unless a level is given after the file.
+/// If no file supplied then defaults to `default_log()` (normally "event.log") when written.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+/// fn register_plugin() {
+///     //runs from a constructor before main, before the logger is configured
+///     log_deferred!("plugin registered", "test.log");
+///     log_deferred!("plugin has no config", "test.log", Level::Warn);
+/// }
+///# fn main() {
+/// register_plugin();
+/// Logger::builder().init(); //writes both records
+///# assert!(recent_events().contains(&"plugin has no config".to_string()));
+///# }
+/// ```
+#[macro_export]
+macro_rules! log_deferred {
+    ($message:literal) => {{
+        static DEFERRED: $crate::Deferred = $crate::Deferred::new($crate::Level::Info, $message, None);
+        DEFERRED.queue()
+    }};
+    ($message:literal, $log:literal) => {
+        $crate::log_deferred!($message, $log, $crate::Level::Info)
+    };
+    ($message:literal, $log:literal, $level:expr) => {{
+        static DEFERRED: $crate::Deferred = $crate::Deferred::new($level, $message, Some($log));
+        DEFERRED.queue()
+    }};
+}
@@ -0,0 +1,89 @@
+//! Line by line diffs of Debug output for logging mismatched state.
+
+use std::fmt;
+
+use crate::{Level, LogFiles, LogRecord};
+
+/// Renders a line by line diff from [expected] to [actual].
+///
+/// unchanged lines start with two spaces, lines only in [expected] with `- ` and lines only in [actual] with `+ `.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// assert_eq!(diff_lines("a\nb\nc", "a\nx\nc"), "  a\n- b\n+ x\n  c");
+///# }
+/// ```
+pub fn diff_lines(expected: &str, actual: &str) -> String {
+    let (old, new): (Vec<&str>, Vec<&str>) = (expected.lines().collect(), actual.lines().collect());
+    //common[i][j] is the length of the longest common subsequence of old[i..] and new[j..]
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = match old[i] == new[j] {
+                true => common[i + 1][j + 1] + 1,
+                false => common[i + 1][j].max(common[i][j + 1]),
+            };
+        }
+    }
+    let mut lines = Vec::with_capacity(old.len().max(new.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            lines.push(format!("  {}", old[i]));
+            i += 1;
+            j += 1;
+        } else if j == new.len() || (i < old.len() && common[i + 1][j] >= common[i][j + 1]) {
+            lines.push(format!("- {}", old[i]));
+            i += 1;
+        } else {
+            lines.push(format!("+ {}", new[j]));
+            j += 1;
+        }
+    }
+    lines.join("\n")
+}
+
+/// Logs a `Level::Warn` diff of the pretty Debug output of [expected] and [actual] if they differ, returning whether they did.
+///
+/// not meant to be used on its own. use `log_diff!` instead
+pub fn log_diff<E: fmt::Debug, A: fmt::Debug, L: LogFiles>(expected: &E, actual: &A, label: &str, log_file: L) -> bool {
+    let (expected, actual) = (format!("{:#?}", expected), format!("{:#?}", actual));
+    if expected == actual {return false}
+    let record = LogRecord::new(Level::Warn, format_args!("{} differ:\n{}", label, diff_lines(&expected, &actual)));
+    crate::writer::write_record(&record, log_file);
+    true
+}
+
+/// Logs a line by line diff of the Debug output of two values if they differ.
+///
+/// the values are compared by their pretty printed (`{:#?}`) Debug output, so they only need to implement Debug.\
+/// lines only in [expected] start with `- `, lines only in [actual] with `+ `. returns whether the values differed.\
+/// If no file supplied then defaults to `default_log()` (normally "event.log").
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+/// #[derive(Debug)]
+/// struct Order {id: u32, items: Vec<&'static str>, paid: bool}
+///# fn main() {
+///# clean!("diff.log");
+/// let expected = Order {id: 7, items: vec!["tea", "cake"], paid: true};
+/// let actual = Order {id: 7, items: vec!["tea", "scone"], paid: true};
+/// assert!(log_diff!(expected, actual, "diff.log"));
+/// let text = std::fs::read_to_string("diff.log").unwrap();
+/// assert!(text.contains("`expected` and `actual` differ"));
+/// assert!(text.contains("-         \"cake\",\n+         \"scone\","));
+///# clean!("diff.log");
+///# }
+/// ```
+#[macro_export]
+macro_rules! log_diff {
+    ($expected:expr, $actual:expr) => {
+        $crate::log_diff!($expected, $actual, &$crate::default_log())
+    };
+    ($expected:expr, $actual:expr, $log:expr) => {
+        $crate::log_diff(&$expected, &$actual, concat!("`", stringify!($expected), "` and `", stringify!($actual), "`"), $log)
+    };
+}
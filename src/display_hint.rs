@@ -0,0 +1,56 @@
+//! Spotting error types whose `Display` leaves out what their `Debug` knows. debug builds only.
+
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::sync::Mutex;
+
+use crate::{Level, LogFiles, LogRecord};
+
+///the types already hinted about, by the name their Debug output starts with
+static HINTED: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+///the type name a Debug output starts with, like `ParseError` in `ParseError { line: 3 }`
+fn debug_name(debug: &str) -> &str {
+    let end = debug.find(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':')).unwrap_or(debug.len());
+    &debug[..end]
+}
+
+///whether [display] looks like it lost information [debug] has: debug has fields, is at least twice as long,
+///and fewer than half of the words in it appear in display
+fn loses_information(display: &str, debug: &str) -> bool {
+    if !debug.contains(['{', '(']) || debug.len() < display.len() * 2 + 16 {return false}
+    let display = display.to_lowercase();
+    let words: Vec<String> = debug[debug_name(debug).len()..]
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.len() > 1)
+        .map(str::to_lowercase)
+        .collect();
+    let shown = words.iter().filter(|w| display.contains(w.as_str())).count();
+    !words.is_empty() && shown * 2 < words.len()
+}
+
+///in debug builds, logs a hint to [log_file] the first time an error type whose Display loses information is logged
+pub(crate) fn check<L: LogFiles>(e: &(dyn Error + 'static), log_file: L) {
+    if !cfg!(debug_assertions) {return}
+    let display = e.to_string();
+    let debug = format!("{:?}", e);
+    if !loses_information(&display, &debug) {return}
+    let name = debug_name(&debug);
+    if name.is_empty() || !HINTED.lock().unwrap_or_else(|e| e.into_inner()).insert(name.to_string()) {return}
+    let message = format!("Dynerr: hint: the Display of {} ({:?}) leaves out most of its Debug ({}). \
+        consider adding those details to its Display", name, display, debug);
+    crate::write_record(&LogRecord::new(Level::Info, message).with_field("type", name), log_file);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spots_lossy_display() {
+        assert!(loses_information("parse failed", "ParseError { line: 31, column: 7, expected: \"number\", found: \"word\" }"));
+        assert!(!loses_information("expected number at 31:7, found word", "ParseError { line: 31, column: 7, expected: \"number\", found: \"word\" }"));
+        assert!(!loses_information("file not found", "NotFound"));
+        assert_eq!(debug_name("io::Error { kind: NotFound }"), "io::Error");
+    }
+}
@@ -0,0 +1,91 @@
+//! Comparing errors, which cant implement `PartialEq` behind `dyn Error`.
+
+use std::error::Error;
+
+use crate::__DowncastThrough;
+
+///how deep two source chains are compared before they are assumed to loop
+const MAX_DEPTH: usize = 64;
+
+///the type or variant name [e] starts its Debug text with, like `ParseIntError` or `NotFound`.
+///a guess at the type, two types with the same name or a hand written Debug can fool it
+fn debug_name(e: &(dyn Error + 'static)) -> String {
+    let debug = format!("{:?}", e);
+    let end = debug.find(['(', '{', ' ']).unwrap_or(debug.len());
+    debug[..end].to_string()
+}
+
+/// Returns whether two errors are probably the same kind of error with the same text.
+///
+/// the concrete type of a `dyn Error` cant be read on stable Rust, so the name its Debug text starts with stands in for it.
+/// for a derived Debug that is the type name, or the variant name for an enum.
+/// this is fuzzy: types sharing a name, or Debug impls that dont start with one, can compare equal when they arent.
+/// use `errors_equal_as` when the type is known.
+/// crate provided wrappers like `Attached` debug and display as the error they wrap, so they compare equal to it.\
+/// sources arent compared. use `chains_equal` to compare them too.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// let a: DynError = "x".parse::<u32>().unwrap_err().into();
+/// let b: DynError = "y".parse::<u32>().unwrap_err().into();
+/// let c: DynError = "".parse::<u32>().unwrap_err().into();
+/// assert!(errors_equal(&*a, &*b.attach(5u32)));
+/// assert!(!errors_equal(&*a, &*c)); //"cannot parse integer from empty string"
+/// assert!(!errors_equal(&*a, &*DynError::from("invalid digit found in string")));
+///# }
+/// ```
+pub fn errors_equal(a: &(dyn Error + 'static), b: &(dyn Error + 'static)) -> bool {
+    a.to_string() == b.to_string() && debug_name(a) == debug_name(b)
+}
+
+/// Returns whether two errors are both a [T], seen through crate provided wrappers like `Attached`, and equal as one.
+///
+/// an exact comparison for when the type is known, unlike `errors_equal`.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+/// use std::num::ParseIntError;
+///# fn main() {
+/// let a: DynError = "x".parse::<u32>().unwrap_err().into();
+/// let b: DynError = "y".parse::<u8>().unwrap_err().into();
+/// assert!(errors_equal_as::<ParseIntError>(&*a, &*b.attach(5u32)));
+/// assert!(!errors_equal_as::<ParseIntError>(&*a, &*DynError::from("invalid digit found in string")));
+///# }
+/// ```
+pub fn errors_equal_as<T: Error + PartialEq + 'static>(a: &(dyn Error + 'static), b: &(dyn Error + 'static)) -> bool {
+    match (a.__downcast_through::<T>(), b.__downcast_through::<T>()) {
+        (Some(a), Some(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Returns whether two errors and every error in their source chains are pairwise `errors_equal`.
+///
+/// chains of different lengths are never equal.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// let missing = || std::io::Error::new(std::io::ErrorKind::NotFound, "save.dat");
+/// let a = Escalated::new(errors::NotFound::new("save"), &missing());
+/// let b = Escalated::new(errors::NotFound::new("save"), &missing());
+/// let c = Escalated::new(errors::NotFound::new("save"), &errors::Unavailable::new("disk"));
+/// assert!(chains_equal(&a, &b));
+/// assert!(errors_equal(&a, &c) && !chains_equal(&a, &c));
+///# }
+/// ```
+pub fn chains_equal(a: &(dyn Error + 'static), b: &(dyn Error + 'static)) -> bool {
+    let (mut a, mut b) = (Some(a), Some(b));
+    for _ in 0..=MAX_DEPTH {
+        match (a, b) {
+            (Some(x), Some(y)) if errors_equal(x, y) => (a, b) = (x.source(), y.source()),
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+    true
+}
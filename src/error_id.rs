@@ -0,0 +1,82 @@
+//! Stable names for error types.
+//!
+//! `std::any::type_name` can change between compiler versions, so anything that identifies errors
+//! across builds, like log analytics, should use the id registered with `register_error_id!` instead.
+
+use std::error::Error;
+
+use crate::LogRecord;
+use crate::registry::Registry;
+
+///a downcasting check for one registered type and its id
+type Identifier = (fn(&(dyn Error + 'static)) -> bool, &'static str);
+
+///every registered type
+static IDS: Registry<Identifier> = Registry::new();
+
+///whether [e] is or wraps a [T]
+fn is<T: Error + 'static>(e: &(dyn Error + 'static)) -> bool {
+    use crate::__DowncastThrough;
+    e.__downcast_through::<T>().is_some()
+}
+
+/// Registers [id] as the stable id of every error of type [T].
+///
+/// registering a type again replaces its id.\
+/// not meant to be used on its own. use `register_error_id!` instead
+pub fn register_error_id<T: Error + 'static>(id: &'static str) {
+    IDS.register::<T>((is::<T>, id));
+}
+
+/// Returns the id registered for the type of [e], or its id if it is a `CatalogError`.
+pub fn error_id(e: &(dyn Error + 'static)) -> Option<&'static str> {
+    use crate::__DowncastThrough;
+    IDS.find_map(|(check, id)| check(e).then_some(*id))
+        .or_else(|| e.__downcast_through::<crate::CatalogError>().map(crate::CatalogError::id))
+}
+
+///[record] with an `error_id` field if [e] has a registered id
+pub(crate) fn with_error_id(record: LogRecord, e: &(dyn Error + 'static)) -> LogRecord {
+    match error_id(e) {
+        Some(id) => record.with_field("error_id", id),
+        None => record,
+    }
+}
+
+/// Assigns stable string ids to error types.
+///
+/// errors with an id are logged with an `error_id` field when they fail a `check!`, are recovered from
+/// by `check!`, `Checker` or `check_budget!`, or pass through a `#[logged]` function. `error_id()` looks up the id of any error.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# use std::{error, fmt};
+/// #[derive(Debug)]
+/// struct QuotaExceeded;
+///# impl fmt::Display for QuotaExceeded {
+///#     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///#         write!(f, "quota exceeded")
+///#     }
+///# }
+///# impl error::Error for QuotaExceeded {}
+///# fn main() {
+/// register_error_id!(
+///     QuotaExceeded = "app.quota_exceeded",
+///     std::num::ParseIntError = "app.bad_number",
+/// );
+///# clean!("ids.log");
+/// let e: DynError = Box::new(QuotaExceeded);
+/// assert_eq!(error_id(&*e), Some("app.quota_exceeded"));
+/// check_recover(e, "ids.log");
+///# let text = std::fs::read_to_string("ids.log").unwrap();
+///# assert!(text.contains("error_id=app.quota_exceeded"));
+///# clean!("ids.log");
+///# }
+/// ```
+#[macro_export]
+macro_rules! register_error_id {
+    ($($ty:ty = $id:literal),+ $(,)?) => {
+        $($crate::register_error_id::<$ty>($id);)+
+    };
+}
@@ -0,0 +1,161 @@
+//! Opt-in statistics on the errors a debug build raises, for finding error wrapping loops and oversized errors.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+///how deep a source chain is followed before it is assumed to loop
+const MAX_DEPTH: usize = 64;
+
+///the statistics gathered so far
+#[derive(Default)]
+struct Tracked {
+    total: u64,
+    max_depth: usize,
+    deepest: Option<&'static str>,
+    types: HashMap<&'static str, (u64, usize)>,
+}
+
+///every error tracked since the last reset
+static TRACKED: Mutex<Option<Tracked>> = Mutex::new(None);
+
+///mirrors `LoggerBuilder::track_errors`, so raising an error doesnt have to read the configuration
+static TRACKING: AtomicBool = AtomicBool::new(false);
+
+///turns tracking on or off. called by `init()`
+pub(crate) fn set_tracking(tracking: bool) {
+    TRACKING.store(tracking, Ordering::Relaxed);
+}
+
+///records the error of type [name], whose type is [size] bytes, that was just raised as [e].
+///does nothing unless `LoggerBuilder::track_errors` was set in a debug build
+pub(crate) fn track(name: &'static str, size: usize, e: &(dyn Error + 'static)) {
+    if !cfg!(debug_assertions) || !TRACKING.load(Ordering::Relaxed) {return}
+    let depth = chain_depth(e);
+    let mut tracked = TRACKED.lock().unwrap_or_else(|e| e.into_inner());
+    let tracked = tracked.get_or_insert_with(Tracked::default);
+    tracked.total += 1;
+    if depth > tracked.max_depth {
+        tracked.max_depth = depth;
+        tracked.deepest = Some(name);
+    }
+    let entry = tracked.types.entry(name).or_insert((0, 0));
+    entry.0 += 1;
+    entry.1 = entry.1.max(size);
+}
+
+///the number of errors in the source chain of [e], counting [e]
+fn chain_depth(e: &(dyn Error + 'static)) -> usize {
+    let mut depth = 1;
+    let mut current = e.source();
+    while let Some(e) = current {
+        if depth > MAX_DEPTH {break}
+        depth += 1;
+        current = e.source();
+    }
+    depth
+}
+
+/// How often one error type was raised and how big it is. part of `ErrorStats`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeStats {
+    ///the type name
+    pub name: &'static str,
+    ///how many were raised
+    pub count: u64,
+    ///the size of the type in bytes, as `size_of` gives it. heap data owned by the error, like its strings, isnt counted
+    pub type_size: usize,
+}
+
+/// Statistics on every error raised with `dynerr!` or wrapped with `attach` since tracking started. made by `error_stats`
+///
+/// a chain deeper than 64 errors is reported as 65 deep, and usually means errors are being wrapped in a loop.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ErrorStats {
+    ///how many errors were tracked
+    pub total: u64,
+    ///the deepest source chain seen, counting the outer error
+    pub max_depth: usize,
+    ///the type of the outer error of the deepest chain
+    pub deepest: Option<&'static str>,
+    ///every type raised, most frequent first
+    pub types: Vec<TypeStats>,
+}
+
+impl ErrorStats {
+    ///the [n] most frequently raised types
+    pub fn most_frequent(&self, n: usize) -> &[TypeStats] {
+        &self.types[..n.min(self.types.len())]
+    }
+
+    ///the [n] types with the largest `type_size`, largest first
+    pub fn largest(&self, n: usize) -> Vec<&TypeStats> {
+        let mut types: Vec<&TypeStats> = self.types.iter().collect();
+        types.sort_by(|a, b| b.type_size.cmp(&a.type_size).then(a.name.cmp(b.name)));
+        types.truncate(n);
+        types
+    }
+}
+
+impl fmt::Display for ErrorStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} errors tracked, deepest chain {}", self.total, self.max_depth)?;
+        if let Some(deepest) = self.deepest {
+            write!(f, " ({})", deepest)?;
+        }
+        for stats in self.most_frequent(5) {
+            write!(f, "\n  {} x{}, {} bytes", stats.name, stats.count, stats.type_size)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns statistics on the errors raised since `LoggerBuilder::track_errors` was set or `reset_error_stats` was called.
+///
+/// errors are tracked when raised with `dynerr!` and when wrapped with `attach`, and only in debug builds.
+/// tracking walks the source chain of every error, so leave it off outside of debugging.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+/// fn parse(text: &str) -> DynResult<u32> {
+///     match text.parse() {
+///         Ok(n) => Ok(n),
+///         Err(e) => dynerr!(e),
+///     }
+/// }
+///# fn main() {
+/// Logger::builder()
+///     .track_errors()
+///     .init();
+/// for text in ["x", "y", "1"] {
+///     let _ = parse(text);
+/// }
+/// let _ = parse("z").map_err(|e| e.attach(7u32));
+/// let stats = error_stats();
+/// assert_eq!(stats.total, 4);
+/// assert_eq!(stats.max_depth, 1);
+/// assert!(stats.most_frequent(1)[0].name.ends_with("ParseIntError")); //the full path depends on the compiler
+/// assert_eq!(stats.most_frequent(1)[0].count, 3);
+/// log_debug!(&stats, "test.log");
+/// reset_error_stats();
+/// assert_eq!(error_stats().total, 0);
+///# init();
+///# }
+/// ```
+pub fn error_stats() -> ErrorStats {
+    let tracked = TRACKED.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(tracked) = &*tracked else {return ErrorStats::default()};
+    let mut types: Vec<TypeStats> = tracked.types.iter()
+        .map(|(name, (count, type_size))| TypeStats {name, count: *count, type_size: *type_size})
+        .collect();
+    types.sort_by(|a, b| b.count.cmp(&a.count).then(a.name.cmp(b.name)));
+    ErrorStats {total: tracked.total, max_depth: tracked.max_depth, deepest: tracked.deepest, types}
+}
+
+///forgets every error tracked so far
+pub fn reset_error_stats() {
+    *TRACKED.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
@@ -0,0 +1,231 @@
+//! Ready made error types for the failures most programs run into.
+//!
+//! small programs can return these through `DynResult` instead of declaring their own enums.\
+//! `severity()` and `error_kind()` know them without registering, and so does `http::dyn_to_status` with the `http` feature.
+//!
+//!# Example
+//! ```rust
+//!# use dynerr::*;
+//! use dynerr::errors::{InvalidInput, NotFound};
+//!
+//! fn port(config: &str) -> DynResult<u16> {
+//!     let value = config.strip_prefix("port=").ok_or_else(|| NotFound::new("port setting"))?;
+//!     value.parse().map_err(|_| InvalidInput::new("port", "must be a number below 65536").into())
+//! }
+//!# fn main() {
+//! let e = port("port=http").unwrap_err();
+//! assert_eq!(e.to_string(), "invalid port: must be a number below 65536");
+//! let field = dynmatch!(e,
+//!     type InvalidInput {
+//!         arm InvalidInput {field, ..} => field.clone(),
+//!         _ => unreachable!()
+//!     },
+//!     _ => String::new()
+//! );
+//! assert_eq!(field, "port");
+//!# }
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+use crate::{ErrorSeverity, Severity, __DowncastThrough};
+
+/// Something that was looked for doesnt exist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotFound {
+    ///what was missing
+    pub what: String,
+}
+
+impl NotFound {
+    ///creates the error
+    pub fn new<S: Into<String>>(what: S) -> Self {
+        Self {what: what.into()}
+    }
+}
+
+impl fmt::Display for NotFound {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} not found", self.what)
+    }
+}
+
+/// An operation took too long.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timeout {
+    ///what timed out
+    pub operation: String,
+    ///how long was waited before giving up
+    pub elapsed: Duration,
+}
+
+impl Timeout {
+    ///creates the error
+    pub fn new<S: Into<String>>(operation: S, elapsed: Duration) -> Self {
+        Self {operation: operation.into(), elapsed}
+    }
+}
+
+impl fmt::Display for Timeout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} timed out after {:?}", self.operation, self.elapsed)
+    }
+}
+
+/// A value supplied to the program was rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidInput {
+    ///the name of the rejected value
+    pub field: String,
+    ///why it was rejected
+    pub reason: String,
+}
+
+impl InvalidInput {
+    ///creates the error
+    pub fn new<F: Into<String>, R: Into<String>>(field: F, reason: R) -> Self {
+        Self {field: field.into(), reason: reason.into()}
+    }
+}
+
+impl fmt::Display for InvalidInput {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid {}: {}", self.field, self.reason)
+    }
+}
+
+/// Something was asked for that this program or platform cant do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unsupported {
+    ///what isnt supported
+    pub what: String,
+}
+
+impl Unsupported {
+    ///creates the error
+    pub fn new<S: Into<String>>(what: S) -> Self {
+        Self {what: what.into()}
+    }
+}
+
+impl fmt::Display for Unsupported {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} is not supported", self.what)
+    }
+}
+
+/// Something needed is temporarily out of reach. trying again later may work.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unavailable {
+    ///what couldnt be reached
+    pub what: String,
+}
+
+impl Unavailable {
+    ///creates the error
+    pub fn new<S: Into<String>>(what: S) -> Self {
+        Self {what: what.into()}
+    }
+}
+
+impl fmt::Display for Unavailable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} is unavailable", self.what)
+    }
+}
+
+/// An operation was cancelled on request, like by the user pressing cancel. returned by `check_cancel!`.
+///
+/// its severity is `Severity::Ignorable`, so `check!(x, or: ..)` and `#[logged]` dont log it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cancelled {
+    ///what was cancelled
+    pub what: String,
+}
+
+impl Cancelled {
+    ///creates the error
+    pub fn new<S: Into<String>>(what: S) -> Self {
+        Self {what: what.into()}
+    }
+}
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} was cancelled", self.what)
+    }
+}
+
+impl Error for NotFound {}
+impl Error for Timeout {}
+impl Error for InvalidInput {}
+impl Error for Unsupported {}
+impl Error for Unavailable {}
+impl Error for Cancelled {}
+
+impl ErrorSeverity for NotFound {}
+impl ErrorSeverity for Timeout {}
+impl ErrorSeverity for InvalidInput {}
+impl ErrorSeverity for Unavailable {}
+
+impl ErrorSeverity for Cancelled {
+    ///cancelling is expected, not a failure
+    fn severity(&self) -> Severity {
+        Severity::Ignorable
+    }
+}
+
+impl ErrorSeverity for Unsupported {
+    ///trying again wont help
+    fn severity(&self) -> Severity {
+        Severity::Fatal
+    }
+}
+
+///the severity of [e] if it is one of the types in this module
+pub(crate) fn severity(e: &(dyn Error + 'static)) -> Option<Severity> {
+    e.__downcast_through::<NotFound>().map(ErrorSeverity::severity)
+        .or_else(|| e.__downcast_through::<Timeout>().map(ErrorSeverity::severity))
+        .or_else(|| e.__downcast_through::<InvalidInput>().map(ErrorSeverity::severity))
+        .or_else(|| e.__downcast_through::<Unsupported>().map(ErrorSeverity::severity))
+        .or_else(|| e.__downcast_through::<Unavailable>().map(ErrorSeverity::severity))
+        .or_else(|| e.__downcast_through::<Cancelled>().map(ErrorSeverity::severity))
+}
+
+///the kind of [e] if it is one of the types in this module
+pub(crate) fn kind(e: &(dyn Error + 'static)) -> Option<&'static str> {
+    if e.__downcast_through::<NotFound>().is_some() {Some("not_found")}
+    else if e.__downcast_through::<Timeout>().is_some() {Some("timeout")}
+    else if e.__downcast_through::<InvalidInput>().is_some() {Some("invalid_input")}
+    else if e.__downcast_through::<Unsupported>().is_some() {Some("unsupported")}
+    else if e.__downcast_through::<Unavailable>().is_some() {Some("unavailable")}
+    else if e.__downcast_through::<Cancelled>().is_some() {Some("cancelled")}
+    else {None}
+}
+
+#[cfg(feature = "http")]
+mod http_status {
+    use super::*;
+    use crate::http::HttpStatus;
+
+    impl HttpStatus for NotFound {fn status(&self) -> u16 {404}}
+    impl HttpStatus for Timeout {fn status(&self) -> u16 {504}}
+    impl HttpStatus for InvalidInput {fn status(&self) -> u16 {400}}
+    impl HttpStatus for Unsupported {fn status(&self) -> u16 {501}}
+    impl HttpStatus for Unavailable {fn status(&self) -> u16 {503}}
+    impl HttpStatus for Cancelled {fn status(&self) -> u16 {499}}
+}
+
+///the HTTP status of [e] if it is one of the types in this module
+#[cfg(feature = "http")]
+pub(crate) fn status(e: &(dyn Error + 'static)) -> Option<u16> {
+    use crate::http::HttpStatus;
+    e.__downcast_through::<NotFound>().map(HttpStatus::status)
+        .or_else(|| e.__downcast_through::<Timeout>().map(HttpStatus::status))
+        .or_else(|| e.__downcast_through::<InvalidInput>().map(HttpStatus::status))
+        .or_else(|| e.__downcast_through::<Unsupported>().map(HttpStatus::status))
+        .or_else(|| e.__downcast_through::<Unavailable>().map(HttpStatus::status))
+        .or_else(|| e.__downcast_through::<Cancelled>().map(HttpStatus::status))
+}
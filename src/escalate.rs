@@ -0,0 +1,52 @@
+//! Translating the error matched by a `dynmatch!` arm into a new one and returning it.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::{DetachedError, DynError};
+
+/// A new error raised by `escalate!` inside a `dynmatch!` arm, sourced by the error the arm matched.
+///
+/// displays and debugs exactly like the new error, and `dynmatch!` matches the new error as if it wasnt wrapped.\
+/// the arm only borrows the matched error, so the source is a `DetachedError` copy of its text and sources.
+pub struct Escalated {
+    error: DynError,
+    source: DetachedError,
+}
+
+impl Escalated {
+    ///wraps [error] with a copy of [matched] as its source.
+    ///
+    ///not meant to be used on its own. use `escalate!` inside a `dynmatch!` arm instead
+    pub fn new<E: Error + 'static>(error: E, matched: &(dyn Error + 'static)) -> Self {
+        Self {error: Box::new(error), source: DetachedError::new(matched)}
+    }
+
+    ///the new error
+    pub fn inner(&self) -> &(dyn Error + 'static) {
+        &*self.error
+    }
+
+    ///unwraps the new error, dropping the source
+    pub fn into_inner(self) -> DynError {
+        self.error
+    }
+}
+
+impl fmt::Debug for Escalated {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.error, f)
+    }
+}
+
+impl fmt::Display for Escalated {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.error, f)
+    }
+}
+
+impl Error for Escalated {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
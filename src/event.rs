@@ -0,0 +1,84 @@
+//! Typed events that carry their own level, log file and formatting.
+
+use std::fmt;
+
+use crate::{Level, LogTarget};
+
+/// Something that can be logged.
+///
+/// every `Display` type is a `LogEvent` logged at `Level::Info` to the file it is given.
+/// implementing it directly lets an application log typed events instead of strings,
+/// with the level, target and text of each kind of event decided in one place.\
+/// `log!` uses the event's level. the other logging macros use their own.
+/// the target is used whenever the macro isnt given a log file.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# use std::fmt;
+/// declare_targets!(ASSETS, NET);
+///
+/// enum GameEvent {
+///     AssetLoaded {path: &'static str, bytes: usize},
+///     NetworkRetry {attempt: u32},
+/// }
+///
+/// impl LogEvent for GameEvent {
+///     fn level(&self) -> Level {
+///         match self {
+///             GameEvent::AssetLoaded {..} => Level::Debug,
+///             GameEvent::NetworkRetry {..} => Level::Warn,
+///         }
+///     }
+///     fn target(&self) -> Option<LogTarget> {
+///         match self {
+///             GameEvent::AssetLoaded {..} => Some(ASSETS),
+///             GameEvent::NetworkRetry {..} => Some(NET),
+///         }
+///     }
+///     fn fmt_event(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///         match self {
+///             GameEvent::AssetLoaded {path, bytes} => write!(f, "loaded {} ({} bytes)", path, bytes),
+///             GameEvent::NetworkRetry {attempt} => write!(f, "retrying connection, attempt {}", attempt),
+///         }
+///     }
+/// }
+///# fn main() {
+/// log!(GameEvent::AssetLoaded {path: "hero.png", bytes: 5120});  //assets.log at Level::Debug
+/// log!(GameEvent::NetworkRetry {attempt: 2});                     //net.log at Level::Warn
+/// log!(GameEvent::NetworkRetry {attempt: 3}, "test.log");         //test.log at Level::Warn
+///# let text = std::fs::read_to_string("net.log").unwrap();
+///# assert!(text.contains("WARN  retrying connection, attempt 2"));
+///# clean!(ASSETS);
+///# clean!(NET);
+///# }
+/// ```
+pub trait LogEvent {
+    ///the level `log!` writes the event at
+    fn level(&self) -> Level {
+        Level::Info
+    }
+
+    ///the log file the event goes to when no file is given
+    fn target(&self) -> Option<LogTarget> {
+        None
+    }
+
+    ///writes the text of the event
+    fn fmt_event(&self, f: &mut fmt::Formatter) -> fmt::Result;
+}
+
+impl<T: fmt::Display + ?Sized> LogEvent for T {
+    fn fmt_event(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+///displays a `LogEvent` with its `fmt_event`
+pub(crate) struct EventText<'a, T: ?Sized>(pub &'a T);
+
+impl<T: LogEvent + ?Sized> fmt::Display for EventText<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt_event(f)
+    }
+}
@@ -0,0 +1,151 @@
+//! A one line machine readable report of how a run ended, for workflow schedulers.
+//!
+//! schedulers like Airflow or systemd can branch on the status, kind and code of the summary
+//! instead of parsing log files for the reason a job failed.
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::{ExitCode, Termination};
+
+use crate::DynError;
+
+/// The environment variable naming the file an `ExitSummary` returned from `main` is written to, instead of stdout.
+pub const EXIT_SUMMARY_VAR: &str = "DYNERR_EXIT_SUMMARY";
+
+/// Whether a run succeeded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RunStatus {
+    Succeeded,
+    Failed,
+}
+
+impl RunStatus {
+    ///`succeeded` or `failed`, the words `summary::report` uses
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RunStatus::Succeeded    => "succeeded",
+            RunStatus::Failed       => "failed",
+        }
+    }
+}
+
+/// How a run ended, serialized as a single JSON line like
+/// `{"status":"failed","kind":"not_found","message":"config.toml not found","code":1}`.
+///
+/// the kind is the one registered with `register_kind`, and is null along with the message when the run succeeded.\
+/// returned from `main`, it writes itself to the file named by `DYNERR_EXIT_SUMMARY` or to stdout
+/// then exits with its code.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+/// fn run() -> DynResult<()> {
+///     Err(errors::NotFound::new("config.toml"))?
+/// }
+///
+/// fn main() -> ExitSummary {
+///     let summary = ExitSummary::from(run().exit_summary());
+///     assert_eq!(summary.to_json(), r#"{"status":"failed","kind":"not_found","message":"config.toml not found","code":1}"#);
+///#    let summary = ExitSummary::success(); //so the doctest passes
+///     summary
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ExitSummary {
+    pub status: RunStatus,
+    pub kind: Option<String>,
+    pub message: Option<String>,
+    pub code: u8,
+}
+
+impl ExitSummary {
+    ///a successful run, with code 0
+    pub fn success() -> Self {
+        Self {status: RunStatus::Succeeded, kind: None, message: None, code: 0}
+    }
+
+    ///a run that failed with [e], with code 1
+    pub fn failure(e: &(dyn Error + 'static)) -> Self {
+        Self {
+            status: RunStatus::Failed,
+            kind: crate::error_kind(e).map(str::to_string),
+            message: Some(e.to_string()),
+            code: 1,
+        }
+    }
+
+    ///this summary with the exit code [code]
+    pub fn with_code(mut self, code: u8) -> Self {
+        self.code = code;
+        self
+    }
+
+    ///the summary as one line of JSON
+    pub fn to_json(&self) -> String {
+        self.to_string()
+    }
+
+    ///writes the summary as a line to [path], replacing whatever was there
+    pub fn write_to<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, format!("{}\n", self))
+    }
+
+    ///writes the summary as a line to stdout
+    pub fn print(&self) -> io::Result<()> {
+        writeln!(io::stdout().lock(), "{}", self)
+    }
+}
+
+///writes [value] as a JSON string, or null
+fn write_optional(f: &mut fmt::Formatter, value: &Option<String>) -> fmt::Result {
+    match value {
+        Some(value) => crate::format::write_json_string(f, value),
+        None => f.write_str("null"),
+    }
+}
+
+impl fmt::Display for ExitSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{{\"status\":\"{}\",\"kind\":", self.status.as_str())?;
+        write_optional(f, &self.kind)?;
+        f.write_str(",\"message\":")?;
+        write_optional(f, &self.message)?;
+        write!(f, ",\"code\":{}}}", self.code)
+    }
+}
+
+impl<T> From<Result<T, ExitSummary>> for ExitSummary {
+    ///the summary of a finished run, whatever it returned
+    fn from(result: Result<T, ExitSummary>) -> Self {
+        result.err().unwrap_or_else(ExitSummary::success)
+    }
+}
+
+impl Termination for ExitSummary {
+    ///writes the summary to the file named by `DYNERR_EXIT_SUMMARY`, or stdout, then exits with its code
+    fn report(self) -> ExitCode {
+        let written = match std::env::var_os(EXIT_SUMMARY_VAR) {
+            Some(path) => self.write_to(path),
+            None => self.print(),
+        };
+        if let Err(e) = written {
+            let _ = writeln!(io::stderr().lock(), "Dynerr: couldnt write the exit summary: {}", e);
+        }
+        ExitCode::from(self.code)
+    }
+}
+
+/// Turns the error of a result into an `ExitSummary`.
+pub trait IntoExitSummary<T> {
+    ///[self] with its error summarized
+    fn exit_summary(self) -> Result<T, ExitSummary>;
+}
+
+impl<T, E: Into<DynError>> IntoExitSummary<T> for Result<T, E> {
+    fn exit_summary(self) -> Result<T, ExitSummary> {
+        self.map_err(|e| ExitSummary::failure(&*e.into()))
+    }
+}
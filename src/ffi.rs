@@ -0,0 +1,163 @@
+//! Passing errors across a C boundary.
+//!
+//! `into_handle` turns a `DynError` into a reference counted opaque pointer that C code can inspect with
+//! `dynerr_message`, `dynerr_code`, `dynerr_clone` and `dynerr_free`.\
+//! when the handle comes back into Rust, `from_handle` turns it back into a `DynError`.\
+//! handles hold a `DynError`, which isnt `Send`, so they must only be used on the thread that created them.
+//!
+//! only available with the `ffi` feature.
+
+use std::error::Error;
+use std::ffi::CString;
+use std::fmt;
+use std::os::raw::{c_char, c_int};
+use std::rc::Rc;
+
+use crate::{DynError, __DowncastThrough};
+use crate::registry::Registry;
+
+/// The error behind a handle. opaque to C.
+#[derive(Debug)]
+pub struct ErrorHandle {
+    error: DynError,
+    message: CString,
+    code: c_int,
+}
+
+///a lookup from a registered type to its code
+type Coder = Box<dyn Fn(&(dyn Error + 'static)) -> Option<c_int> + Send + Sync>;
+
+///every registered coder
+static CODERS: Registry<Coder> = Registry::new();
+
+/// Registers how errors of type [T] are turned into the code returned by `dynerr_code`.
+///
+/// io errors default to their raw OS error and every other error to 1. registering a type again replaces its code.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# use std::num::ParseIntError;
+///# fn main() {
+/// ffi::register_code::<ParseIntError>(|_| 22);
+/// let handle = ffi::into_handle("x".parse::<u32>().unwrap_err().into());
+/// assert_eq!(unsafe {ffi::dynerr_code(handle)}, 22);
+/// unsafe {ffi::dynerr_free(handle)};
+///# }
+/// ```
+pub fn register_code<T: Error + 'static>(code: fn(&T) -> c_int) {
+    CODERS.register::<T>(Box::new(move |e| e.__downcast_through::<T>().map(code)));
+}
+
+///the code of [e] from the registry, its OS error, or 1
+fn code_of(e: &(dyn Error + 'static)) -> c_int {
+    CODERS.find_map(|coder| coder(e))
+        .or_else(|| e.__downcast_through::<std::io::Error>().and_then(std::io::Error::raw_os_error))
+        .unwrap_or(1)
+}
+
+/// Turns [e] into a handle owning one reference.
+///
+/// release it with `dynerr_free` or `from_handle`.
+pub fn into_handle(e: DynError) -> *const ErrorHandle {
+    let message = CString::new(e.to_string().replace('\0', "\\0")).unwrap_or_default();
+    let code = code_of(&*e);
+    Rc::into_raw(Rc::new(ErrorHandle {error: e, message, code}))
+}
+
+/// Takes back the reference owned by [handle] as a `DynError`.
+///
+/// returns the original error if this was the last reference,
+/// otherwise a `SharedHandleError` that displays like it and sources it.
+///
+/// # Safety
+/// [handle] must come from `into_handle` or `dynerr_clone` and not have been released yet.
+pub unsafe fn from_handle(handle: *const ErrorHandle) -> DynError {
+    let handle = Rc::from_raw(handle);
+    match Rc::try_unwrap(handle) {
+        Ok(handle) => handle.error,
+        Err(shared) => Box::new(SharedHandleError(shared)),
+    }
+}
+
+/// An error that is still referenced by C code when it came back into Rust.
+#[derive(Debug)]
+pub struct SharedHandleError(Rc<ErrorHandle>);
+
+impl SharedHandleError {
+    ///the original error
+    pub fn inner(&self) -> &(dyn Error + 'static) {
+        &*self.0.error
+    }
+}
+
+impl fmt::Display for SharedHandleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0.error, f)
+    }
+}
+
+impl Error for SharedHandleError {
+    ///displays as the original error, so its source is the original error's source
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.inner().source()
+    }
+}
+
+/// Returns the Display text of the error as a NUL terminated string.
+///
+/// the string lives as long as the handle.
+///
+/// # Safety
+/// [handle] must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn dynerr_message(handle: *const ErrorHandle) -> *const c_char {
+    (*handle).message.as_ptr()
+}
+
+/// Returns the code of the error, see `register_code`.
+///
+/// # Safety
+/// [handle] must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn dynerr_code(handle: *const ErrorHandle) -> c_int {
+    (*handle).code
+}
+
+/// Adds a reference to the handle. each reference must be released separately.
+///
+/// # Safety
+/// [handle] must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn dynerr_clone(handle: *const ErrorHandle) -> *const ErrorHandle {
+    Rc::increment_strong_count(handle);
+    handle
+}
+
+/// Releases one reference to the handle. null is ignored.
+///
+/// # Safety
+/// [handle] must be null or a live handle, and the reference must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn dynerr_free(handle: *const ErrorHandle) {
+    if !handle.is_null() {drop(Rc::from_raw(handle))}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let handle = into_handle(std::io::Error::from_raw_os_error(2).into());
+        unsafe {
+            assert_eq!(dynerr_code(handle), 2);
+            let shared = from_handle(dynerr_clone(handle));
+            assert!(shared.downcast_ref::<SharedHandleError>().is_some());
+            crate::error_conformance!(*shared);
+            drop(shared);
+            let original = from_handle(handle);
+            assert!(original.downcast_ref::<std::io::Error>().is_some());
+        }
+    }
+}
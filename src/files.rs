@@ -0,0 +1,183 @@
+//! What the logging functions accept as a log file.
+
+use std::sync::Mutex;
+
+/// One or more log files an event is written to.
+/// 
+/// implemented for paths (`&str`, `String`) and for arrays, slices and vecs of them,
+/// so `log!(event, ["event.log", "audit.log"])` writes to both files.
+pub trait LogFiles {
+    ///the paths to write to
+    fn log_files(&self) -> Vec<&str>;
+
+    ///the name of the `LogTarget` written to, used by `LoggerBuilder::target_level`
+    fn target(&self) -> Option<&str> {
+        None
+    }
+
+    ///the path to write to if there is only one, saving the allocation of `log_files`. not meant to be implemented on its own
+    #[doc(hidden)]
+    fn __single_log_file(&self) -> Option<&str> {
+        None
+    }
+}
+
+impl LogFiles for str {
+    fn log_files(&self) -> Vec<&str> {
+        vec![self]
+    }
+
+    fn __single_log_file(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+impl LogFiles for String {
+    fn log_files(&self) -> Vec<&str> {
+        vec![self.as_str()]
+    }
+
+    fn __single_log_file(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+impl<T: LogFiles + ?Sized> LogFiles for &T {
+    fn log_files(&self) -> Vec<&str> {
+        (**self).log_files()
+    }
+
+    fn target(&self) -> Option<&str> {
+        (**self).target()
+    }
+
+    fn __single_log_file(&self) -> Option<&str> {
+        (**self).__single_log_file()
+    }
+}
+
+impl<S: AsRef<str>> LogFiles for [S] {
+    fn log_files(&self) -> Vec<&str> {
+        self.iter().map(AsRef::as_ref).collect()
+    }
+}
+
+impl<S: AsRef<str>, const N: usize> LogFiles for [S; N] {
+    fn log_files(&self) -> Vec<&str> {
+        self.iter().map(AsRef::as_ref).collect()
+    }
+}
+
+impl<S: AsRef<str>> LogFiles for Vec<S> {
+    fn log_files(&self) -> Vec<&str> {
+        self.iter().map(AsRef::as_ref).collect()
+    }
+}
+
+/// A log file declared with `declare_targets!`.
+/// 
+/// using constants instead of string paths turns a misspelled log file into a compile error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LogTarget {
+    name: &'static str,
+    path: Option<&'static str>,
+}
+
+impl LogTarget {
+    ///a target named [name] written to [path], or to the lowercased name plus ".log" if no path is given
+    pub const fn new(name: &'static str, path: Option<&'static str>) -> Self {
+        Self {name, path}
+    }
+
+    ///the name the target was declared with
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    ///the file the target writes to.
+    ///a default path is made the first time a target with that name asks for it and reused after that,
+    ///so it is only ever allocated once per name
+    pub fn path(&self) -> &'static str {
+        ///the default path made for each target name
+        static DEFAULT_PATHS: Mutex<Vec<(&'static str, &'static str)>> = Mutex::new(Vec::new());
+        if let Some(path) = self.path {return path}
+        let mut paths = DEFAULT_PATHS.lock().unwrap_or_else(|e| e.into_inner());
+        match paths.iter().find(|(name, _)| *name == self.name) {
+            Some((_, path)) => path,
+            None => {
+                let path: &'static str = Box::leak(format!("{}.log", self.name.to_lowercase()).into_boxed_str());
+                paths.push((self.name, path));
+                path
+            }
+        }
+    }
+}
+
+impl LogFiles for LogTarget {
+    fn log_files(&self) -> Vec<&str> {
+        vec![self.path()]
+    }
+
+    fn __single_log_file(&self) -> Option<&str> {
+        Some(self.path())
+    }
+
+    fn target(&self) -> Option<&str> {
+        Some(self.name)
+    }
+}
+
+impl AsRef<str> for LogTarget {
+    fn as_ref(&self) -> &str {
+        self.path()
+    }
+}
+
+/// Declares `LogTarget` constants to use in place of log file paths.
+/// 
+/// a target without a path writes to its lowercased name plus ".log".\
+/// the constants work anywhere a log file is accepted, including arrays of targets.
+/// 
+///# Example
+/// ```rust
+///# use dynerr::*;
+/// declare_targets!(NET, DB = "database.log", pub UI);
+///# fn main() {
+/// log!("connected", NET);                 //net.log
+/// log_warn!("slow query", DB);            //database.log
+/// log!("button clicked", [UI, NET]);      //ui.log and net.log
+/// assert_eq!(NET.path(), "net.log");
+///# clean!(NET);
+///# clean!(DB);
+///# clean!(UI);
+///# }
+/// ```
+#[macro_export]
+macro_rules! declare_targets {
+    ($($vis:vis $name:ident $(= $path:expr)?),* $(,)?) => {
+        $(
+            $vis const $name: $crate::LogTarget = $crate::LogTarget::new(stringify!($name), $crate::__target_path!($($path)?));
+        )*
+    };
+}
+
+///the optional path of a `declare_targets!` entry
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __target_path {
+    () => {None};
+    ($path:expr) => {Some($path)};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_path_made_once() {
+        let (target, same) = (LogTarget::new("Default_Path_Made_Once", None), LogTarget::new("Default_Path_Made_Once", None));
+        assert_eq!(target.path(), "default_path_made_once.log");
+        assert!(std::ptr::eq(target.path(), same.path()));
+        assert_eq!(LogTarget::new("Given", Some("given.txt")).path(), "given.txt");
+    }
+}
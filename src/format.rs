@@ -0,0 +1,419 @@
+//! The plain text formatter used when writing records.
+
+use std::fmt;
+
+use crate::{Level, LogRecord};
+use crate::record::{Timestamp, RECORD_FIELD, THREAD_FIELD};
+
+/// How the level of a record is marked in the plain text format.
+/// 
+/// every style is fixed width so messages line up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LevelMarkers {
+    ///`WARN `
+    #[default]
+    Plain,
+    ///`[WARN ]`
+    Brackets,
+    ///`🟡 [WARN ]`
+    Emoji,
+}
+
+impl LevelMarkers {
+    ///the emoji drawn before a level by `LevelMarkers::Emoji`
+    fn emoji(level: Level) -> &'static str {
+        match level {
+            Level::Error    => "🔴",
+            Level::Warn     => "🟡",
+            Level::Info     => "🟢",
+            Level::Debug    => "🔵",
+            Level::Trace    => "⚪",
+        }
+    }
+
+    ///writes the marker for [level]
+    fn write<W: fmt::Write + ?Sized>(&self, f: &mut W, level: Level) -> fmt::Result {
+        match self {
+            LevelMarkers::Plain     => write!(f, "{:<5}", level),
+            LevelMarkers::Brackets  => write!(f, "[{:<5}]", level),
+            LevelMarkers::Emoji     => write!(f, "{} [{:<5}]", Self::emoji(level), level),
+        }
+    }
+}
+
+/// Formats records as plain text lines.
+/// 
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// Logger::builder()
+///     .text_format(TextFormat::new().markers(LevelMarkers::Brackets))
+///     .init();
+/// log_warn!("low on memory", "test.log"); //2026-10-15T12:34:56.789Z [WARN ] low on memory
+///# }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TextFormat {
+    markers: LevelMarkers,
+    thread: bool,
+    record_ids: bool,
+    deterministic: bool,
+}
+
+impl TextFormat {
+    ///the default format
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///sets how levels are marked. defaults to `LevelMarkers::Plain`
+    pub fn markers(mut self, markers: LevelMarkers) -> Self {
+        self.markers = markers;
+        self
+    }
+
+    ///writes the thread name and id of every record as a `thread=name#id` field. defaults to false
+    pub fn thread(mut self, thread: bool) -> Self {
+        self.thread = thread;
+        self
+    }
+
+    /// Writes the process id and number of every record first, as a `record=pid:id` field. defaults to false
+    /// 
+    /// records are numbered from 1 in the order they are created, so a record can be found again from its id,
+    /// and `logged_panic!` adds the range of ids logged by the process to its panic message.
+    /// 
+    ///# Example
+    /// ```rust
+    ///# use dynerr::*;
+    ///# fn main() {
+    /// let record = LogRecord::new(Level::Warn, "low on memory");
+    /// let line = TextFormat::new().record_ids(true).format(&record);
+    /// assert!(line.contains(&format!("{{record={}:", std::process::id())));
+    /// assert_eq!(line.parse::<LogRecord>().unwrap().id, record.id);
+    ///# }
+    /// ```
+    pub fn record_ids(mut self, record_ids: bool) -> Self {
+        self.record_ids = record_ids;
+        self
+    }
+
+    ///whether record ids are written
+    pub(crate) fn writes_record_ids(&self) -> bool {
+        self.record_ids && !self.deterministic
+    }
+
+    /// Writes output that is the same on every run, for comparing logs against golden files in tests. defaults to false
+    /// 
+    /// every timestamp is written as `1970-01-01T00:00:00.000Z`, thread and record ids are left out,
+    /// and the values of fields added with `LogRecord::with_quantity`, like timings, are written as `_`.
+    /// 
+    ///# Example
+    /// ```rust
+    ///# use dynerr::*;
+    ///# use std::time::Duration;
+    ///# fn main() {
+    /// let format = TextFormat::new().thread(true).deterministic(true);
+    /// let record = LogRecord::new(Level::Info, "cache warmed").with_quantity("took", Elapsed(Duration::from_millis(3)));
+    /// let line = format.format(&record);
+    /// assert!(line.starts_with("1970-01-01T00:00:00.000Z INFO  {thread="));
+    /// assert!(line.ends_with(" took=_} cache warmed") && !line.contains('#'));
+    ///# }
+    /// ```
+    pub fn deterministic(mut self, deterministic: bool) -> Self {
+        self.deterministic = deterministic;
+        self
+    }
+
+    ///writes [record] without a trailing newline
+    pub fn write<W: fmt::Write + ?Sized>(&self, f: &mut W, record: &LogRecord) -> fmt::Result {
+        match self.deterministic {
+            true => write!(f, "{} ", Timestamp(std::time::UNIX_EPOCH))?,
+            false => write!(f, "{} ", Timestamp(record.timestamp))?,
+        }
+        self.markers.write(f, record.level)?;
+        f.write_str(" ")?;
+        let ids = match (record.pid, record.id) {
+            (Some(pid), Some(id)) if self.writes_record_ids() => Some((pid, id)),
+            _ => None,
+        };
+        let thread = self.thread && (record.thread.is_some() || record.thread_id.is_some());
+        if ids.is_some() || thread || !record.fields.is_empty() || record.message.starts_with('{') {
+            f.write_str("{")?;
+            if let Some((pid, id)) = ids {
+                write!(f, "{}={}:{}", RECORD_FIELD, pid, id)?;
+                if thread || !record.fields.is_empty() {f.write_str(" ")?}
+            }
+            if thread {
+                let name = record.thread.as_deref().unwrap_or("");
+                let quoted = !name.is_empty() && !is_bare(name);
+                write!(f, "{}=", THREAD_FIELD)?;
+                if quoted {
+                    f.write_str("\"")?;
+                    write_escaped(f, name)?;
+                } else {
+                    f.write_str(name)?;
+                }
+                if !self.deterministic {
+                    f.write_str("#")?;
+                    if let Some(id) = record.thread_id {write!(f, "{}", id)?}
+                }
+                if quoted {f.write_str("\"")?}
+            }
+            for (i, (key, value)) in record.fields.iter().enumerate() {
+                if i > 0 || thread {f.write_str(" ")?}
+                write_value(f, key)?;
+                f.write_str("=")?;
+                match self.deterministic && record.quantities.iter().any(|(k, _)| k == key) {
+                    true => f.write_str("_")?,
+                    false => write_value(f, value)?,
+                }
+            }
+            f.write_str("} ")?;
+        }
+        f.write_str(&record.message)
+    }
+
+    ///formats [record] into a string without a trailing newline
+    pub fn format(&self, record: &LogRecord) -> String {
+        let mut line = String::new();
+        let _ = self.write(&mut line, record);
+        line
+    }
+}
+
+///whether [s] can be parsed back without quotes
+fn is_bare(s: &str) -> bool {
+    !s.is_empty() && !s.contains(|c: char| c.is_whitespace() || matches!(c, '"' | '\\' | '=' | '{' | '}'))
+}
+
+///writes [s] quoted if it couldnt be parsed back bare
+fn write_value<W: fmt::Write + ?Sized>(f: &mut W, s: &str) -> fmt::Result {
+    if is_bare(s) {return f.write_str(s)}
+    f.write_str("\"")?;
+    write_escaped(f, s)?;
+    f.write_str("\"")
+}
+
+///writes [s] with the characters that cant appear inside quotes escaped
+fn write_escaped<W: fmt::Write + ?Sized>(f: &mut W, s: &str) -> fmt::Result {
+    for c in s.chars() {
+        match c {
+            '"'     => f.write_str("\\\"")?,
+            '\\'    => f.write_str("\\\\")?,
+            '\n'    => f.write_str("\\n")?,
+            '\r'    => f.write_str("\\r")?,
+            '\t'    => f.write_str("\\t")?,
+            c       => f.write_char(c)?,
+        }
+    }
+    Ok(())
+}
+
+/// Formats records as single line JSON objects.
+/// 
+/// the thread name and id are included whenever the record has them.
+/// fields added with `LogRecord::with_quantity` are written as numbers.
+/// 
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// let record = LogRecord::new(Level::Info, "saved").with_field("slot", 2);
+/// let json = JsonFormat::new().format(&record);
+/// assert!(json.contains(r#""level":"INFO","message":"saved","fields":{"slot":"2"}"#));
+///# }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct JsonFormat;
+
+impl JsonFormat {
+    ///the default format
+    pub fn new() -> Self {
+        Self
+    }
+
+    ///writes [record] without a trailing newline
+    pub fn write<W: fmt::Write + ?Sized>(&self, f: &mut W, record: &LogRecord) -> fmt::Result {
+        write_json(f, record, &mut |f, s| write_json_string(f, s))
+    }
+
+    ///formats [record] into a string without a trailing newline
+    pub fn format(&self, record: &LogRecord) -> String {
+        let mut line = String::new();
+        let _ = self.write(&mut line, record);
+        line
+    }
+}
+
+///writes [record] as a JSON object, writing the message, field names and values and thread name with [string]
+pub(crate) fn write_json<W: fmt::Write + ?Sized>(f: &mut W, record: &LogRecord, string: &mut dyn FnMut(&mut W, &str) -> fmt::Result) -> fmt::Result {
+    write!(f, "{{\"timestamp\":\"{}\",\"level\":\"{}\",\"message\":", Timestamp(record.timestamp), record.level)?;
+    string(f, &record.message)?;
+    f.write_str(",\"fields\":{")?;
+    for (i, (key, value)) in record.fields.iter().enumerate() {
+        if i > 0 {f.write_str(",")?}
+        string(f, key)?;
+        f.write_str(":")?;
+        match record.quantities.iter().find(|(k, _)| k == key) {
+            Some((_, number)) => f.write_str(number)?,
+            None => string(f, value)?,
+        }
+    }
+    f.write_str("}")?;
+    if let Some(thread) = &record.thread {
+        f.write_str(",\"thread\":")?;
+        string(f, thread)?;
+    }
+    if let Some(id) = record.thread_id {
+        write!(f, ",\"thread_id\":{}", id)?;
+    }
+    if let Some(pid) = record.pid {
+        write!(f, ",\"pid\":{}", pid)?;
+    }
+    if let Some(id) = record.id {
+        write!(f, ",\"id\":{}", id)?;
+    }
+    f.write_str("}")
+}
+
+/// Formats records as logfmt lines, like `time=2026-10-15T12:34:56.789Z level=warn msg="connection lost" peer=10.0.0.1`.
+/// 
+/// fields follow the message, then the thread name if the record has one.
+/// 
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// let record = LogRecord::new(Level::Warn, "connection lost").with_field("peer", "10.0.0.1");
+/// let line = LogfmtFormat::new().format(&record);
+/// assert!(line.contains(r#" level=warn msg="connection lost" peer=10.0.0.1"#));
+///# }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LogfmtFormat;
+
+impl LogfmtFormat {
+    ///the default format
+    pub fn new() -> Self {
+        Self
+    }
+
+    ///writes [record] without a trailing newline
+    pub fn write<W: fmt::Write + ?Sized>(&self, f: &mut W, record: &LogRecord) -> fmt::Result {
+        write!(f, "time={} level={} msg=", Timestamp(record.timestamp), record.level.as_str().to_lowercase())?;
+        write_value(f, &record.message)?;
+        for (key, value) in &record.fields {
+            f.write_str(" ")?;
+            write_value(f, key)?;
+            f.write_str("=")?;
+            write_value(f, value)?;
+        }
+        if let Some(thread) = &record.thread {
+            f.write_str(" thread=")?;
+            write_value(f, thread)?;
+        }
+        Ok(())
+    }
+
+    ///formats [record] into a string without a trailing newline
+    pub fn format(&self, record: &LogRecord) -> String {
+        let mut line = String::new();
+        let _ = self.write(&mut line, record);
+        line
+    }
+}
+
+/// Formats records as CSV rows with the columns in `CsvFormat::HEADER`.
+/// 
+/// the fields are joined into one column as `key=value` pairs. values are quoted when they need to be.
+/// log files encoded as CSV start with the header row.
+/// 
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// let record = LogRecord::new(Level::Error, "disk full, stopping").with_field("free", 0);
+/// let row = CsvFormat::new().format(&record);
+/// assert!(row.contains(r#",ERROR,"disk full, stopping",free=0,"#));
+///# }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CsvFormat;
+
+impl CsvFormat {
+    ///the header row of a CSV log file
+    pub const HEADER: &'static str = "timestamp,level,message,fields,thread";
+
+    ///the default format
+    pub fn new() -> Self {
+        Self
+    }
+
+    ///writes [record] without a trailing newline
+    pub fn write<W: fmt::Write + ?Sized>(&self, f: &mut W, record: &LogRecord) -> fmt::Result {
+        write!(f, "{},{},", Timestamp(record.timestamp), record.level.as_str())?;
+        write_csv_cell(f, &record.message)?;
+        f.write_str(",")?;
+        let mut fields = String::new();
+        for (i, (key, value)) in record.fields.iter().enumerate() {
+            if i > 0 {fields.push(' ')}
+            write_value(&mut fields, key)?;
+            fields.push('=');
+            write_value(&mut fields, value)?;
+        }
+        write_csv_cell(f, &fields)?;
+        f.write_str(",")?;
+        write_csv_cell(f, record.thread.as_deref().unwrap_or(""))
+    }
+
+    ///formats [record] into a string without a trailing newline
+    pub fn format(&self, record: &LogRecord) -> String {
+        let mut line = String::new();
+        let _ = self.write(&mut line, record);
+        line
+    }
+}
+
+///writes [s] as a CSV cell, quoted with doubled quotes if it contains a separator, quote or line break
+fn write_csv_cell<W: fmt::Write + ?Sized>(f: &mut W, s: &str) -> fmt::Result {
+    if !s.contains([',', '"', '\n', '\r']) {return f.write_str(s)}
+    f.write_str("\"")?;
+    f.write_str(&s.replace('"', "\"\""))?;
+    f.write_str("\"")
+}
+
+/// How records are encoded in a log file, set per file with `LoggerBuilder::encoding`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Encoding {
+    ///the configured `TextFormat`, which `LogReader` can parse back
+    #[default]
+    Text,
+    ///`JsonFormat`
+    Json,
+    ///`LogfmtFormat`
+    Logfmt,
+    ///`CsvFormat`, starting the file with its header row
+    Csv,
+    ///`JsonFormat`, with strings written again after their first time replaced by `{"ref":N}`
+    ///and defined on `{"intern":N,"text":".."}` lines. `expand_interned` turns it back into plain `Json`
+    InternedJson,
+}
+
+///writes [s] as a quoted and escaped JSON string
+pub(crate) fn write_json_string<W: fmt::Write + ?Sized>(f: &mut W, s: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in s.chars() {
+        match c {
+            '"'     => f.write_str("\\\"")?,
+            '\\'    => f.write_str("\\\\")?,
+            '\n'    => f.write_str("\\n")?,
+            '\r'    => f.write_str("\\r")?,
+            '\t'    => f.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c       => f.write_char(c)?,
+        }
+    }
+    f.write_str("\"")
+}
@@ -0,0 +1,74 @@
+//! Collects errors from worker threads for a coordinating thread.
+
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::{Level, LogRecord, MultiError};
+
+/// A cloneable sink that worker threads send their errors into.
+/// 
+/// every error is logged with the name of the thread that sent it.\
+/// If no log file supplied then defaults to the `default_log()` of the thread that created it.
+/// 
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// let funnel = ErrorFunnel::new().log_file("test.log");
+/// let workers: Vec<_> = (0..4).map(|i| {
+///     let funnel = funnel.clone();
+///     std::thread::Builder::new().name(format!("worker-{}", i)).spawn(move || {
+///         if i % 2 == 0 {funnel.send_err(format!("job {} failed", i))}
+///     }).unwrap()
+/// }).collect();
+/// for worker in workers {worker.join().unwrap()}
+/// let errors = funnel.finish().unwrap_err();
+/// assert_eq!(errors.len(), 2);
+///# }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ErrorFunnel {
+    errors: Arc<Mutex<Vec<Box<dyn Error + Send + Sync>>>>,
+    log_file: String,
+}
+
+impl Default for ErrorFunnel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ErrorFunnel {
+    ///creates an empty funnel
+    pub fn new() -> Self {
+        Self {
+            errors: Arc::new(Mutex::new(Vec::new())),
+            log_file: crate::default_log(),
+        }
+    }
+
+    ///sets the file submissions are logged to
+    pub fn log_file(mut self, log_file: &str) -> Self {
+        self.log_file = log_file.to_string();
+        self
+    }
+
+    ///logs [e] with the current thread's name then stores it
+    pub fn send_err<E: Into<Box<dyn Error + Send + Sync>>>(&self, e: E) {
+        let e = e.into();
+        let current = thread::current();
+        let name = current.name().map(str::to_string).unwrap_or_else(|| format!("{:?}", current.id()));
+        crate::write_record(&LogRecord::new(Level::Error, &e).with_field("thread", name), &self.log_file);
+        self.errors.lock().unwrap_or_else(|e| e.into_inner()).push(e);
+    }
+
+    ///takes every error sent so far, Ok if there were none
+    pub fn finish(&self) -> Result<(), MultiError> {
+        let errors = std::mem::take(&mut *self.errors.lock().unwrap_or_else(|e| e.into_inner()));
+        errors.into_iter()
+            .map(|e| e as crate::DynError)
+            .collect::<MultiError>()
+            .into_result()
+    }
+}
@@ -0,0 +1,63 @@
+//! Hints telling users how to fix an error.
+//! 
+//! implement `Help` on your error then `register_help::<YourError>()` once at startup.
+
+use std::error::Error;
+
+use crate::registry::Registry;
+
+/// Implemented by error types that can tell the user how to fix them.
+/// 
+///# Example
+/// ```rust,should_panic
+///# use dynerr::*;
+///# use std::{fmt, error};
+/// #[derive(Debug)]
+/// struct MissingConfig;
+///# impl fmt::Display for MissingConfig {
+///#     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///#         write!(f, "config file missing")
+///#     }
+///# }
+///# impl error::Error for MissingConfig {}
+/// impl Help for MissingConfig {
+///     fn help(&self) -> Option<String> {
+///         Some("run `app init` first".to_string())
+///     }
+/// }
+///# fn main() {
+/// register_help::<MissingConfig>();
+/// let e: DynError = Box::new(MissingConfig);
+/// assert_eq!(help(&*e).unwrap(), "run `app init` first");
+/// //logged with a hint field and panics with "config file missing\nhint: run `app init` first"
+/// check!(Err::<(), _>(e), "test.log"; on_err = log);
+///# }
+/// ```
+pub trait Help {
+    ///how to fix this error, if known
+    fn help(&self) -> Option<String>;
+}
+
+///a downcasting lookup for one registered type
+type Helper = fn(&(dyn Error + 'static)) -> Option<String>;
+
+///every registered helper
+static REGISTRY: Registry<Helper> = Registry::new();
+
+///downcasts to [T] and asks it for its hint
+fn ask<T: Error + Help + 'static>(e: &(dyn Error + 'static)) -> Option<String> {
+    use crate::__DowncastThrough;
+    e.__downcast_through::<T>().and_then(Help::help)
+}
+
+/// Registers [T] so failed `check!`s can see its `Help` impl through a `DynError`.
+/// 
+/// registering the same type twice has no effect.
+pub fn register_help<T: Error + Help + 'static>() {
+    REGISTRY.register::<T>(ask::<T>);
+}
+
+/// Returns the hint of any error whose type has a registered `Help`.
+pub fn help(e: &(dyn Error + 'static)) -> Option<String> {
+    REGISTRY.find_map(|ask| ask(e))
+}
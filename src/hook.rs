@@ -0,0 +1,68 @@
+//! A process wide callback for every error `check!` handles.
+
+use std::error::Error;
+use std::fmt;
+use std::panic::Location;
+use std::sync::RwLock;
+
+type Hook = Box<dyn Fn(&(dyn Error + 'static), &'static Location<'static>) + Send + Sync>;
+
+///the installed error hook
+static HOOK: RwLock<Option<Hook>> = RwLock::new(None);
+
+/// Calls [hook] with every error a `check!` gives up on, along with where the check is.
+///
+/// runs when a check fails, recovers from an error with `or:`, or falls back to a `default(..)` policy,
+/// and for the same cases of `Checker`. errors that a retry recovered from are not reported.\
+/// the hook runs before the error is logged, on the thread that hit it. setting a new hook replaces the old one.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// static ERRORS: AtomicUsize = AtomicUsize::new(0);
+///# fn main() {
+/// set_error_hook(|e, location| {
+///     ERRORS.fetch_add(1, Ordering::Relaxed);
+///     eprintln!("{} at {}", e, location);
+/// });
+/// let port: u16 = check!("http".parse(), "test.log", or: 80);
+/// assert_eq!((port, ERRORS.load(Ordering::Relaxed)), (80, 1));
+///# clear_error_hook();
+///# }
+/// ```
+pub fn set_error_hook<F>(hook: F)
+where F: Fn(&(dyn Error + 'static), &'static Location<'static>) + Send + Sync + 'static {
+    *HOOK.write().unwrap_or_else(|e| e.into_inner()) = Some(Box::new(hook));
+}
+
+/// Removes the hook set by `set_error_hook`.
+pub fn clear_error_hook() {
+    *HOOK.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+///passes [e] to the hook, if one is set
+#[track_caller]
+pub(crate) fn report(e: &(dyn Error + 'static)) {
+    if let Some(hook) = HOOK.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        hook(e, Location::caller());
+    }
+}
+
+///passes an error made from the text of [event] to the hook, if one is set
+#[track_caller]
+pub(crate) fn report_display<T: fmt::Display + ?Sized>(event: &T) {
+    if let Some(hook) = HOOK.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        let e: crate::DynError = event.to_string().into();
+        hook(&*e, Location::caller());
+    }
+}
+
+/// Passes the error a `check!` policy fell back from to the error hook.
+///
+/// not meant to be used on its own. used by `check!`
+#[doc(hidden)]
+#[track_caller]
+pub fn __report_error(e: &(dyn Error + 'static)) {
+    report(e)
+}
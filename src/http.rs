@@ -0,0 +1,166 @@
+//! Mapping errors to HTTP status codes.
+//!
+//! implement `HttpStatus` on your error then `register_status::<YourError>()` once at startup.\
+//! `dyn_to_status` then picks the status of any `DynError`, falling back to sensible defaults for
+//! io, parse and timeout errors and 500 for everything else.
+//!
+//! only available with the `http` feature.
+//!
+//! an adapter for a web framework is a `dynmatch!` away. with axum, for example:
+//! ```rust,ignore
+//! struct ApiError(DynError);
+//!
+//! impl IntoResponse for ApiError {
+//!     fn into_response(self) -> Response {
+//!         let status = StatusCode::from_u16(http::dyn_to_status(&self.0)).unwrap();
+//!         let body = dynmatch!(self.0,
+//!             type ValidationError {
+//!                 arm ValidationError::Field(name) => format!("invalid field: {}", name),
+//!                 _ => self.0.to_string()
+//!             },
+//!             _ => "internal error".to_string()
+//!         );
+//!         (status, body).into_response()
+//!     }
+//! }
+//! ```
+
+use std::error::Error;
+use std::io::ErrorKind;
+
+use crate::{DynError, __DowncastThrough};
+use crate::registry::Registry;
+
+/// Implemented by error types that know which HTTP status they map to.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# use std::{fmt, error};
+/// #[derive(Debug)]
+/// enum ApiError {
+///     NoSuchUser,
+///     Banned,
+/// }
+///# impl fmt::Display for ApiError {
+///#     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///#         write!(f, "{:?}", self)
+///#     }
+///# }
+///# impl error::Error for ApiError {}
+/// impl http::HttpStatus for ApiError {
+///     fn status(&self) -> u16 {
+///         match self {
+///             ApiError::NoSuchUser    => 404,
+///             ApiError::Banned        => 403,
+///         }
+///     }
+/// }
+///# fn main() {
+/// http::register_status::<ApiError>();
+/// let e: DynError = Box::new(ApiError::Banned);
+/// assert_eq!(http::dyn_to_status(&e), 403);
+///# }
+/// ```
+pub trait HttpStatus {
+    ///the status code this error should be reported with. defaults to 500
+    fn status(&self) -> u16 {
+        500
+    }
+}
+
+///a downcasting lookup for one registered type
+type Mapper = fn(&(dyn Error + 'static)) -> Option<u16>;
+
+///every registered mapper
+static MAPPERS: Registry<Mapper> = Registry::new();
+
+///downcasts to [T] and asks it for its status
+fn map<T: Error + HttpStatus + 'static>(e: &(dyn Error + 'static)) -> Option<u16> {
+    e.__downcast_through::<T>().map(HttpStatus::status)
+}
+
+/// Registers [T] so `dyn_to_status()` uses its `HttpStatus` impl.
+///
+/// registered types take precedence over the default mappings, so this also overrides them for std types wrapped in your own.\
+/// registering the same type twice has no effect.
+pub fn register_status<T: Error + HttpStatus + 'static>() {
+    MAPPERS.register::<T>(map::<T>);
+}
+
+/// Overrides the status of every error of type [T], including std types.
+///
+/// overriding a type again replaces its status.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// http::override_status::<std::num::ParseIntError>(|_| 422);
+/// let e: DynError = "x".parse::<u32>().unwrap_err().into();
+/// assert_eq!(http::dyn_to_status(&e), 422);
+///# }
+/// ```
+pub fn override_status<T: Error + 'static>(status: fn(&T) -> u16) {
+    OVERRIDES.register::<T>(Box::new(move |e| e.__downcast_through::<T>().map(status)));
+}
+
+///a lookup for one overridden type
+type Override = Box<dyn Fn(&(dyn Error + 'static)) -> Option<u16> + Send + Sync>;
+
+///every override, in the order their types were first overridden
+static OVERRIDES: Registry<Override> = Registry::new();
+
+/// Returns the HTTP status code [e] should be reported with.
+///
+/// checks overrides (latest first), then registered `HttpStatus` types, then the types in `errors`, then the defaults:
+/// - io errors by kind: 404 not found, 403 permission denied, 400 invalid input or data, 504 timed out, 409 already exists, 503 for connection errors
+/// - failures to parse numbers, bools, chars and utf8: 400
+/// - anything else: 500
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// let e: DynError = Box::new(std::io::Error::from(std::io::ErrorKind::NotFound));
+/// assert_eq!(http::dyn_to_status(&e), 404);
+/// assert_eq!(http::dyn_to_status(&DynError::from("oops")), 500);
+///# }
+/// ```
+pub fn dyn_to_status(e: &DynError) -> u16 {
+    let e: &(dyn Error + 'static) = &**e;
+    let overridden = OVERRIDES.read()
+        .iter()
+        .rev()
+        .find_map(|(_, o)| o(e));
+    overridden
+        .or_else(|| MAPPERS.find_map(|m| m(e)))
+        .or_else(|| crate::errors::status(e))
+        .unwrap_or_else(|| default_status(e))
+}
+
+///the status of common std errors
+fn default_status(e: &(dyn Error + 'static)) -> u16 {
+    if let Some(e) = e.__downcast_through::<std::io::Error>() {
+        return match e.kind() {
+            ErrorKind::NotFound             => 404,
+            ErrorKind::PermissionDenied     => 403,
+            ErrorKind::InvalidInput
+            | ErrorKind::InvalidData        => 400,
+            ErrorKind::TimedOut             => 504,
+            ErrorKind::AlreadyExists        => 409,
+            ErrorKind::ConnectionRefused
+            | ErrorKind::ConnectionReset
+            | ErrorKind::ConnectionAborted
+            | ErrorKind::NotConnected       => 503,
+            _                               => 500,
+        }
+    }
+    let bad_input = e.__downcast_through::<std::num::ParseIntError>().is_some()
+        || e.__downcast_through::<std::num::ParseFloatError>().is_some()
+        || e.__downcast_through::<std::str::ParseBoolError>().is_some()
+        || e.__downcast_through::<std::char::ParseCharError>().is_some()
+        || e.__downcast_through::<std::str::Utf8Error>().is_some()
+        || e.__downcast_through::<std::string::FromUtf8Error>().is_some();
+    if bad_input {400} else {500}
+}
@@ -0,0 +1,161 @@
+//! `Encoding::InternedJson`, which writes strings repeated across records once and refers to them by number.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::sync::Mutex;
+
+use crate::LogRecord;
+use crate::format::{write_json, write_json_string};
+
+///strings shorter than this are always written out, a reference wouldnt be any shorter
+const MIN_LEN: usize = 12;
+///how many strings seen only once are remembered per file before they are forgotten
+const MAX_SEEN: usize = 4096;
+///how many strings a file defines before it starts a new dictionary, reusing the numbers of the old one
+const MAX_IDS: usize = 4096;
+
+///the strings of one file
+#[derive(Default)]
+struct Interner {
+    ///strings written out once, which are interned the next time they come up
+    seen: HashSet<String>,
+    ///strings defined in the file, by the number they are referred to with
+    ids: HashMap<String, u64>,
+}
+
+///the interner of every file written with `Encoding::InternedJson`
+static INTERNERS: Mutex<Vec<(String, Interner)>> = Mutex::new(Vec::new());
+
+///encodes [records] for [log_file], each ending in a newline and preceded by lines defining the strings it refers to.
+///a [fresh] file starts a new dictionary, since the lines defining the old one are gone
+pub(crate) fn encode(records: &[&LogRecord], log_file: &str, fresh: bool) -> String {
+    let mut interners = INTERNERS.lock().unwrap_or_else(|e| e.into_inner());
+    let index = match interners.iter().position(|(path, _)| path == log_file) {
+        Some(index) => index,
+        None => {
+            interners.push((log_file.to_string(), Interner::default()));
+            interners.len() - 1
+        }
+    };
+    let interner = &mut interners[index].1;
+    if fresh {*interner = Interner::default()}
+    let mut text = String::new();
+    for record in records {
+        if interner.ids.len() >= MAX_IDS {*interner = Interner::default()}
+        let mut definitions = String::new();
+        let mut line = String::new();
+        let _ = write_json(&mut line, record, &mut |f, s| {
+            if s.len() < MIN_LEN {return write_json_string(f, s)}
+            if let Some(id) = interner.ids.get(s) {return write!(f, "{{\"ref\":{}}}", id)}
+            if interner.seen.remove(s) {
+                let id = interner.ids.len() as u64;
+                write!(definitions, "{{\"intern\":{},\"text\":", id)?;
+                write_json_string(&mut definitions, s)?;
+                definitions.push_str("}\n");
+                interner.ids.insert(s.to_string(), id);
+                return write!(f, "{{\"ref\":{}}}", id)
+            }
+            if interner.seen.len() >= MAX_SEEN {interner.seen.clear()}
+            interner.seen.insert(s.to_string());
+            write_json_string(f, s)
+        });
+        text.push_str(&definitions);
+        text.push_str(&line);
+        text.push('\n');
+    }
+    text
+}
+
+///starts a new dictionary for [log_file], after a write that may not have reached it
+pub(crate) fn forget(log_file: &str) {
+    INTERNERS.lock().unwrap_or_else(|e| e.into_inner()).retain(|(path, _)| path != log_file);
+}
+
+/// Turns a log written with `Encoding::InternedJson` back into one `JsonFormat` line per record.
+///
+/// the lines defining strings are dropped and every reference is replaced by the string it refers to.
+/// a number can be defined again further down, after the file started a new dictionary, and refers to its latest definition.
+/// a reference to a string that was never defined is left as it is.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+///# clean!("ingest.json");
+/// Logger::builder()
+///     .encoding("ingest.json", Encoding::InternedJson)
+///     .init();
+/// for _ in 0..3 {
+///     log_warn!("connection to the database lost", "ingest.json");
+/// }
+/// let interned = std::fs::read_to_string("ingest.json").unwrap();
+/// assert_eq!(interned.matches("connection to the database lost").count(), 2); //logged once, then defined once
+/// let expanded = expand_interned(&interned);
+/// assert_eq!(expanded.lines().count(), 3);
+/// assert!(expanded.lines().all(|line| line.contains(r#""message":"connection to the database lost""#)));
+///# clean!("ingest.json");
+///# init();
+///# }
+/// ```
+pub fn expand_interned(text: &str) -> String {
+    let mut strings: HashMap<u64, &str> = HashMap::new();
+    let mut expanded = String::new();
+    for line in text.lines() {
+        if let Some(rest) = line.strip_prefix("{\"intern\":") {
+            if let Some((id, string)) = rest.split_once(",\"text\":") {
+                if let (Ok(id), Some(string)) = (id.parse(), string.strip_suffix('}')) {
+                    strings.insert(id, string);
+                }
+            }
+            continue
+        }
+        let mut rest = line;
+        while let Some(start) = rest.find("{\"ref\":") {
+            let after = &rest[start + 7..];
+            let Some(end) = after.find('}') else {break};
+            expanded.push_str(&rest[..start]);
+            match after[..end].parse().ok().and_then(|id: u64| strings.get(&id)) {
+                Some(string) => expanded.push_str(string),
+                None => expanded.push_str(&rest[start..start + 8 + end]),
+            }
+            rest = &after[end + 1..];
+        }
+        expanded.push_str(rest);
+        expanded.push('\n');
+    }
+    expanded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{JsonFormat, Level};
+
+    #[test]
+    fn expands_to_json() {
+        let records: Vec<LogRecord> = (0..10)
+            .map(|i| LogRecord::new(Level::Info, "request handled in time").with_field("route", ["/api/v1/users", "/api/v1/orders"][i % 2]))
+            .collect();
+        let records: Vec<&LogRecord> = records.iter().collect();
+        let interned = encode(&records, "expands_to_json.log", true);
+        let json: String = records.iter().map(|r| JsonFormat::new().format(r) + "\n").collect();
+        assert!(interned.len() < json.len());
+        assert_eq!(expand_interned(&interned), json);
+    }
+
+    #[test]
+    fn new_dictionaries() {
+        let records: Vec<LogRecord> = (0..MAX_IDS + 10)
+            .flat_map(|i| [0, 1].map(|_| LogRecord::new(Level::Info, format!("message number {:05}", i))))
+            .collect();
+        let records: Vec<&LogRecord> = records.iter().collect();
+        let mut interned = encode(&records[..4], "new_dictionaries.log", true);
+        forget("new_dictionaries.log");
+        interned += &encode(&records[4..], "new_dictionaries.log", false);
+        let json: String = records.iter().map(|r| JsonFormat::new().format(r) + "\n").collect();
+        assert_eq!(expand_interned(&interned), json);
+        let interners = INTERNERS.lock().unwrap_or_else(|e| e.into_inner());
+        let (_, interner) = interners.iter().find(|(path, _)| path == "new_dictionaries.log").unwrap();
+        assert!(interner.ids.len() <= MAX_IDS);
+    }
+}
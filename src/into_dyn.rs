@@ -0,0 +1,48 @@
+//! Turning the error of any result `check!` is given into a `DynError`.
+//!
+//! errors that convert into a `DynError` keep their type so they can still be downcast.
+//! anything else that implements `Display` is kept as its text.\
+//! the choice is made with autoref method resolution: `(&&wrapper).__to_dyn()` finds `__ViaInto` first
+//! if its bounds hold, and falls back to `__ViaDisplay` one dereference later.
+
+use std::cell::Cell;
+use std::fmt;
+
+use crate::DynError;
+
+///an error being converted by `__into_dyn!`
+#[doc(hidden)]
+pub struct __IntoDyn<E>(pub Cell<Option<E>>);
+
+#[doc(hidden)]
+pub trait __ViaInto {
+    fn __to_dyn(&self) -> DynError;
+}
+
+impl<E: Into<DynError>> __ViaInto for &__IntoDyn<E> {
+    fn __to_dyn(&self) -> DynError {
+        self.0.take().expect("Dynerr: error converted twice").into()
+    }
+}
+
+#[doc(hidden)]
+pub trait __ViaDisplay {
+    fn __to_dyn(&self) -> DynError;
+}
+
+impl<E: fmt::Display> __ViaDisplay for __IntoDyn<E> {
+    fn __to_dyn(&self) -> DynError {
+        self.0.take().expect("Dynerr: error converted twice").to_string().into()
+    }
+}
+
+///converts [e] into a `DynError`, keeping its type if it has an `Into<DynError>` conversion and its text otherwise
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __into_dyn {
+    ($e:expr) => {{
+        #[allow(unused_imports)]
+        use $crate::{__ViaInto as _, __ViaDisplay as _};
+        (&&$crate::__IntoDyn(::std::cell::Cell::new(::std::option::Option::Some($e)))).__to_dyn()
+    }};
+}
@@ -0,0 +1,114 @@
+//! Joining threads that return results.
+
+use std::any::Any;
+use std::error::Error;
+use std::fmt;
+use std::thread::JoinHandle;
+
+use crate::{Level, LogFiles, MultiError, Relax};
+
+/// A thread that panicked instead of returning. made by `join_checked!`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PanicError {
+    message: String,
+    thread: Option<String>,
+}
+
+impl PanicError {
+    ///the error for a thread named [thread] that panicked with [payload]
+    pub fn new(payload: Box<dyn Any + Send>, thread: Option<&str>) -> Self {
+        let message = payload.downcast_ref::<&str>().map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Box<dyn Any>".to_string());
+        Self {message, thread: thread.map(str::to_string)}
+    }
+
+    ///the panic message
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    ///the name of the thread that panicked, if it had one
+    pub fn thread(&self) -> Option<&str> {
+        self.thread.as_deref()
+    }
+}
+
+impl fmt::Display for PanicError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.thread {
+            Some(thread) => write!(f, "thread '{}' panicked: {}", thread, self.message),
+            None => write!(f, "thread panicked: {}", self.message),
+        }
+    }
+}
+
+impl Error for PanicError {}
+
+/// Joins [handle], logging and collecting its error or panic in [errors].
+///
+/// not meant to be used on its own. use `join_checked!` instead
+pub fn __join_one<T, E: Relax, L: LogFiles>(handle: JoinHandle<Result<T, E>>, errors: &mut MultiError, log_file: L) -> Option<T> {
+    let thread = handle.thread().name().map(str::to_string);
+    let e = match handle.join() {
+        Ok(Ok(v)) => return Some(v),
+        Ok(Err(e)) => e.relax(),
+        Err(payload) => Box::new(PanicError::new(payload, thread.as_deref())),
+    };
+    crate::log_level(crate::LogText(&*e), Level::Error, log_file);
+    errors.push(e);
+    None
+}
+
+/// Joins every thread, returning a tuple of their values or a `MultiError` of everything that went wrong.
+///
+/// each thread returns a `Result` whose error is one `relax!` accepts, like `Box<dyn Error + Send + Sync>`.\
+/// threads that panic become a `PanicError`. every failure is logged at `Level::Error` as it is joined.\
+/// all threads are joined even if one failed. the log file goes after a `;`.\
+/// If no file supplied then defaults to `default_log()` (normally "event.log").
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# use std::error::Error;
+///# use std::thread;
+/// type Task<T> = Result<T, Box<dyn Error + Send + Sync>>;
+///# fn main() {
+/// let users = thread::spawn(|| -> Task<u32> {Ok("12".parse()?)});
+/// let name = thread::spawn(|| -> Task<String> {Ok("ann".to_string())});
+/// let (users, name) = join_checked!(users, name; "test.log").unwrap();
+/// assert_eq!((users, name.as_str()), (12, "ann"));
+///
+/// let parse = thread::spawn(|| -> Task<u32> {Ok("x".parse()?)});
+/// let crash = thread::Builder::new().name("crash".into()).spawn(|| -> Task<()> {panic!("out of range")}).unwrap();
+/// let errors = join_checked!(parse, crash; "test.log").unwrap_err();
+/// assert_eq!(errors.len(), 2);
+/// assert_eq!(errors.iter().nth(1).unwrap().to_string(), "thread 'crash' panicked: out of range");
+///# }
+/// ```
+#[macro_export]
+macro_rules! join_checked {
+    ($($handle:expr),+ $(,)?) => {
+        $crate::join_checked!($($handle),+; &$crate::default_log())
+    };
+    ($($handle:expr),+ ; $log:expr) => {{
+        let mut errors = $crate::MultiError::new();
+        $crate::__join_checked!((errors, $log) [] $($handle,)+)
+    }};
+}
+
+///joins the handles of `join_checked!` one at a time. each `value` binding is a separate variable thanks to hygiene
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __join_checked {
+    (($errors:ident, $log:expr) [$($value:ident)*]) => {
+        match $errors.into_result() {
+            Ok(()) => Ok(($($value.unwrap(),)*)),
+            Err(e) => Err(e),
+        }
+    };
+    (($errors:ident, $log:expr) [$($value:ident)*] $handle:expr, $($rest:expr,)*) => {{
+        let value = $crate::__join_one($handle, &mut $errors, $log);
+        $crate::__join_checked!(($errors, $log) [$($value)* value] $($rest,)*)
+    }};
+}
@@ -0,0 +1,218 @@
+//! Symbolic error kinds and the config driven policies attached to them.
+//!
+//! `register_kind::<YourError>("kind")` names a type once at startup.\
+//! operators then decide what happens to each kind in a config file loaded with `KindPolicies::load`,
+//! without recompiling.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::path::Path;
+
+use crate::{DynResult, Severity};
+use crate::registry::Registry;
+
+/// What to do with errors of a kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KindPolicy {
+    ///treat the error as `Severity::Fatal`
+    Fatal,
+    ///retry the failed operation up to this many times in `check!(x; on_err = configured ..)`
+    Retry(u32),
+    ///treat the error as `Severity::Ignorable`
+    Ignore,
+}
+
+impl From<KindPolicy> for Severity {
+    fn from(policy: KindPolicy) -> Self {
+        match policy {
+            KindPolicy::Fatal       => Severity::Fatal,
+            KindPolicy::Retry(_)    => Severity::Recoverable,
+            KindPolicy::Ignore      => Severity::Ignorable,
+        }
+    }
+}
+
+///a downcasting check for one registered type and the kind it maps to
+type Namer = (fn(&(dyn Error + 'static)) -> bool, &'static str);
+
+///every registered type
+static KINDS: Registry<Namer> = Registry::new();
+
+///whether [e] is or wraps a [T]
+fn is<T: Error + 'static>(e: &(dyn Error + 'static)) -> bool {
+    use crate::__DowncastThrough;
+    e.__downcast_through::<T>().is_some()
+}
+
+/// Registers [kind] as the symbolic kind of every error of type [T].
+///
+/// registering a type again replaces its kind.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// register_kind::<std::num::ParseIntError>("bad_number");
+/// let e: DynError = "x".parse::<u32>().unwrap_err().into();
+/// assert_eq!(error_kind(&*e), Some("bad_number"));
+///# }
+/// ```
+pub fn register_kind<T: Error + 'static>(kind: &'static str) {
+    KINDS.register::<T>((is::<T>, kind));
+}
+
+/// Returns the kind registered for the type of [e], if any.
+/// 
+/// the types in `errors` have the kinds `not_found`, `timeout`, `invalid_input`, `unsupported`, `unavailable` and `cancelled`
+/// unless registered otherwise.
+pub fn error_kind(e: &(dyn Error + 'static)) -> Option<&'static str> {
+    KINDS.find_map(|(check, kind)| check(e).then_some(*kind))
+        .or_else(|| crate::errors::kind(e))
+}
+
+/// Returns the policy configured for the kind of [e], if any.
+///
+/// consulted by `severity()`, and so by `check!(x, or: default)`, and by the `configured` step of `check!(x; on_err = ..)`.
+pub fn kind_policy(e: &(dyn Error + 'static)) -> Option<KindPolicy> {
+    let kind = error_kind(e)?;
+    crate::config::config().kind_policies.get(kind)
+}
+
+/// The policy of every configured kind. installed with `LoggerBuilder::kind_policies`.
+///
+/// parsed from a small subset of TOML:
+/// ```toml
+/// fatal = ["disk_full", "corrupt_save"]
+/// ignore = ["cache_miss"]
+///
+/// [retry]
+/// busy = 3
+/// ```
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# #[derive(Debug)]
+///# struct Busy;
+///# impl std::fmt::Display for Busy {
+///#     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+///#         write!(f, "busy")
+///#     }
+///# }
+///# impl std::error::Error for Busy {}
+///# fn main() -> DynResult<()> {
+/// register_kind::<Busy>("busy");
+/// let policies = "[retry]\nbusy = 2".parse::<KindPolicies>()?;
+/// Logger::builder().kind_policies(policies).init();
+///
+/// let mut attempts = 0;
+/// let i = check!({attempts += 1; if attempts < 3 {Err(Busy)} else {Ok(7)}}, "test.log"; on_err = configured);
+/// assert_eq!(i, 7);
+///# init();
+///# Ok(())
+///# }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KindPolicies(HashMap<String, KindPolicy>);
+
+impl KindPolicies {
+    ///no policies
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///reads and parses the config file at [path]
+    pub fn load<P: AsRef<Path>>(path: P) -> DynResult<Self> {
+        Ok(std::fs::read_to_string(path)?.parse()?)
+    }
+
+    ///sets the policy of [kind]
+    pub fn set<K: Into<String>>(mut self, kind: K, policy: KindPolicy) -> Self {
+        self.0.insert(kind.into(), policy);
+        self
+    }
+
+    ///the policy of [kind]
+    pub fn get(&self, kind: &str) -> Option<KindPolicy> {
+        self.0.get(kind).copied()
+    }
+}
+
+/// Error returned when a kind config file cant be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseKindsError {
+    pub line: String,
+    pub reason: &'static str,
+}
+
+impl fmt::Display for ParseKindsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Dynerr: invalid kind config ({}): {}", self.reason, self.line)
+    }
+}
+
+impl Error for ParseKindsError {}
+
+impl std::str::FromStr for KindPolicies {
+    type Err = ParseKindsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut policies = Self::new();
+        let mut retry_table = false;
+        for raw in s.lines() {
+            let fail = |reason| ParseKindsError {line: raw.to_string(), reason};
+            let line = raw.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {continue}
+            if let Some(table) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                retry_table = match table.trim() {
+                    "retry" => true,
+                    _ => return Err(fail("unknown table")),
+                };
+                continue
+            }
+            let (key, value) = line.split_once('=').ok_or_else(|| fail("expected key = value"))?;
+            let (key, value) = (unquote(key.trim()), value.trim());
+            if retry_table {
+                let times = value.parse().map_err(|_| fail("retry count must be a number"))?;
+                policies.0.insert(key.to_string(), KindPolicy::Retry(times));
+                continue
+            }
+            let policy = match key {
+                "fatal"     => KindPolicy::Fatal,
+                "ignore"    => KindPolicy::Ignore,
+                _ => return Err(fail("unknown key")),
+            };
+            let list = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')).ok_or_else(|| fail("expected a list of kinds"))?;
+            for kind in list.split(',').map(str::trim).filter(|k| !k.is_empty()) {
+                if !kind.starts_with('"') || !kind.ends_with('"') || kind.len() < 2 {
+                    return Err(fail("kinds must be quoted"))
+                }
+                policies.0.insert(unquote(kind).to_string(), policy);
+            }
+        }
+        Ok(policies)
+    }
+}
+
+///strips the quotes around a TOML string
+fn unquote(s: &str) -> &str {
+    s.strip_prefix('"').and_then(|s| s.strip_suffix('"')).unwrap_or(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let policies: KindPolicies = "# handling\nfatal = [\"disk_full\", \"corrupt\"]\nignore = [\"cache_miss\"]\n\n[retry]\n\"net.busy\" = 3\n"
+            .parse().unwrap();
+        assert_eq!(policies.get("corrupt"), Some(KindPolicy::Fatal));
+        assert_eq!(policies.get("cache_miss"), Some(KindPolicy::Ignore));
+        assert_eq!(policies.get("net.busy"), Some(KindPolicy::Retry(3)));
+        assert_eq!(policies.get("other"), None);
+        assert_eq!("fatal = [disk_full]".parse::<KindPolicies>().unwrap_err().reason, "kinds must be quoted");
+        assert_eq!("[fatal]".parse::<KindPolicies>().unwrap_err().reason, "unknown table");
+    }
+}
@@ -0,0 +1,83 @@
+//! Severity levels attached to every logged event.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// The severity of a logged event.
+/// 
+/// levels are ordered from most to least severe, so `Level::Error < Level::Debug`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+/// The least severe level that is compiled in, set with the `max_level_*` features. `None` with `max_level_off`.
+/// 
+/// records below it are dropped before they are formatted, and the checks compile away.
+/// when several features are enabled the most restrictive one wins, so `--all-features` means `max_level_off`.
+/// the features are meant for binaries, and libraries shouldnt enable them.
+pub const STATIC_MAX_LEVEL: Option<Level> = 
+    if cfg!(feature = "max_level_off") {None}
+    else if cfg!(feature = "max_level_error") {Some(Level::Error)}
+    else if cfg!(feature = "max_level_warn") {Some(Level::Warn)}
+    else if cfg!(feature = "max_level_info") {Some(Level::Info)}
+    else if cfg!(feature = "max_level_debug") {Some(Level::Debug)}
+    else {Some(Level::Trace)};
+
+impl Level {
+    ///every level, from most to least severe
+    pub const ALL: [Level; 5] = [Level::Error, Level::Warn, Level::Info, Level::Debug, Level::Trace];
+
+    ///whether records at this level are kept by `STATIC_MAX_LEVEL`
+    pub const fn is_compiled_in(self) -> bool {
+        match STATIC_MAX_LEVEL {
+            Some(max) => self as u8 <= max as u8,
+            None => false,
+        }
+    }
+
+    ///the uppercase name of the level
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Level::Error    => "ERROR",
+            Level::Warn     => "WARN",
+            Level::Info     => "INFO",
+            Level::Debug    => "DEBUG",
+            Level::Trace    => "TRACE",
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+/// Error returned when parsing an unknown level name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseLevelError(pub String);
+
+impl fmt::Display for ParseLevelError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Dynerr: unknown log level: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseLevelError {}
+
+impl FromStr for Level {
+    type Err = ParseLevelError;
+
+    ///parses a level name, ignoring case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Level::ALL.iter()
+            .find(|l| l.as_str().eq_ignore_ascii_case(s.trim()))
+            .copied()
+            .ok_or_else(|| ParseLevelError(s.to_string()))
+    }
+}
@@ -6,10 +6,173 @@
 
 
 
-use std::fmt;
 use std::path::Path;
-use std::fs::{OpenOptions, remove_file};
-use std::io::prelude::*;
+use std::fs::remove_file;
+
+pub mod watchdog;
+pub mod errors;
+pub mod redact;
+pub mod summary;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "http")]
+pub mod http;
+#[cfg(all(unix, feature = "signals"))]
+pub mod signals;
+mod level;
+mod config;
+mod record;
+mod format;
+mod redirect;
+mod severity;
+mod multi;
+mod funnel;
+mod scope;
+mod panic;
+mod assert;
+mod clock;
+mod writer;
+mod files;
+mod attach;
+mod capture;
+mod kind;
+mod startup;
+mod relax;
+mod session;
+mod unwrap;
+mod help;
+mod registered;
+mod audit;
+mod traced;
+mod deadline;
+mod diff;
+mod checker;
+mod event;
+mod crash;
+mod hook;
+mod budget;
+mod borrowed;
+mod join;
+mod lossless;
+mod log_display;
+mod conformance;
+mod dedup;
+mod provide;
+mod deferred;
+mod logged;
+mod error_id;
+mod quantity;
+mod middleware;
+mod stopwatch;
+mod into_dyn;
+mod display_hint;
+mod exit_summary;
+mod escalate;
+mod small;
+mod collect;
+mod catalog;
+mod cancel;
+mod timeout;
+mod context;
+mod error_stats;
+mod cached;
+mod intern;
+mod setup;
+mod equal;
+mod registry;
+
+pub use level::{Level, ParseLevelError, STATIC_MAX_LEVEL};
+pub use config::{CapAction, CheckFailure, Durability, LineEnding, Logger, LoggerBuilder, init, is_initialized};
+pub use record::{LogRecord, LogReader, ParseRecordError};
+pub use middleware::{register_middleware, clear_middleware, apply_middleware, __Raise};
+pub use quantity::{Quantity, Elapsed, Bytes, Throughput};
+pub use format::{LevelMarkers, TextFormat, JsonFormat, LogfmtFormat, CsvFormat, Encoding};
+pub use redirect::{DEFAULT_LOG, default_log, redirect_log, with_temp_log, RedirectGuard};
+pub use severity::{Severity, ErrorSeverity, register_severity, severity, check_recover};
+pub use multi::MultiError;
+pub use join::{PanicError, __join_one};
+pub use lossless::Lossless;
+pub use conformance::conformance_problems;
+pub use deferred::Deferred;
+pub use logged::{CallInfo, __logged_entry, __logged_result};
+/// Logs the errors a function returning `DynResult` propagates, along with the function's name and arguments.
+///
+/// the arguments are Debug formatted. leave any out with `skip(..)`, and log every call with `entry`.
+/// the error is logged to `default_log()` once, by the innermost `#[logged]` function it passes through,
+/// and is returned with a `CallInfo` attached. errors whose `severity()` is `Severity::Ignorable`, like `errors::Cancelled`, arent logged.\
+/// only available with the `attributes` feature.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+/// #[logged(skip(password))]
+/// fn connect(user: &str, password: &str) -> DynResult<u32> {
+///     std::fs::File::open("none")?;
+///     Ok(password.len() as u32)
+/// }
+///
+/// #[logged(entry)]
+/// fn start(attempts: u8) -> DynResult<u32> {
+///     connect("admin", "hunter2")
+/// }
+///# fn main() {
+/// let e = start(3).unwrap_err();
+/// let call = e.get_attached::<CallInfo>().unwrap();
+/// assert_eq!(call.function, "connect");
+/// assert_eq!(call.args, "user=\"admin\"");
+///# }
+/// ```
+#[cfg(feature = "attributes")]
+pub use dynerr_attributes::logged;
+pub use provide::{Provide, Request, register_provider, request_ref};
+pub use log_display::{LogDisplay, LogText, register_log_display, __log_error};
+pub use funnel::ErrorFunnel;
+pub use scope::{enter_scope, current_scopes, ScopeGuard};
+pub use stopwatch::TimeScope;
+pub use exit_summary::{ExitSummary, RunStatus, IntoExitSummary, EXIT_SUMMARY_VAR};
+pub use into_dyn::{__IntoDyn, __ViaInto, __ViaDisplay};
+pub use panic::{log_and_panic, log_and_panic_with_context, check_failed, check_failed_hint, install_panic_hook};
+pub use assert::AssertionError;
+pub use clock::{Clock, SystemClock, FakeClock};
+pub use writer::{recent_events, subscribe, failed_over_logs, capped_logs};
+pub(crate) use writer::{write_record, try_write_record};
+pub use files::{LogFiles, LogTarget};
+pub use escalate::Escalated;
+pub use small::{SmallDynError, SmallResult};
+pub use collect::__CheckCollector;
+pub use catalog::{CatalogEntry, CatalogError};
+pub use cancel::{Cancellation, CancelToken};
+pub use timeout::__with_timeout;
+#[cfg(feature = "async")]
+pub use timeout::TimeoutFuture;
+pub use context::{LogContext, ContextGuard, spawn_logged};
+pub use error_stats::{ErrorStats, TypeStats, error_stats, reset_error_stats};
+#[cfg(feature = "async")]
+pub use context::{logged_task, LoggedTask};
+pub use cached::CachedError;
+pub use intern::expand_interned;
+pub use equal::{errors_equal, errors_equal_as, chains_equal};
+pub use setup::{setup_basic_cli_logging, setup_service_logging, SERVICE_CRASH_LOG, SERVICE_CRASH_MARKER};
+pub use attach::{Attach, Attached, __DowncastThrough, __Peeled, __downcast_matched, __downcast_source, __text_matches};
+pub use capture::capture_bundle;
+pub use startup::log_startup_info;
+pub use relax::{Relax, DetachedError, SharedDynError, Share};
+pub use session::{session_logs, clean_session, clean_all, clean_matching};
+pub use unwrap::LoggedUnwrap;
+pub use help::{Help, register_help, help};
+pub use audit::{AuditError, verify_log};
+pub use traced::{Traced, TracedError};
+pub use deadline::{deadline_exceeded, __parse_duration};
+pub use diff::{diff_lines, log_diff};
+pub use checker::Checker;
+pub use redact::Redactor;
+pub use event::LogEvent;
+pub use crash::{CrashInfo, previous_crash};
+pub use hook::{set_error_hook, clear_error_hook, __report_error};
+pub use budget::ErrorBudget;
+pub use borrowed::{DynErrorRef, DynResultRef, Classify};
+pub use error_id::{register_error_id, error_id};
+pub use kind::{KindPolicy, KindPolicies, ParseKindsError, register_kind, error_kind, kind_policy};
 
 ///type alias for an error returned by `dynerr!` and `DynResult<T>`
 pub type DynError = Box<dyn std::error::Error>;
@@ -55,6 +218,11 @@ pub type DynResult<T> = std::result::Result<T, DynError>;
 
 /// A macro for returning custom errors as DynError.
 /// 
+/// errors returned as a `DynError` first pass through any middleware registered with `register_middleware`.\
+/// `Send` and `Sync` boxes work too, but skip middleware since it takes and returns a `DynError`.
+/// so does a box of the error's own type, like `Box<MyError>`, which gets the error boxed as it is.
+/// errors that borrow data, returned as a `DynErrorRef`, are raised with `dynerr!(ref e)`.
+/// 
 ///# Example
 /// 
 /// ```rust
@@ -114,13 +282,22 @@ pub type DynResult<T> = std::result::Result<T, DynError>;
 /// ```
 #[macro_export]
 macro_rules! dynerr {
-    ($e:expr) => {return Err(Box::new($e))};
+    (ref $e:expr) => {return Err(Box::new($e))};
+    ($e:expr) => {return Err($crate::__Raise::__raise($e))};
 }
 
 /// Performs a dynamic match operation on multiple error types.
 /// 
 /// types must be specified beforehand with the "type" keyword.\
-/// match arms (excluding the final exhaustive arm) must be specified with the "arm" keyword. 
+/// match arms (excluding the final exhaustive arm) must be specified with the "arm" keyword.\
+/// a type block declared with "exhaustive type" has no `_` arm, so the compiler checks its arms cover every variant.
+/// 
+/// a `group [TypeA, TypeB] => code` block runs one handler for any of several types.\
+/// a `source type` block matches the first error of its type in the source chain, to drill into wrapped errors.\
+/// a `DynErrorRef` cant be downcast, so it is matched with `class` blocks instead, see `Classify`.
+/// 
+/// the error is evaluated once and only borrowed while looking for its type, so the `_` arms can take ownership of it.\
+/// `dynmatch!(let e = expr, ...)` binds an error that isnt in a variable yet, so the arms can use it too.
 /// 
 ///# Example
 /// ```rust
@@ -183,10 +360,9 @@ macro_rules! dynerr {
 ///                 arm ExampleError1::ThisError(2) => logged_panic!("it was 2!"),              //arm [pattern] => {code}
 ///                 _ => panic!("{}",e)                                                         //_ => {code}
 ///             },
-///             type ExampleError2 {
+///             exhaustive type ExampleError2 {                                                 //every variant handled, no _ arm
 ///                 arm ExampleError2::ThatError(8) => logged_panic!("it was 8!", "test.log"),
-///                 arm ExampleError2::ThatError(9) => 9,
-///                 _ => panic!("{}",e)
+///                 arm ExampleError2::ThatError(_) => 9
 ///             },
 ///             type std::io::Error {                                                           //an error type not defined by you
 ///                 arm i if i.kind() == std::io::ErrorKind::NotFound => 5,                      //a match guard included in the match
@@ -198,33 +374,315 @@ macro_rules! dynerr {
 /// };
 ///# }
 /// ```
+/// 
+/// handling several types the same way:
+/// ```rust
+///# use dynerr::*;
+/// fn read_port(text: &str) -> DynResult<u16> {
+///     Ok(text.trim().parse()?)
+/// }
+///# fn main() {
+/// let e = read_port("99999").unwrap_err();
+/// let status = dynmatch!(e,
+///     group [std::num::ParseIntError, std::num::ParseFloatError, std::str::Utf8Error] => 400,
+///     type std::io::Error {
+///         arm _ => 500,
+///         _ => 500
+///     },
+///     _ => 500
+/// );
+/// assert_eq!(status, 400);
+///# }
+/// ```
+/// 
+/// matching on the text of errors whose types are private, as a last resort.\
+/// `text` blocks match if the error's message contains any of the `|` separated alternatives, ignoring case:
+/// ```rust
+///# use dynerr::*;
+/// fn send() -> DynResult<()> {
+///     let e: DynError = "write failed: Connection reset by peer".into(); //an error from a library with private error types
+///     Err(e)
+/// }
+///# fn main() {
+/// let e = send().unwrap_err();
+/// let reconnect = dynmatch!(e,
+///     type std::io::Error {
+///         arm i if i.kind() == std::io::ErrorKind::ConnectionReset => true,
+///         _ => false
+///     },
+///     text "connection reset|broken pipe" => true,
+///     _ => false
+/// );
+/// assert!(reconnect);
+///# }
+/// ```
+/// 
+/// matching other kinds of error. anything that derefs to a `dyn Error`, `dyn Error + Send`, `dyn Error + Sync`
+/// or `dyn Error + Send + Sync` works, including boxes, `Arc`s and plain references:
+/// ```rust
+///# use dynerr::*;
+/// use std::error::Error;
+/// fn is_io(e: &(dyn Error + 'static)) -> bool {
+///     dynmatch!(e,
+///         type std::io::Error {
+///             arm _ => true,
+///             _ => true
+///         },
+///         _ => false
+///     )
+/// }
+///# fn main() {
+/// let e: Box<dyn Error + Send + Sync> = std::fs::File::open("none").unwrap_err().into();
+/// assert!(is_io(&*e));
+/// let found = dynmatch!(e,
+///     type std::io::Error {
+///         arm i if i.kind() == std::io::ErrorKind::NotFound => true,
+///         _ => false
+///     },
+///     _ => false
+/// );
+/// assert!(found);
+///# }
+/// ```
+/// 
+/// matching the source of a wrapping error without a second `dynmatch!`:
+/// ```rust
+///# use dynerr::*;
+///# use std::{error, fmt, io};
+/// #[derive(Debug)]
+/// enum ConfigError {
+///     Io(io::Error),
+///     Syntax(usize),
+/// }
+///# impl fmt::Display for ConfigError {
+///#     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///#         write!(f, "couldnt load the config")
+///#     }
+///# }
+/// impl error::Error for ConfigError {
+///     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+///         match self {
+///             ConfigError::Io(e) => Some(e),
+///             ConfigError::Syntax(_) => None,
+///         }
+///     }
+/// }
+///# fn main() {
+/// let e: DynError = Box::new(ConfigError::Io(io::Error::new(io::ErrorKind::NotFound, "config.toml")));
+/// let action = dynmatch!(e,
+///     source type io::Error {
+///         arm io if io.kind() == io::ErrorKind::NotFound => "write the default config",
+///         _ => "give up"
+///     },
+///     type ConfigError {
+///         arm ConfigError::Syntax(_) => "show the line",
+///         _ => "give up"
+///     },
+///     _ => "give up"
+/// );
+/// assert_eq!(action, "write the default config");
+///# }
+/// ```
+/// 
+/// translating the matched error into your own and returning it with `escalate!`, which is available in every arm.\
+/// the new error is wrapped in an `Escalated` whose source is a copy of the matched error:
+/// ```rust
+///# use dynerr::*;
+///# use std::{error::Error, fmt, io};
+/// #[derive(Debug, PartialEq)]
+/// enum SaveError {
+///     NoSpace,
+///     Other,
+/// }
+///# impl fmt::Display for SaveError {
+///#     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///#         write!(f, "couldnt save the game")
+///#     }
+///# }
+///# impl Error for SaveError {}
+/// fn write_save() -> DynResult<()> {
+///     dynerr!(io::Error::new(io::ErrorKind::StorageFull, "disk full"))
+/// }
+///
+/// fn save() -> DynResult<()> {
+///     if let Err(e) = write_save() {
+///         dynmatch!(e,
+///             type io::Error {
+///                 arm i if i.kind() == io::ErrorKind::StorageFull => escalate!(SaveError::NoSpace),
+///                 _ => escalate!(SaveError::Other)
+///             },
+///             _ => return Err(e)
+///         )
+///     }
+///     Ok(())
+/// }
+///# fn main() {
+/// let e = save().unwrap_err();
+/// assert_eq!(e.__downcast_through::<SaveError>(), Some(&SaveError::NoSpace));
+/// assert_eq!(e.source().unwrap().to_string(), "disk full");
+///# }
+/// ```
+/// 
+/// consuming the error in the fallback:
+/// ```rust
+///# use dynerr::*;
+/// fn parse(text: &str) -> DynResult<u32> {
+///     Ok(text.parse()?)
+/// }
+/// fn retry_later(e: DynError) -> u32 {
+///     log_warn!(e, "test.log");
+///     0
+/// }
+///# fn main() {
+/// let value = dynmatch!(let e = parse("x").unwrap_err(),
+///     type std::io::Error {
+///         arm _ => 1,
+///         _ => retry_later(e)
+///     },
+///     _ => retry_later(e)
+/// );
+/// assert_eq!(value, 0);
+///# }
+/// ```
 #[macro_export]
 macro_rules! dynmatch {
-    ($e:expr, $(type $ty:ty {$(arm $( $pattern:pat )|+ $( if $guard: expr )? => $result:expr),*, _ => $any:expr}),*, _ => $end:expr) => (
-        $(
-            if let Some(e) = $e.downcast_ref::<$ty>() {
-                match e {
-                    $(
-                        $( $pattern )|+ $( if $guard )? => {$result}
-                    )*
-                    _ => $any
-                }
-            } else
-        )*
+    (let $name:ident = $e:expr, $($blocks:tt)*) => ({
+        let $name = $e;
+        $crate::dynmatch!($name, $($blocks)*)
+    });
+    ($e:expr, class $($blocks:tt)*) => ({
+        //errors that arent 'static cant be downcast, so only `class` blocks are allowed
+        match &*$e {
+            __dynmatch_ref => $crate::__dynmatch_classes!((__dynmatch_ref) class $($blocks)*)
+        }
+    });
+    ($e:expr, $($blocks:tt)*) => ({
+        #[allow(unused_imports)]
+        use $crate::__DowncastThrough as _;
+        //a match evaluates [e] once and keeps a temporary alive for every block. the borrow ends before the fallback runs
+        match $e.__peel() {
+            (__dynmatch_outer, __dynmatch_inner) => $crate::__dynmatch_blocks!((__dynmatch_outer, __dynmatch_inner) $($blocks)*)
+        }
+    });
+}
+
+///expands the type blocks of `dynmatch!` one at a time.\
+///wrappers are peeled once up front. each type block compares the `TypeId` of its type with the error's and the innermost wrapped error's,
+///and only the block that matches downcasts, once.
+///an error wrapped more than once has every wrapper compared
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __dynmatch_blocks {
+    (($outer:ident, $inner:ident) _ => $end:expr $(,)?) => {
+        $crate::__dynmatch_arm!($outer, $end)
+    };
+    (($outer:ident, $inner:ident) type $ty:ty {$(arm $( $pattern:pat )|+ $( if $guard: expr )? => $result:expr),*, _ => $any:expr}, $($rest:tt)*) => {
+        if $inner.__is::<$ty>($outer) {
+            let e = $crate::__downcast_matched::<$ty>($outer, $inner);
+            match e {
+                $(
+                    $( $pattern )|+ $( if $guard )? => {$crate::__dynmatch_arm!(e, $result)}
+                )*
+                _ => {$crate::__dynmatch_arm!(e, $any)}
+            }
+        } else {
+            $crate::__dynmatch_blocks!(($outer, $inner) $($rest)*)
+        }
+    };
+    (($outer:ident, $inner:ident) group [$($ty:ty),+ $(,)?] => $result:expr, $($rest:tt)*) => {
+        if false $(|| $inner.__is::<$ty>($outer))+ {
+            $crate::__dynmatch_arm!($outer, $result)
+        } else {
+            $crate::__dynmatch_blocks!(($outer, $inner) $($rest)*)
+        }
+    };
+    (($outer:ident, $inner:ident) text $pattern:literal => $result:expr, $($rest:tt)*) => {
+        if $crate::__text_matches($outer, $inner, $pattern) {
+            $crate::__dynmatch_arm!($outer, $result)
+        } else {
+            $crate::__dynmatch_blocks!(($outer, $inner) $($rest)*)
+        }
+    };
+    (($outer:ident, $inner:ident) source type $ty:ty {$(arm $( $pattern:pat )|+ $( if $guard: expr )? => $result:expr),*, _ => $any:expr}, $($rest:tt)*) => {
+        if let Some(e) = $crate::__downcast_source::<$ty>($outer) {
+            match e {
+                $(
+                    $( $pattern )|+ $( if $guard )? => {$crate::__dynmatch_arm!(e, $result)}
+                )*
+                _ => {$crate::__dynmatch_arm!(e, $any)}
+            }
+        } else {
+            $crate::__dynmatch_blocks!(($outer, $inner) $($rest)*)
+        }
+    };
+    (($outer:ident, $inner:ident) exhaustive type $ty:ty {$(arm $( $pattern:pat )|+ $( if $guard: expr )? => $result:expr),+ $(,)?}, $($rest:tt)*) => {
+        if $inner.__is::<$ty>($outer) {
+            let e = $crate::__downcast_matched::<$ty>($outer, $inner);
+            match e {
+                $(
+                    $( $pattern )|+ $( if $guard )? => {$crate::__dynmatch_arm!(e, $result)}
+                )+
+            }
+        } else {
+            $crate::__dynmatch_blocks!(($outer, $inner) $($rest)*)
+        }
+    };
+}
+
+///expands a `dynmatch!` arm with a local `escalate!` that returns a new error sourced by [matched]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __dynmatch_arm {
+    //the `$` is passed in as a token so the local macro can declare its own metavariables
+    (($d:tt) $matched:ident, $result:expr) => {{
+        #[allow(unused_macros)]
+        macro_rules! escalate {
+            ($d new:expr) => {
+                return ::std::result::Result::Err(::std::convert::From::from($crate::Escalated::new($d new, $matched)))
+            };
+        }
+        $result
+    }};
+    ($matched:ident, $result:expr) => {
+        $crate::__dynmatch_arm!(($) $matched, $result)
+    };
+}
+
+///expands the `class` blocks of `dynmatch!` one at a time
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __dynmatch_classes {
+    (($e:ident) _ => $end:expr $(,)?) => {
         {$end}
-    );
+    };
+    (($e:ident) class $ty:ty {$(arm $( $pattern:pat )|+ $( if $guard: expr )? => $result:expr),*, _ => $any:expr}, $($rest:tt)*) => {
+        if let Some(class) = <$ty as $crate::Classify>::classify($e) {
+            match class {
+                $(
+                    $( $pattern )|+ $( if $guard )? => {$result}
+                )*
+                _ => $any
+            }
+        } else {
+            $crate::__dynmatch_classes!(($e) $($rest)*)
+        }
+    };
 }
 
-///deletes the supplied file
-pub fn clean_log(log_file: &str) {
-    if Path::new(log_file).exists() {
-        remove_file(log_file).unwrap_or_else(|e| panic!("Dynerr: Error cleaning file: {}", e))
+///deletes the supplied files
+pub fn clean_log<L: LogFiles>(log_file: L) {
+    for log_file in log_file.log_files() {
+        if Path::new(log_file).exists() {
+            remove_file(log_file).unwrap_or_else(|e| panic!("Dynerr: Error cleaning file: {}", e))
+        }
+        session::untrack(log_file);
+        audit::forget(log_file);
     }
 }
 
 /// deletes the supplied log file.
 /// 
-/// if no file supplied defaults to "event.log".
+/// if no file supplied defaults to `default_log()` (normally "event.log").
 /// 
 /// #Example
 /// ```
@@ -239,33 +697,59 @@ pub fn clean_log(log_file: &str) {
 #[macro_export]
 macro_rules! clean {
     () => {
-        $crate::clean_log("event.log")
+        $crate::clean_log(&$crate::default_log())
     };
     ($log:expr) => {
         $crate::clean_log($log)
     };
 }
 
-/// Appends [event] to [log_file].
+/// Appends [event] to [log_file] at `Level::Info`.
+/// 
+/// creates the file if it doesnt exist.\
+/// if the file cant be written to the record goes to the `LoggerBuilder::fallback` logs or stderr instead.\
+/// not meant to be used on its own. use logging macros instead
+pub fn log<T: LogEvent, L: LogFiles>(event: T, log_file: L) -> T {
+    let level = event.level();
+    log_level(event, level, log_file)
+}
+
+/// Appends [event] to [log_file] at [level].
 /// 
+/// [log_file] can be a single path or several, see `LogFiles`.\
 /// creates the file if it doesnt exist.\
-/// panics on failure to create or appending to file.\
+/// fsyncs the file if the configured `Durability` asks for it.\
+/// if the file cant be written to the record goes to the `LoggerBuilder::fallback` logs or stderr instead.\
 /// not meant to be used on its own. use logging macros instead
-pub fn log<T: fmt::Display>(event: T, log_file: &str) -> T {
-    let mut file = OpenOptions::new()
-        .append(true)
-        .create(true)
-        .open(log_file)
-        .unwrap_or_else(|e| panic!("Dynerr: Error opening log during crash: {} (error passed to logger was: {})",e,event));
-    file.write_all(format!("{}\n",event.to_string()).as_bytes())
-        .unwrap_or_else(|e| panic!("Dynerr: Error appending to log during crash: {} (error passed to logger was: {})",e,event));
+pub fn log_level<T: LogEvent, L: LogFiles>(event: T, level: Level, log_file: L) -> T {
+    if !level.is_compiled_in() {return event}
+    record::with_record(level, event::EventText(&event), |record| write_record(record, log_file));
     event
 }
 
+/// Appends [event] to its `LogEvent::target`, or to `default_log()` if it has none.
+/// 
+/// logs at [level], or at the event's own level if none is given.\
+/// not meant to be used on its own. use logging macros instead
+pub fn log_to_target<T: LogEvent>(event: T, level: Option<Level>) -> T {
+    let level = level.unwrap_or_else(|| event.level());
+    match event.target() {
+        Some(target) => log_level(event, level, target),
+        None => log_level(event, level, default_log()),
+    }
+}
+
 /// Appends [event] to [file].
 /// 
-/// If no file supplied then defaults to "event.log".\
-/// creates the file if it doesnt exist.
+/// If no file supplied then defaults to `default_log()` (normally "event.log").\
+/// an array of files writes the event to each of them.\
+/// [event] can be anything that implements `Display`, or a typed `LogEvent` that picks its own level and file.\
+/// creates the file if it doesnt exist.\
+/// each event is written as a line like `2026-10-15T12:34:56.789Z INFO  this is a test`, with the event text kept exactly as it was.
+/// versions before records were added wrote the event text alone. tools that read those logs line by line should read the message
+/// with `LogReader` instead, or take the text after the level.\
+/// [event] is not a format string. `log!("x = {}", x)` logs the text `x = {}` to a file named after x, because the second argument is the file.
+/// use `logf!("x = {}", x)` to format the event, and the `f` version of the other logging macros.
 ///
 /// 
 ///# Example
@@ -275,21 +759,142 @@ pub fn log<T: fmt::Display>(event: T, log_file: &str) -> T {
 ///# fn main() {
 /// log!("this is a test", "test.log");
 /// log!("do log!");
+/// log!("payment accepted", ["event.log", "audit.log"]);
 ///# }
 /// ```
 #[macro_export]
 macro_rules! log {
     ($event:expr) => {
-        $crate::log($event, "event.log")
+        $crate::log_to_target($event, None)
     };
     ($event:expr, $log:expr) => {
         $crate::log($event, $log)
     };
 }
 
-/// Appends [event] to [file] then panics.
+/// Appends several events to [file] at `Level::Info` as one block, so nothing logged by another thread lands between them.
+/// 
+/// If no file supplied then defaults to `default_log()` (normally "event.log").\
+/// the events can be anything that implements `Display`, and dont have to be the same type.
+/// use `Logger::write_batch` to batch records with other levels or fields.
+///
+///# Example
+/// 
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+///# clean!("report.log");
+/// let (passed, failed) = (41, 1);
+/// log_batch!(["test report:", format!("  passed: {}", passed), format!("  failed: {}", failed)], "report.log");
+///# assert_eq!(std::fs::read_to_string("report.log").unwrap().lines().count(), 3);
+///# clean!("report.log");
+///# }
+/// ```
+#[macro_export]
+macro_rules! log_batch {
+    ([$($event:expr),* $(,)?]) => {
+        $crate::log_batch!([$($event),*], $crate::default_log())
+    };
+    ([$($event:expr),* $(,)?], $log:expr) => {
+        $crate::Logger::write_batch(&[$($crate::LogRecord::new($crate::Level::Info, $event)),*], $log)
+    };
+}
+
+/// Appends [event] to [file] at `Level::Error`.
+/// 
+/// If no file supplied then defaults to `default_log()` (normally "event.log").\
+/// creates the file if it doesnt exist.
+/// 
+///# Example
+/// 
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// log_error!("disk full", "test.log");
+///# }
+/// ```
+#[macro_export]
+macro_rules! log_error {
+    ($event:expr) => {
+        $crate::log_to_target($event, Some($crate::Level::Error))
+    };
+    ($event:expr, $log:expr) => {
+        $crate::log_level($event, $crate::Level::Error, $log)
+    };
+}
+
+/// Appends [event] to [file] at `Level::Warn`.
+/// 
+/// If no file supplied then defaults to `default_log()` (normally "event.log").\
+/// creates the file if it doesnt exist.
+/// 
+///# Example
+/// 
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// log_warn!("disk almost full", "test.log");
+///# }
+/// ```
+#[macro_export]
+macro_rules! log_warn {
+    ($event:expr) => {
+        $crate::log_to_target($event, Some($crate::Level::Warn))
+    };
+    ($event:expr, $log:expr) => {
+        $crate::log_level($event, $crate::Level::Warn, $log)
+    };
+}
+
+/// Appends [event] to [file] at `Level::Info`. same as `log!`.
+/// 
+/// If no file supplied then defaults to `default_log()` (normally "event.log").\
+/// creates the file if it doesnt exist.
+/// 
+///# Example
+/// 
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// log_info!("level loaded", "test.log");
+///# }
+/// ```
+#[macro_export]
+macro_rules! log_info {
+    ($event:expr) => {
+        $crate::log_to_target($event, Some($crate::Level::Info))
+    };
+    ($event:expr, $log:expr) => {
+        $crate::log_level($event, $crate::Level::Info, $log)
+    };
+}
+
+/// Appends [event] to [file] at `Level::Debug`.
+/// 
+/// If no file supplied then defaults to `default_log()` (normally "event.log").\
+/// creates the file if it doesnt exist.
+/// 
+///# Example
+/// 
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// log_debug!("cache miss", "test.log");
+///# }
+/// ```
+#[macro_export]
+macro_rules! log_debug {
+    ($event:expr) => {
+        $crate::log_to_target($event, Some($crate::Level::Debug))
+    };
+    ($event:expr, $log:expr) => {
+        $crate::log_level($event, $crate::Level::Debug, $log)
+    };
+}
+
+/// Appends [event] to [file] at `Level::Trace`.
 /// 
-/// If no file supplied then defaults to "event.log".\
+/// If no file supplied then defaults to `default_log()` (normally "event.log").\
 /// creates the file if it doesnt exist.
 /// 
 ///# Example
@@ -297,28 +902,286 @@ macro_rules! log {
 /// ```rust
 ///# use dynerr::*;
 ///# fn main() {
+/// log_trace!("entered update()", "test.log");
+///# }
+/// ```
+#[macro_export]
+macro_rules! log_trace {
+    ($event:expr) => {
+        $crate::log_to_target($event, Some($crate::Level::Trace))
+    };
+    ($event:expr, $log:expr) => {
+        $crate::log_level($event, $crate::Level::Trace, $log)
+    };
+}
+
+/// Appends [event] to [file] at `Level::Error` then panics.
+/// 
+/// If no file supplied then defaults to `default_log()` (normally "event.log").\
+/// creates the file if it doesnt exist.\
+/// the active `scope!` stack is logged with the event.
+/// if `TextFormat::record_ids` is set, the panic message ends with the id of the panic's own record,
+/// like `(log records 4242:57 and earlier)`, to find the log lines leading up to a crash report.
+/// records filtered out by a file level were numbered too, so some of the earlier ids may be missing from the log.\
+/// like `log!`, [event] is not a format string. use `logged_panicf!` to format it.
+/// 
+///# Example
+/// 
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
 ///#     if false {
 /// logged_panic!("Hi!", "test.log");
 /// logged_panic!("default log");
 ///#     }
 ///# }
 /// ```
+/// 
+/// with `ctx: [..]` the listed values are Debug formatted into fields of the record, to show the state that caused the panic:
+/// ```rust
+///# use dynerr::*;
+///# #[derive(Debug)]
+///# struct Config {retries: u32}
+///# fn main() {
+///# clean!("ctx.log");
+/// let (x, y) = (3, -1);
+/// let config = Config {retries: 0};
+/// let panicked = std::panic::catch_unwind(|| {
+///     if y < 0 {
+///         logged_panic!(ctx: [x, y, config.retries], "invariant broken", "ctx.log");
+///     }
+/// });
+///# assert!(panicked.is_err());
+///# let text = std::fs::read_to_string("ctx.log").unwrap();
+///# assert!(text.contains("x=3") && text.contains("y=-1") && text.contains("config.retries=0"));
+///# clean!("ctx.log");
+///# }
+/// ```
 #[macro_export]
 macro_rules! logged_panic {
+    (ctx: [$($value:expr),* $(,)?], $e:expr) => {
+        $crate::logged_panic!(ctx: [$($value),*], $e, &$crate::default_log())
+    };
+
+    (ctx: [$($value:expr),* $(,)?], $e:expr, $log:expr) => {
+        $crate::log_and_panic_with_context($e, &[$((stringify!($value), &$value as &dyn ::std::fmt::Debug)),*], $log)
+    };
+
     ($e: expr) => {
-        panic!("{}",log!($e));
+        $crate::log_and_panic($e, &$crate::default_log())
     };
 
     ($e: expr, $log:expr) => {
-        panic!("{}",log!($e, $log));
+        $crate::log_and_panic($e, $log)
     }
 }
 
 
+/// Formats then appends an event at `Level::Info`, returning the formatted event.
+/// 
+/// the formatting version of `log!`, since `log!("x = {}", x)` would log to a file named x.\
+/// takes the same arguments as `format!`. a file can be supplied after a `;`, otherwise defaults to `default_log()`.
+/// 
+///# Example
+/// 
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// let (x, name) = (5, "slime");
+/// let event = logf!("x = {}", x);
+/// assert_eq!(event, "x = 5");
+/// logf!("spawned {} at {}", name, x; "test.log");
+/// logf!("spawned {name}"; ["test.log", "event.log"]);
+/// assert_eq!(logf!("{name} has {hp} hp", hp = x * 2; "test.log"), "slime has 10 hp");
+///# }
+/// ```
+#[macro_export]
+macro_rules! logf {
+    ($($args:tt)*) => {
+        $crate::__logf!($crate::Level::Info; $($args)*)
+    };
+}
+
+/// Formats then appends an event at `Level::Error`, returning the formatted event.
+/// 
+/// takes the same arguments as `format!`. a file can be supplied after a `;`, otherwise defaults to `default_log()`.
+/// 
+///# Example
+/// 
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// let slot = 3;
+/// log_errorf!("saving slot {}", slot);
+/// log_errorf!("saving slot {slot}"; "test.log");
+///# }
+/// ```
+#[macro_export]
+macro_rules! log_errorf {
+    ($($args:tt)*) => {
+        $crate::__logf!($crate::Level::Error; $($args)*)
+    };
+}
+
+/// Formats then appends an event at `Level::Warn`, returning the formatted event.
+/// 
+/// takes the same arguments as `format!`. a file can be supplied after a `;`, otherwise defaults to `default_log()`.
+/// 
+///# Example
+/// 
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// let slot = 3;
+/// log_warnf!("saving slot {}", slot);
+/// log_warnf!("saving slot {slot}"; "test.log");
+///# }
+/// ```
+#[macro_export]
+macro_rules! log_warnf {
+    ($($args:tt)*) => {
+        $crate::__logf!($crate::Level::Warn; $($args)*)
+    };
+}
+
+/// Formats then appends an event at `Level::Info`, returning the formatted event.
+/// 
+/// takes the same arguments as `format!`. a file can be supplied after a `;`, otherwise defaults to `default_log()`.
+/// 
+///# Example
+/// 
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// let slot = 3;
+/// log_infof!("saving slot {}", slot);
+/// log_infof!("saving slot {slot}"; "test.log");
+///# }
+/// ```
+#[macro_export]
+macro_rules! log_infof {
+    ($($args:tt)*) => {
+        $crate::__logf!($crate::Level::Info; $($args)*)
+    };
+}
+
+/// Formats then appends an event at `Level::Debug`, returning the formatted event.
+/// 
+/// takes the same arguments as `format!`. a file can be supplied after a `;`, otherwise defaults to `default_log()`.
+/// 
+///# Example
+/// 
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// let slot = 3;
+/// log_debugf!("saving slot {}", slot);
+/// log_debugf!("saving slot {slot}"; "test.log");
+///# }
+/// ```
+#[macro_export]
+macro_rules! log_debugf {
+    ($($args:tt)*) => {
+        $crate::__logf!($crate::Level::Debug; $($args)*)
+    };
+}
+
+/// Formats then appends an event at `Level::Trace`, returning the formatted event.
+/// 
+/// takes the same arguments as `format!`. a file can be supplied after a `;`, otherwise defaults to `default_log()`.
+/// 
+///# Example
+/// 
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// let slot = 3;
+/// log_tracef!("saving slot {}", slot);
+/// log_tracef!("saving slot {slot}"; "test.log");
+///# }
+/// ```
+#[macro_export]
+macro_rules! log_tracef {
+    ($($args:tt)*) => {
+        $crate::__logf!($crate::Level::Trace; $($args)*)
+    };
+}
+
+/// Formats an event, appends it at `Level::Error` then panics with it.
+/// 
+/// takes the same arguments as `format!`. a file can be supplied after a `;`, otherwise defaults to `default_log()`.
+/// 
+///# Example
+/// 
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+///#     if false {
+/// let name = "save.dat";
+/// logged_panicf!("bad {}: {}", name, "truncated");
+/// logged_panicf!("bad {name}: {why}", why = "truncated"; "test.log");
+///#     }
+///# }
+/// ```
+#[macro_export]
+macro_rules! logged_panicf {
+    ($($args:tt)*) => {
+        $crate::__logf!(@split (panic) [] $($args)*)
+    };
+}
+
+///formats the arguments of the `logf!` family and logs them at a level.
+///the arguments are forwarded to `format!` as tokens up to the `;` before the file, so named arguments work
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __logf {
+    (@split $how:tt [$($fmt:tt)*] ; $log:expr) => {
+        $crate::__logf!(@log $how [$($fmt)*] $log)
+    };
+    (@split $how:tt [$($fmt:tt)*]) => {
+        $crate::__logf!(@log $how [$($fmt)*] &$crate::default_log())
+    };
+    (@split $how:tt [$($fmt:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::__logf!(@split $how [$($fmt)* $next] $($rest)*)
+    };
+    (@log (panic) [$($fmt:tt)*] $log:expr) => {
+        $crate::log_and_panic(format!($($fmt)*), $log)
+    };
+    (@log ($level:expr) [$($fmt:tt)*] $log:expr) => {
+        match $level.is_compiled_in() {
+            true => $crate::log_level(format!($($fmt)*), $level, $log),
+            false => ::std::string::String::new(),
+        }
+    };
+    ($level:expr; $($args:tt)*) => {
+        $crate::__logf!(@split ($level) [] $($args)*)
+    };
+}
+
 /// Shortcut for .unwrap_or_else(|e| logged_panic!(e)) for DynResult.
 /// 
-/// If no file supplied then defaults to "event.log".\
-/// creates the file if it doesnt exist.
+/// works on any `Result` whose error implements `Display`, not just `DynResult`.
+/// errors that convert into a `DynError` keep their type for `Severity`, `Help` and policies, others are logged by their text.\
+/// If no file supplied then defaults to `default_log()` (normally "event.log").\
+/// creates the file if it doesnt exist.\
+/// with `or: default` the error's `Severity` is consulted instead of always panicking.
+/// only `Severity::Fatal` errors panic, others are logged (or ignored) and `default` is returned.
+/// 
+/// with `; on_err = ...` a chain of policies separated by `then` is applied in order:
+/// - `retry(n)` evaluates the expression up to n more times, returning the first success
+/// - `configured` retries as many times as the `KindPolicy::Retry` configured for the error's kind, if any
+/// - `log` logs the latest error at `Level::Warn`
+/// - `default(value)` returns value
+/// - `panic` does a logged panic
+/// 
+/// a chain that doesnt end in `default` or `panic` ends like a plain `check!`. policies after `default` or `panic` would never run,
+/// so they are a compile error.
+/// 
+/// with `hint: "..."` the hint is logged with the error and added to the panic message.
+/// errors whose type has a registered `Help` get their hint without it.
+/// 
+/// a failed `check!` does a logged panic, or logs and exits the process if `LoggerBuilder::check_failure`
+/// is set to `CheckFailure::Exit`.
 /// 
 ///# Example
 /// 
@@ -332,15 +1195,171 @@ macro_rules! logged_panic {
 ///# fn main() {
 /// let i = check!(try_something());
 /// let i = check!(try_something(), "test.log");
+/// let i = check!(try_something(), or: ());
+/// let i = check!(try_something(), "test.log", or: ());
+/// let i = check!(try_something(); on_err = retry(3) then log then default(()));
+/// let i = check!(try_something(), "test.log"; on_err = log then panic);
+/// let i = check!(try_something(), hint: "run `app init` first");
+///# }
+/// ```
+///
+/// a policy after `default` or `panic` is a compile error:
+/// ```rust,compile_fail
+///# use dynerr::*;
+///# fn main() {
+/// let i = check!("x".parse::<u32>(); on_err = default(0) then log);
+///# }
+/// ```
+/// 
+/// results from other libraries can be checked directly, even if their error only implements `Display`:
+/// ```rust
+///# use dynerr::*;
+///# use std::fmt;
+/// struct Refused(u16); //a third party error that isnt a std::error::Error
+/// impl fmt::Display for Refused {
+///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///         write!(f, "port {} refused the connection", self.0)
+///     }
+/// }
+/// fn connect(port: u16) -> Result<u16, Refused> {
+///     Err(Refused(port))
+/// }
+///# fn main() {
+/// let port = check!(connect(80), "test.log", or: 8080);
+/// let port = check!(connect(port), "test.log"; on_err = retry(2) then log then default(0));
+///# assert_eq!(port, 0);
+///# assert!(recent_events().contains(&"port 8080 refused the connection".to_string()));
 ///# }
 /// ```
 #[macro_export]
 macro_rules! check {
+    ($x:expr; on_err = $($policy:tt)+) => {
+        $crate::check!($x, &$crate::default_log(); on_err = $($policy)+)
+    };
+    ($x:expr, $log:expr; on_err = $($policy:tt)+) => {
+        '__check: {
+            let mut __err: $crate::DynError = match $x {
+                Ok(v) => break '__check v,
+                Err(e) => $crate::__into_dyn!(e),
+            };
+            $crate::__check_policy!(('__check, __err, $x, $log) $($policy)+)
+        }
+    };
+    ($x:expr, hint: $hint:expr) => {
+        $crate::check!($x, &$crate::default_log(), hint: $hint)
+    };
+    ($x:expr, $log:expr, hint: $hint:expr) => {
+        match $x {
+            Ok(v) => v,
+            Err(e) => $crate::check_failed_hint($crate::__into_dyn!(e), Some($hint), $log),
+        }
+    };
+    ($x:expr, or: $default:expr) => {
+        match $x {
+            Ok(v) => v,
+            Err(e) => {
+                $crate::check_recover($crate::__into_dyn!(e), &$crate::default_log());
+                $default
+            }
+        }
+    };
+    ($x:expr, $log:expr, or: $default:expr) => {
+        match $x {
+            Ok(v) => v,
+            Err(e) => {
+                $crate::check_recover($crate::__into_dyn!(e), $log);
+                $default
+            }
+        }
+    };
+    ($x:expr) => {
+        $crate::check!($x, &$crate::default_log())
+    };
+    ($x:expr, $log:expr) => {
+        match $x {
+            ::std::result::Result::Ok(v) => v,
+            ::std::result::Result::Err(e) => $crate::check_failed_hint($crate::__into_dyn!(e), None, $log),
+        }
+    };
+}
+
+///applies the `on_err` policies of `check!` one at a time
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __check_policy {
+    (($label:lifetime, $err:ident, $x:expr, $log:expr)) => {
+        $crate::check_failed_hint($err, None, $log)
+    };
+    (($($ctx:tt)*) then $($rest:tt)*) => {
+        $crate::__check_policy!(($($ctx)*) $($rest)*)
+    };
+    (($label:lifetime, $err:ident, $x:expr, $log:expr) retry($n:expr) $($rest:tt)*) => {{
+        for _ in 0..$n {
+            $crate::summary::__count_retry();
+            match $x {
+                Ok(v) => break $label v,
+                Err(e) => $err = $crate::__into_dyn!(e),
+            }
+        }
+        $crate::__check_policy!(($label, $err, $x, $log) $($rest)*)
+    }};
+    (($label:lifetime, $err:ident, $x:expr, $log:expr) configured $($rest:tt)*) => {{
+        if let Some($crate::KindPolicy::Retry(n)) = $crate::kind_policy(&*$err) {
+            for _ in 0..n {
+                $crate::summary::__count_retry();
+                match $x {
+                    Ok(v) => break $label v,
+                    Err(e) => $err = $crate::__into_dyn!(e),
+                }
+            }
+        }
+        $crate::__check_policy!(($label, $err, $x, $log) $($rest)*)
+    }};
+    (($label:lifetime, $err:ident, $x:expr, $log:expr) log $($rest:tt)*) => {{
+        $crate::__log_error(&*$err, $crate::Level::Warn, $log);
+        $crate::__check_policy!(($label, $err, $x, $log) $($rest)*)
+    }};
+    (($label:lifetime, $err:ident, $x:expr, $log:expr) default($default:expr)) => {{
+        $crate::__report_error(&*$err);
+        break $label $default
+    }};
+    (($label:lifetime, $err:ident, $x:expr, $log:expr) panic) => {
+        $crate::log_and_panic($err, $log)
+    };
+    (($($ctx:tt)*) default($default:expr) $($rest:tt)+) => {
+        compile_error!("Dynerr: `default(..)` has to be the last on_err policy")
+    };
+    (($($ctx:tt)*) panic $($rest:tt)+) => {
+        compile_error!("Dynerr: `panic` has to be the last on_err policy")
+    };
+}
+
+/// Shortcut for .unwrap_or_else(|| logged_panic!(..)) for Option.
+/// 
+/// If no file supplied then defaults to `default_log()` (normally "event.log").\
+/// logs "value was None at file:line" unless a message is supplied.\
+/// creates the file if it doesnt exist.
+/// 
+///# Example
+/// 
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// let i = check_some!(Some(5));
+/// let i = check_some!(Some(5), "test.log");
+/// let i = check_some!(Some(5), "test.log", "config had no width");
+///# }
+/// ```
+#[macro_export]
+macro_rules! check_some {
     ($x:expr) => {
-        $x.unwrap_or_else(|e| logged_panic!(e))
+        $x.unwrap_or_else(|| $crate::logged_panic!(format!("value was None at {}:{}", file!(), line!())))
     };
     ($x:expr, $log:expr) => {
-        $x.unwrap_or_else(|e| logged_panic!(e, $log))
+        $x.unwrap_or_else(|| $crate::logged_panic!(format!("value was None at {}:{}", file!(), line!()), $log))
+    };
+    ($x:expr, $log:expr, $msg:expr) => {
+        $x.unwrap_or_else(|| $crate::logged_panic!(format!("{} at {}:{}", $msg, file!(), line!()), $log))
     };
 }
 
@@ -424,6 +1443,76 @@ mod tests {
         log!("do logged_panic! if error");
         let _i = check!(example(1));
         let _i = check!(example(1), "test.log");
+        let _i = check!(example(3), "test.log", or: 0);
+        let _i = check_some!(Some(1));
+        let _i = check_some!(Some(1), "test.log", "no value");
         Ok(())
     }
+
+    #[test]
+    fn check_policies() {
+        let mut attempts = 0;
+        let mut flaky = || {attempts += 1; if attempts < 3 {example(3)} else {example(1)}};
+        assert_eq!(check!(flaky(), "test.log"; on_err = retry(5) then panic), 1);
+        assert_eq!(attempts, 3);
+        assert_eq!(check!(example(3), "test.log"; on_err = retry(2) then log then default(0)), 0);
+    }
+
+    #[test]
+    fn check_hint() {
+        let panic = std::panic::catch_unwind(|| check!(example(3), "test.log", hint: "pass 1")).unwrap_err();
+        let message = panic.downcast_ref::<String>().unwrap();
+        assert!(message.ends_with("ExampleError1::ThisError: 3\nhint: pass 1"));
+    }
+
+    #[test]
+    fn exhaustive_dynmatch() {
+        let i = match example(7) {
+            Ok(i) => i,
+            Err(e) => dynmatch!(e,
+                exhaustive type ExampleError1 {
+                    arm ExampleError1::ThisError(i) => *i
+                },
+                exhaustive type ExampleError2 {
+                    arm ExampleError2::ThatError(i) => *i + 1,
+                },
+                _ => 0
+            )
+        };
+        assert_eq!(i, 8);
+    }
+
+    #[test]
+    fn dynmatch_consumes_fallback() {
+        let evaluated = std::cell::Cell::new(0);
+        let make = || {
+            evaluated.set(evaluated.get() + 1);
+            example(3).unwrap_err()
+        };
+        let message = dynmatch!(let e = make(),
+            type ExampleError1 {
+                arm ExampleError1::ThisError(0) => String::new(),
+                _ => e.to_string()
+            },
+            _ => String::new()
+        );
+        assert_eq!(message, "ExampleError1::ThisError: 3");
+        assert_eq!(evaluated.get(), 1);
+        let e = example(20).unwrap_err();
+        let owned: DynError = dynmatch!(e, type ExampleError1 {arm ExampleError1::ThisError(_) if false => unreachable!(), _ => unreachable!()}, _ => e);
+        assert!(owned.downcast_ref::<std::io::Error>().is_some());
+    }
+
+    #[test]
+    fn dynmatch_probes_every_wrapper() {
+        let escalated = Escalated::new(ExampleError2::ThatError(2), &ExampleError1::ThisError(1));
+        let e: DynError = Box::new(escalated);
+        let e = e.attach(5u32);
+        let e: DynError = Box::new(CachedError::new(e));
+        for _ in 0..2 {
+            assert_eq!(dynmatch!(e, type Escalated {arm _ if true => 1, _ => 0}, _ => 0), 1);
+            assert_eq!(dynmatch!(e, exhaustive type ExampleError2 {arm ExampleError2::ThatError(i) => *i}, _ => 0), 2);
+            assert_eq!(dynmatch!(e, text "ThatError" => 3, _ => 0), 3);
+        }
+    }
 }
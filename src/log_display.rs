@@ -0,0 +1,163 @@
+//! Giving an error a more detailed text in the log than its `Display`.
+//!
+//! implement `LogDisplay` on your error then `register_log_display::<YourError>()` once at startup.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::{Level, LogFiles, LogRecord};
+use crate::registry::Registry;
+
+/// Implemented by error types that should be written to the log differently from how they are displayed.
+///
+/// `Display` stays the short message users see, in panic messages and on stderr.
+/// the log gets `fmt_log` instead, which can add multi-line detail blocks or tables of fields.\
+/// it is used for errors logged as a `DynError`: failed and recovered `check!`s, `check!` policies,
+/// `Checker`, `check_budget!` and `join_checked!`.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# use std::{error, fmt};
+/// #[derive(Debug)]
+/// struct QueryFailed {sql: &'static str, code: u32}
+/// impl fmt::Display for QueryFailed {
+///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///         write!(f, "the database query failed")
+///     }
+/// }
+/// impl error::Error for QueryFailed {}
+/// impl LogDisplay for QueryFailed {
+///     fn fmt_log(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///         write!(f, "query failed with code {}\n  sql: {}", self.code, self.sql)
+///     }
+/// }
+///# fn main() {
+/// register_log_display::<QueryFailed>();
+///# clean!("query.log");
+/// let e: DynError = Box::new(QueryFailed {sql: "SELECT 1", code: 7});
+/// check_recover(e, "query.log");
+///# let text = std::fs::read_to_string("query.log").unwrap();
+///# assert!(text.contains("query failed with code 7\n  sql: SELECT 1"));
+///# clean!("query.log");
+///# }
+/// ```
+pub trait LogDisplay {
+    ///writes the text of this error for the log
+    fn fmt_log(&self, f: &mut fmt::Formatter) -> fmt::Result;
+}
+
+///a downcast to one registered type. returns None if the error isnt that type
+type Formatter = for<'a> fn(&'a (dyn Error + 'static)) -> Option<&'a dyn LogDisplay>;
+
+///every registered formatter
+static REGISTRY: Registry<Formatter> = Registry::new();
+
+///downcasts to [T], which has a log text
+fn format<'a, T: Error + LogDisplay + 'static>(e: &'a (dyn Error + 'static)) -> Option<&'a dyn LogDisplay> {
+    use crate::__DowncastThrough;
+    e.__downcast_through::<T>().map(|e| e as &dyn LogDisplay)
+}
+
+///the `LogDisplay` of [e], if its type was registered
+fn log_display<'a>(e: &'a (dyn Error + 'static)) -> Option<&'a dyn LogDisplay> {
+    REGISTRY.find_map(|format| format(e))
+}
+
+/// Registers [T] so the logger can see its `LogDisplay` impl through a `DynError`.
+///
+/// registering the same type twice has no effect.
+pub fn register_log_display<T: Error + LogDisplay + 'static>() {
+    REGISTRY.register::<T>(format::<T>);
+}
+
+/// Displays an error with its registered `LogDisplay`, or its `Display` if it has none.
+#[derive(Clone, Copy)]
+pub struct LogText<'a>(pub &'a (dyn Error + 'static));
+
+impl fmt::Display for LogText<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match log_display(self.0) {
+            Some(display) => display.fmt_log(f),
+            None => fmt::Display::fmt(self.0, f),
+        }
+    }
+}
+
+/// Logs [e] at [level] with its `LogText`, collapsing repeats from the same `check!` if `LoggerBuilder::dedup_call_sites` is set.
+///
+/// not meant to be used on its own. used by `check!`
+#[doc(hidden)]
+#[track_caller]
+pub fn __log_error<L: LogFiles>(e: &(dyn Error + 'static), level: Level, log_file: L) {
+    crate::dedup::log_at_call_site(e, level, log_file);
+}
+
+///a record of [e] at [level] with its `LogText`, error id and Debug text, the way errors logged as a `DynError` are written
+pub(crate) fn error_record(level: Level, e: &(dyn Error + 'static)) -> LogRecord {
+    with_debug(crate::error_id::with_error_id(LogRecord::new(level, LogText(e)), e), e)
+}
+
+///[record] with the Debug text of [e] as a `debug` field, cut to the length set by `LoggerBuilder::capture_debug`.
+///left out if capturing is off, the Debug text is the same as the `LogText`, or [e] has a `LogDisplay`,
+///since its Debug text would show whatever `fmt_log` leaves out
+fn with_debug(record: LogRecord, e: &(dyn Error + 'static)) -> LogRecord {
+    let Some(max) = crate::config::config().debug_capture else {return record};
+    if log_display(e).is_some() {return record}
+    let mut debug = format!("{:?}", e);
+    if debug == e.to_string() {return record}
+    if debug.len() > max {
+        let end = (0..=max).rev().find(|i| debug.is_char_boundary(*i)).unwrap_or(0);
+        debug.truncate(end);
+        debug.push_str("...");
+    }
+    record.with_field("debug", debug)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct Login {password: &'static str}
+
+    impl fmt::Display for Login {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "login failed")
+        }
+    }
+
+    impl Error for Login {}
+
+    impl LogDisplay for Login {
+        fn fmt_log(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "login failed with password {}", "*".repeat(self.password.len()))
+        }
+    }
+
+    #[test]
+    fn debug_capture_keeps_masking() {
+        let _config = crate::config::TEST_CONFIG.lock().unwrap_or_else(|e| e.into_inner());
+        crate::Logger::builder().capture_debug(200).init();
+        let unregistered = error_record(Level::Error, &Login {password: "hunter2"});
+        register_log_display::<Login>();
+        let registered = error_record(Level::Error, &Login {password: "hunter2"});
+        crate::init();
+        assert!(unregistered.fields.iter().any(|(k, v)| k == "debug" && v.contains("hunter2")));
+        assert_eq!(registered.message, "login failed with password *******");
+        assert!(registered.fields.iter().all(|(_, v)| !v.contains("hunter2")));
+    }
+
+    #[test]
+    fn failed_check_uses_log_display_and_id() {
+        let log = std::env::temp_dir().join("dynerr_failed_check.log").to_string_lossy().into_owned();
+        let _ = std::fs::remove_file(&log);
+        register_log_display::<Login>();
+        crate::register_error_id::<Login>("login_failed");
+        let login = || -> Result<(), Login> {Err(Login {password: "hunter2"})};
+        assert!(std::panic::catch_unwind(|| crate::check!(login(), log.as_str())).is_err());
+        let text = std::fs::read_to_string(&log).unwrap();
+        assert!(text.contains("login failed with password *******"), "{}", text);
+        assert!(text.contains("error_id=login_failed") && !text.contains("hunter2"), "{}", text);
+    }
+}
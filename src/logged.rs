@@ -0,0 +1,43 @@
+//! The runtime half of the `#[logged]` attribute.
+
+use crate::{Attach, DynResult, Level, LogRecord};
+
+/// The function an error was propagated out of and the arguments it was called with.
+///
+/// attached to every error that passes through a `#[logged]` function. holds the innermost one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallInfo {
+    ///the name of the function
+    pub function: &'static str,
+    ///the Debug formatted arguments, like `id=7, name="a"`
+    pub args: String,
+}
+
+/// Logs that [function] was called with [args].
+///
+/// not meant to be used on its own. use `#[logged(entry)]` instead
+#[doc(hidden)]
+pub fn __logged_entry(function: &'static str, args: &str) {
+    let record = LogRecord::new(Level::Debug, format_args!("entered {}", function))
+        .with_field("function", function)
+        .with_field("args", args);
+    crate::write_record(&record, crate::default_log());
+}
+
+/// Logs the error [result] holds, if any, and attaches a `CallInfo` to it.
+///
+/// an error is only logged by the innermost `#[logged]` function it passes through, and not at all if it is ignorable.\
+/// not meant to be used on its own. use `#[logged]` instead
+#[doc(hidden)]
+pub fn __logged_result<T>(result: DynResult<T>, function: &'static str, args: String) -> DynResult<T> {
+    result.map_err(|e| {
+        if e.get_attached::<CallInfo>().is_some() {return e}
+        if crate::severity(&*e) == crate::Severity::Ignorable {return e.attach(CallInfo {function, args})}
+        let record = crate::log_display::error_record(Level::Error, &*e)
+            .with_field("function", function)
+            .with_field("args", &args);
+        crate::display_hint::check(&*e, crate::default_log());
+        crate::write_record(&record, crate::default_log());
+        e.attach(CallInfo {function, args})
+    })
+}
@@ -0,0 +1,76 @@
+//! Logging paths and OS strings that might not be valid UTF-8.
+
+use std::ffi::OsStr;
+use std::fmt;
+
+/// Displays an `OsStr` or `Path` without losing anything. used by `log_lossy!`
+///
+/// valid UTF-8 is written as is, except `\` which is written as `\\`. bytes that arent are written as `\xNN` escapes instead of the `�`
+/// that `to_string_lossy` would replace them with, so the original name can still be worked out from the log.\
+/// on Windows an unpaired surrogate shows up as the three escaped bytes of its WTF-8 encoding.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// let path = std::path::Path::new("reports/2026.csv");
+/// logf!("opened {}", Lossless::new(path); "test.log");
+///# #[cfg(unix)] {
+/// use std::os::unix::ffi::OsStrExt;
+/// let name = std::ffi::OsStr::from_bytes(b"caf\xe9.txt");
+/// assert_eq!(Lossless::new(name).to_string(), "caf\\xe9.txt");
+/// let name = std::ffi::OsStr::from_bytes(b"caf\\xe9.txt"); //a valid name that looks like an escape
+/// assert_eq!(Lossless::new(name).to_string(), "caf\\\\xe9.txt");
+///# }
+///# }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Lossless<'a>(&'a OsStr);
+
+impl<'a> Lossless<'a> {
+    ///wraps a path or OS string for displaying
+    pub fn new<S: AsRef<OsStr> + ?Sized>(s: &'a S) -> Self {
+        Self(s.as_ref())
+    }
+}
+
+impl fmt::Display for Lossless<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for chunk in self.0.as_encoded_bytes().utf8_chunks() {
+            let mut parts = chunk.valid().split('\\');
+            f.write_str(parts.next().unwrap_or_default())?;
+            for part in parts {
+                f.write_str("\\\\")?;
+                f.write_str(part)?;
+            }
+            for byte in chunk.invalid() {
+                write!(f, "\\x{:02x}", byte)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Logs a path or OS string without losing bytes that arent valid UTF-8. returns the `Lossless` that was logged.
+///
+/// If no file supplied then defaults to `default_log()` (normally "event.log").\
+/// use `Lossless::new` directly to log one as part of a bigger message.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// for entry in std::fs::read_dir(".").unwrap() {
+///     log_lossy!(entry.unwrap().path(), "test.log");
+/// }
+///# }
+/// ```
+#[macro_export]
+macro_rules! log_lossy {
+    ($x:expr) => {
+        $crate::log!($crate::Lossless::new(&$x))
+    };
+    ($x:expr, $log:expr) => {
+        $crate::log!($crate::Lossless::new(&$x), $log)
+    };
+}
@@ -0,0 +1,130 @@
+//! Functions every error raised with `dynerr!` passes through, for changing errors in one place.
+
+use std::error::Error;
+
+use crate::DynError;
+use crate::registry::Registry;
+
+///a registered middleware function
+type Middleware = fn(DynError) -> DynError;
+
+///every registered middleware by address, in the order they run
+static MIDDLEWARE: Registry<Middleware, usize> = Registry::new();
+
+/// Registers [middleware] to run on every error raised with `dynerr!` as a `DynError`.
+///
+/// middleware runs in the order it was registered, each getting the error the one before it returned.
+/// registering the same function twice has no effect.\
+/// errors raised as a `DynErrorRef` or a `Send` box skip middleware, since it takes and returns a `DynError`.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+/// #[derive(Debug, PartialEq)]
+/// struct RequestId(u32);
+///
+/// fn tag_request(e: DynError) -> DynError {
+///     e.attach(RequestId(7))
+/// }
+///
+/// fn handle() -> DynResult<()> {
+///     dynerr!(std::fmt::Error)
+/// }
+///# fn main() {
+/// register_middleware(tag_request);
+/// let e = handle().unwrap_err();
+/// assert_eq!(e.get_attached::<RequestId>(), Some(&RequestId(7)));
+/// assert!(e.downcast_ref::<Attached>().is_some());
+///# clear_middleware();
+///# }
+/// ```
+pub fn register_middleware(middleware: fn(DynError) -> DynError) {
+    MIDDLEWARE.insert(middleware as usize, middleware);
+}
+
+///removes every registered middleware
+pub fn clear_middleware() {
+    MIDDLEWARE.clear();
+}
+
+/// Runs [e] through every registered middleware.
+///
+/// `dynerr!` calls this itself. call it from your own helpers that create errors to treat them the same way.
+pub fn apply_middleware(e: DynError) -> DynError {
+    //copied out so middleware can register more without deadlocking
+    let registered: Vec<Middleware> = MIDDLEWARE.read().iter().map(|(_, m)| *m).collect();
+    registered.into_iter().fold(e, |e, middleware| middleware(e))
+}
+
+///converts an error raised by `dynerr!` into the error the function returns. not meant to be used on its own
+#[doc(hidden)]
+pub trait __Raise<E> {
+    fn __raise(e: E) -> Self;
+}
+
+impl<E, U: ?Sized + __RaiseInto<E>> __Raise<E> for Box<U> {
+    fn __raise(e: E) -> Self {
+        U::__raise_into(e)
+    }
+}
+
+///boxes an error raised by `dynerr!` as the box the function returns, running middleware for a `Box<dyn Error>`.
+///any other box the error coerces to keeps plain `Box::new`. not meant to be used on its own
+#[doc(hidden)]
+pub trait __RaiseInto<E> {
+    fn __raise_into(e: E) -> Box<Self>;
+}
+
+impl<E> __RaiseInto<E> for E {
+    fn __raise_into(e: E) -> Box<Self> {
+        Box::new(e)
+    }
+}
+
+impl<'a, E: Error + 'static> __RaiseInto<E> for dyn Error + 'a {
+    fn __raise_into(e: E) -> Box<Self> {
+        crate::error_stats::track(std::any::type_name::<E>(), std::mem::size_of::<E>(), &e);
+        apply_middleware(Box::new(e))
+    }
+}
+
+impl<'a, E: Error + Send + 'static> __RaiseInto<E> for dyn Error + Send + 'a {
+    fn __raise_into(e: E) -> Box<Self> {
+        crate::error_stats::track(std::any::type_name::<E>(), std::mem::size_of::<E>(), &e);
+        Box::new(e)
+    }
+}
+
+impl<'a, E: Error + Sync + 'static> __RaiseInto<E> for dyn Error + Sync + 'a {
+    fn __raise_into(e: E) -> Box<Self> {
+        crate::error_stats::track(std::any::type_name::<E>(), std::mem::size_of::<E>(), &e);
+        Box::new(e)
+    }
+}
+
+impl<'a, E: Error + Send + Sync + 'static> __RaiseInto<E> for dyn Error + Send + Sync + 'a {
+    fn __raise_into(e: E) -> Box<Self> {
+        crate::error_stats::track(std::any::type_name::<E>(), std::mem::size_of::<E>(), &e);
+        Box::new(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dynerr;
+    use std::error::Error;
+
+    fn own_box() -> Result<(), Box<std::fmt::Error>> {
+        dynerr!(std::fmt::Error)
+    }
+
+    fn borrowed_box<'a>(_: &'a str) -> Result<(), Box<dyn Error + Send + Sync + 'a>> {
+        dynerr!(std::fmt::Error)
+    }
+
+    #[test]
+    fn raises_into_any_box() {
+        assert_eq!(*own_box().unwrap_err(), std::fmt::Error);
+        assert!(borrowed_box("x").unwrap_err().is::<std::fmt::Error>());
+    }
+}
@@ -0,0 +1,101 @@
+//! An error made of several other errors.
+
+use std::error::Error;
+use std::fmt;
+use std::iter::FromIterator;
+
+use crate::DynError;
+
+/// Several errors collected into one.
+/// 
+/// `source()` is the first collected error.
+/// 
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// let mut errors = MultiError::new();
+/// errors.push("first failure");
+/// errors.push(std::io::Error::from(std::io::ErrorKind::NotFound));
+/// assert_eq!(errors.len(), 2);
+/// assert!(errors.into_result().is_err());
+///# }
+/// ```
+#[derive(Debug, Default)]
+pub struct MultiError {
+    errors: Vec<DynError>,
+}
+
+impl MultiError {
+    ///creates an empty MultiError
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///adds an error
+    pub fn push<E: Into<DynError>>(&mut self, e: E) {
+        self.errors.push(e.into());
+    }
+
+    ///how many errors were collected
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    ///whether no errors were collected
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    ///iterates over the collected errors
+    pub fn iter(&self) -> std::slice::Iter<'_, DynError> {
+        self.errors.iter()
+    }
+
+    ///returns the collected errors
+    pub fn into_errors(self) -> Vec<DynError> {
+        self.errors
+    }
+
+    ///Ok if nothing was collected, otherwise Err(self)
+    pub fn into_result(self) -> Result<(), MultiError> {
+        if self.is_empty() {Ok(())} else {Err(self)}
+    }
+}
+
+impl fmt::Display for MultiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} error(s) occurred", self.errors.len())?;
+        for (i, e) in self.errors.iter().enumerate() {
+            write!(f, "\n  {}: {}", i + 1, e)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for MultiError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.errors.first().map(|e| &**e as &(dyn Error + 'static))
+    }
+}
+
+impl<E: Into<DynError>> FromIterator<E> for MultiError {
+    fn from_iter<I: IntoIterator<Item = E>>(iter: I) -> Self {
+        Self {errors: iter.into_iter().map(Into::into).collect()}
+    }
+}
+
+impl<E: Into<DynError>> Extend<E> for MultiError {
+    fn extend<I: IntoIterator<Item = E>>(&mut self, iter: I) {
+        self.errors.extend(iter.into_iter().map(Into::into));
+    }
+}
+
+impl IntoIterator for MultiError {
+    type Item = DynError;
+    type IntoIter = std::vec::IntoIter<DynError>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.errors.into_iter()
+    }
+}
@@ -0,0 +1,172 @@
+//! Logging of panics.
+
+use std::cell::Cell;
+use std::fmt;
+use std::panic;
+use std::sync::Once;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::{CheckFailure, DynError, Level, LogFiles, LogRecord};
+
+thread_local! {
+    ///set by `log_and_panic` so the panic hook doesnt log the same panic twice
+    static ALREADY_LOGGED: Cell<bool> = const { Cell::new(false) };
+}
+
+///whether `install_panic_hook` has run
+static HOOK_INSTALLED: AtomicBool = AtomicBool::new(false);
+
+///builds an Error record carrying the active scope trail
+fn panic_record<T: fmt::Display>(event: T) -> LogRecord {
+    with_scope(LogRecord::new(Level::Error, event))
+}
+
+///[record] carrying the active scope trail
+fn with_scope(record: LogRecord) -> LogRecord {
+    match crate::scope::scope_trail() {
+        Some(trail) => record.with_field("scope", trail),
+        None => record,
+    }
+}
+
+/// Appends [event] to [log_file] at `Level::Error` then panics with it.
+/// 
+/// not meant to be used on its own. use `logged_panic!` instead
+#[track_caller]
+pub fn log_and_panic<T: fmt::Display, L: LogFiles>(event: T, log_file: L) -> ! {
+    panic_with(panic_record(&event), event, log_file)
+}
+
+/// Appends [event] to [log_file] at `Level::Error` with the Debug formatted values in [context] as fields, then panics with it.
+/// 
+/// not meant to be used on its own. use `logged_panic!(ctx: [..], event)` instead
+#[track_caller]
+pub fn log_and_panic_with_context<T: fmt::Display, L: LogFiles>(event: T, context: &[(&str, &dyn fmt::Debug)], log_file: L) -> ! {
+    let record = context.iter()
+        .fold(panic_record(&event), |record, (name, value)| record.with_field(name, format_args!("{:?}", value)));
+    panic_with(record, event, log_file)
+}
+
+///writes [record] then panics with [event]
+#[track_caller]
+fn panic_with<T: fmt::Display, L: LogFiles>(record: LogRecord, event: T, log_file: L) -> ! {
+    crate::write_record(&record, log_file);
+    let (pid, id) = (record.pid, record.id);
+    crate::crash::mark(&record.with_field("location", std::panic::Location::caller()));
+    if HOOK_INSTALLED.load(Ordering::Relaxed) {
+        ALREADY_LOGGED.with(|l| l.set(true));
+    }
+    let event = crate::redact::redact_text(&event.to_string());
+    let writes_ids = crate::config::config().text_format.writes_record_ids();
+    if let (true, Some(pid), Some(id)) = (writes_ids, pid, id) {
+        panic!("{} (log records {}:{} and earlier)", event, pid, id)
+    }
+    panic!("{}", event)
+}
+
+/// Handles a failed `check!` according to `LoggerBuilder::check_failure`.
+/// 
+/// either behaves like `log_and_panic`, or logs [event] at `Level::Error`, prints it to stderr
+/// and exits the process with the configured code without unwinding.\
+/// [event] is logged by its `Display`. `check!` goes through `check_failed_hint` instead,
+/// so errors keep their `LogDisplay`, `Help`, error id and debug field.
+#[track_caller]
+pub fn check_failed<T: fmt::Display, L: LogFiles>(event: T, log_file: L) -> ! {
+    crate::hook::report_display(&event);
+    if crate::collect::collecting() {
+        crate::collect::skip(panic_record(&event), event.to_string().into(), log_file)
+    }
+    fail(panic_record(&event), event, None, log_file)
+}
+
+/// Handles a failed `check!` like `check_failed`, adding a hint on how to fix it.
+/// 
+/// uses [hint] if supplied, otherwise the `Help` of the error.
+/// the hint is logged as a `hint` field and added to the panic or exit message.\
+/// not meant to be used on its own. use `check!(x, hint: ..)` instead
+#[track_caller]
+pub fn check_failed_hint<L: LogFiles>(e: DynError, hint: Option<&str>, log_file: L) -> ! {
+    fail_hinted(e, hint, Level::Error, None, log_file)
+}
+
+///logs [e] at [level] with its hint then panics or exits, as set by [failure] or the configuration
+#[track_caller]
+pub(crate) fn fail_hinted<L: LogFiles>(e: DynError, hint: Option<&str>, level: Level, failure: Option<CheckFailure>, log_file: L) -> ! {
+    crate::hook::report(&*e);
+    crate::display_hint::check(&*e, &log_file);
+    let record = with_scope(crate::log_display::error_record(level, &*e));
+    let hint = hint.map(str::to_string).or_else(|| crate::help(&*e));
+    if crate::collect::collecting() {
+        let record = match &hint {
+            Some(hint) => record.with_field("hint", hint),
+            None => record,
+        };
+        crate::collect::skip(record, e, log_file)
+    }
+    match hint {
+        Some(hint) => fail(record.with_field("hint", &hint), format_args!("{}\nhint: {}", e, hint), failure, log_file),
+        None => fail(record, e, failure, log_file),
+    }
+}
+
+///writes [record] then panics with [event] or exits, as set by [failure] or the configuration
+#[track_caller]
+fn fail<T: fmt::Display, L: LogFiles>(record: LogRecord, event: T, failure: Option<CheckFailure>, log_file: L) -> ! {
+    let failure = failure.unwrap_or_else(|| crate::config::config().check_failure);
+    match failure {
+        CheckFailure::Panic      => panic_with(record, event, log_file),
+        CheckFailure::Exit(code) => {
+            crate::write_record(&record, log_file);
+            crate::crash::mark(&record.with_field("location", std::panic::Location::caller()));
+            let _ = std::io::Write::flush(&mut std::io::stdout());
+            eprintln!("Error: {}", crate::redact::redact_text(&event.to_string()));
+            std::process::exit(code)
+        }
+    }
+}
+
+/// Installs a panic hook that logs every panic to the panicking thread's `default_log()`.
+/// 
+/// the record includes the panic location and the active `scope!` stack.\
+/// panics raised by `logged_panic!` are not logged twice.\
+/// writes a bug report bundle if `LoggerBuilder::bundle_on_panic` was set, and a crash marker if `LoggerBuilder::crash_marker` was.\
+/// the previously installed hook still runs afterwards. installing more than once has no effect.
+/// 
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// install_panic_hook();
+/// let result = std::panic::catch_unwind(|| {
+///     let _s = scope!("loading save");
+///     panic!("save was empty");
+/// });
+/// assert!(result.is_err());
+///# }
+/// ```
+pub fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !ALREADY_LOGGED.try_with(|l| l.replace(false)).unwrap_or(false) {
+                let payload = info.payload();
+                let message = payload.downcast_ref::<&str>().copied()
+                    .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+                    .unwrap_or("Box<dyn Any>");
+                let mut record = panic_record(format!("panicked: {}", message));
+                if let Some(location) = info.location() {
+                    record = record.with_field("location", location);
+                }
+                let _ = crate::try_write_record(&record, &crate::default_log());
+                crate::crash::mark(&record);
+            }
+            let bundle_dir = crate::config::config().panic_bundle_dir.clone();
+            if let Some(dir) = bundle_dir {
+                let _ = crate::capture::panic_bundle(&dir);
+            }
+            previous(info)
+        }));
+        HOOK_INSTALLED.store(true, Ordering::Relaxed);
+    });
+}
@@ -0,0 +1,113 @@
+//! Asking an error for data by type without knowing its concrete type.
+//!
+//! a stable stand in for the standard library's unstable `Error::provide`.
+//! implement `Provide` on your error then `register_provider::<YourError>()` once at startup.
+
+use std::any::{Any, TypeId};
+use std::error::Error;
+
+use crate::{Attached, __DowncastThrough};
+use crate::registry::Registry;
+
+/// A request for a reference of one type, filled in by `Provide::provide`.
+pub struct Request<'a> {
+    wanted: TypeId,
+    found: Option<&'a dyn Any>,
+}
+
+impl<'a> Request<'a> {
+    ///answers the request with [value] if it asks for a [T] and isnt answered yet
+    pub fn provide_ref<T: Any>(&mut self, value: &'a T) -> &mut Self {
+        if self.would_be_satisfied_by::<T>() {
+            self.found = Some(value);
+        }
+        self
+    }
+
+    ///whether providing a [T] would answer the request
+    pub fn would_be_satisfied_by<T: Any>(&self) -> bool {
+        self.found.is_none() && self.wanted == TypeId::of::<T>()
+    }
+}
+
+/// Implemented by errors that expose data, like backtraces, spans or domain values, to code that doesnt know their type.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# use std::{error, fmt};
+/// #[derive(Debug, PartialEq)]
+/// struct Span {start: usize, end: usize}
+///
+/// #[derive(Debug)]
+/// struct SyntaxError {span: Span, expected: &'static str}
+///# impl fmt::Display for SyntaxError {
+///#     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///#         write!(f, "expected {}", self.expected)
+///#     }
+///# }
+///# impl error::Error for SyntaxError {}
+/// impl Provide for SyntaxError {
+///     fn provide<'a>(&'a self, request: &mut Request<'a>) {
+///         request.provide_ref(&self.span);
+///     }
+/// }
+///
+/// fn underline(e: &DynError) -> Option<String> {
+///     let span = request_ref::<Span>(&**e)?;
+///     Some(" ".repeat(span.start) + &"^".repeat(span.end - span.start))
+/// }
+///# fn main() {
+/// register_provider::<SyntaxError>();
+/// let e: DynError = Box::new(SyntaxError {span: Span {start: 4, end: 6}, expected: "a number"});
+/// assert_eq!(underline(&e).unwrap(), "    ^^");
+/// assert!(request_ref::<std::backtrace::Backtrace>(&*e).is_none());
+///# }
+/// ```
+pub trait Provide {
+    ///answers [request] with any of this error's data it asks for
+    fn provide<'a>(&'a self, request: &mut Request<'a>);
+}
+
+///a downcasting provider for one registered type
+type Provider = for<'a> fn(&'a (dyn Error + 'static), &mut Request<'a>);
+
+///every registered provider
+static REGISTRY: Registry<Provider> = Registry::new();
+
+///downcasts to [T] and lets it answer the request
+fn ask<'a, T: Error + Provide + 'static>(e: &'a (dyn Error + 'static), request: &mut Request<'a>) {
+    if let Some(e) = e.__downcast_through::<T>() {
+        Provide::provide(e, request);
+    }
+}
+
+/// Registers [T] so `request_ref` can see its `Provide` impl through a `DynError`.
+///
+/// registering the same type twice has no effect.
+pub fn register_provider<T: Error + Provide + 'static>() {
+    REGISTRY.register::<T>(ask::<T>);
+}
+
+/// Returns a [T] provided by [e] or any error in its source chain.
+///
+/// asks every registered `Provide` type, and also finds values attached with `Attach::attach`.
+/// the first error in the chain that has a [T] wins.
+pub fn request_ref<'a, T: Any>(e: &'a (dyn Error + 'static)) -> Option<&'a T> {
+    let registry = REGISTRY.read();
+    let mut current = Some(e);
+    while let Some(e) = current {
+        let mut request = Request {wanted: TypeId::of::<T>(), found: None};
+        for (_, ask) in registry.iter() {
+            ask(e, &mut request);
+        }
+        if let Some(found) = request.found.and_then(|found| found.downcast_ref::<T>()) {
+            return Some(found)
+        }
+        if let Some(found) = e.downcast_ref::<Attached>().and_then(Attached::attachment::<T>) {
+            return Some(found)
+        }
+        current = e.source();
+    }
+    None
+}
@@ -0,0 +1,97 @@
+//! Field values that are measurements, shown readably in text logs and as plain numbers in JSON.
+
+use std::fmt;
+use std::time::Duration;
+
+/// A field value with a unit, added to a record with `LogRecord::with_quantity`.
+///
+/// the text format writes its `Display`, like `1.23s` or `4.5 MiB`, and `JsonFormat` writes `value()`.
+pub trait Quantity: fmt::Display {
+    ///the measurement as a plain number in the base unit, like seconds or bytes
+    fn value(&self) -> f64;
+}
+
+/// A duration, displayed like `850ns`, `12.30µs`, `450.25ms`, `1.23s` or `2m 3.40s`. its value is in seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Elapsed(pub Duration);
+
+impl fmt::Display for Elapsed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let secs = self.0.as_secs_f64();
+        match self.0 {
+            d if d < Duration::from_micros(1) => write!(f, "{}ns", d.as_nanos()),
+            d if d < Duration::from_millis(1) => write!(f, "{:.2}µs", secs * 1e6),
+            d if d < Duration::from_secs(1)   => write!(f, "{:.2}ms", secs * 1e3),
+            d if d < Duration::from_secs(60)  => write!(f, "{:.2}s", secs),
+            d => write!(f, "{}m {:.2}s", d.as_secs() / 60, secs % 60.0),
+        }
+    }
+}
+
+impl Quantity for Elapsed {
+    fn value(&self) -> f64 {
+        self.0.as_secs_f64()
+    }
+}
+
+/// A number of bytes, displayed like `512 B` or `4.5 MiB`. its value is in bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Bytes(pub u64);
+
+///writes [bytes] with the largest binary unit that keeps it at 1 or more
+fn write_bytes(f: &mut fmt::Formatter, bytes: f64) -> fmt::Result {
+    const UNITS: [&str; 6] = ["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+    if bytes < 1024.0 {
+        return write!(f, "{} B", bytes.round())
+    }
+    let mut scaled = bytes / 1024.0;
+    let mut unit = 0;
+    while scaled >= 1024.0 && unit < UNITS.len() - 1 {
+        scaled /= 1024.0;
+        unit += 1;
+    }
+    write!(f, "{:.1} {}", scaled, UNITS[unit])
+}
+
+impl fmt::Display for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_bytes(f, self.0 as f64)
+    }
+}
+
+impl Quantity for Bytes {
+    fn value(&self) -> f64 {
+        self.0 as f64
+    }
+}
+
+/// Bytes moved over a duration, displayed like `4.5 MiB/s`. its value is in bytes per second.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Throughput {
+    pub bytes: u64,
+    pub over: Duration,
+}
+
+impl Throughput {
+    ///[bytes] moved in [over]
+    pub fn new(bytes: u64, over: Duration) -> Self {
+        Self {bytes, over}
+    }
+}
+
+impl fmt::Display for Throughput {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_bytes(f, self.value())?;
+        f.write_str("/s")
+    }
+}
+
+impl Quantity for Throughput {
+    ///zero when no time passed
+    fn value(&self) -> f64 {
+        match self.over.as_secs_f64() {
+            secs if secs > 0.0 => self.bytes as f64 / secs,
+            _ => 0.0,
+        }
+    }
+}
@@ -0,0 +1,484 @@
+//! The record format written by the logger, and a reader that parses it back.
+//!
+//! every record is written as a single header line followed by any extra lines of a multi-line message:
+//! ```text
+//! 2026-10-15T12:34:56.789Z INFO  level loaded
+//! 2026-10-15T12:34:57.001Z WARN  {peer=10.0.0.1 reason="timed out"} connection lost
+//! ```
+//! fields are only written when present. values containing spaces or special characters are quoted.\
+//! the level can also be written with the markers described by `LevelMarkers`, the reader accepts all of them.
+
+use std::cell::RefCell;
+use std::convert::TryFrom;
+use std::fmt::{self, Write as _};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::{DynResult, Level, Quantity, TextFormat};
+
+/// A single logged event.
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// let record = LogRecord::new(Level::Warn, "connection lost")
+///     .with_field("peer", "10.0.0.1");
+/// let parsed: LogRecord = record.to_string().parse().unwrap();
+/// assert_eq!(parsed.message, "connection lost");
+/// assert_eq!(parsed.field("peer"), Some("10.0.0.1"));
+///# }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct LogRecord {
+    pub timestamp: SystemTime,
+    pub level: Level,
+    pub message: String,
+    pub fields: Vec<(String, String)>,
+    ///the values of the fields added with `with_quantity` as plain numbers, by field name
+    pub quantities: Vec<(String, String)>,
+    pub thread: Option<String>,
+    ///the number dynerr gave the thread that created the record, counting up from 1 in the order threads first log
+    pub thread_id: Option<u64>,
+    ///the id of the process that created the record
+    pub pid: Option<u32>,
+    ///the number of the record in the process that created it, counting up from 1
+    pub id: Option<u64>,
+}
+
+impl LogRecord {
+    ///creates a record timestamped with the configured `Clock` and tagged with the current thread
+    pub fn new<M: fmt::Display>(level: Level, message: M) -> Self {
+        let current = std::thread::current();
+        Self {
+            timestamp: crate::config::config().clock.now(),
+            level,
+            message: message.to_string(),
+            fields: Vec::new(),
+            quantities: Vec::new(),
+            thread: current.name().map(str::to_string),
+            thread_id: THREAD_ID.with(|id| *id),
+            pid: Some(std::process::id()),
+            id: Some(NEXT_ID.fetch_add(1, Ordering::Relaxed)),
+        }
+    }
+
+    ///turns the record into a new one at [level] with [message], reusing its buffers
+    fn renew<M: fmt::Display>(&mut self, level: Level, message: M) {
+        self.timestamp = crate::config::config().clock.now();
+        self.level = level;
+        self.message.clear();
+        if write!(self.message, "{}", message).is_err() {
+            self.message = message.to_string();
+        }
+        self.fields.clear();
+        self.quantities.clear();
+        self.id = Some(NEXT_ID.fetch_add(1, Ordering::Relaxed));
+    }
+
+    ///appends a key=value field to the record
+    pub fn with_field<K: fmt::Display, V: fmt::Display>(mut self, key: K, value: V) -> Self {
+        self.fields.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    ///replaces the time the record was created at with [timestamp]
+    pub fn with_timestamp(mut self, timestamp: SystemTime) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    /// Appends a key=value field holding a measurement like an `Elapsed` time or a number of `Bytes`.
+    /// 
+    /// text logs show it readably and `JsonFormat` writes it as a number in its base unit.
+    /// 
+    ///# Example
+    /// ```rust
+    ///# use dynerr::*;
+    ///# use std::time::Duration;
+    ///# fn main() {
+    /// let record = LogRecord::new(Level::Info, "upload finished")
+    ///     .with_quantity("took", Elapsed(Duration::from_millis(1230)))
+    ///     .with_quantity("size", Bytes(4_718_592))
+    ///     .with_quantity("rate", Throughput::new(4_718_592, Duration::from_secs(2)));
+    /// assert_eq!(record.field("took"), Some("1.23s"));
+    /// assert_eq!(record.field("size"), Some("4.5 MiB"));
+    /// assert!(record.to_string().contains(r#"rate="2.2 MiB/s""#));
+    /// let json = JsonFormat::new().format(&record);
+    /// assert!(json.contains(r#""took":1.23,"size":4718592,"rate":2359296"#));
+    ///# }
+    /// ```
+    pub fn with_quantity<K: fmt::Display, Q: Quantity>(mut self, key: K, value: Q) -> Self {
+        let key = key.to_string();
+        let number = value.value();
+        if number.is_finite() {
+            self.quantities.push((key.clone(), number.to_string()));
+        }
+        self.fields.push((key, value.to_string()));
+        self
+    }
+
+    ///returns the value of the first field named [key]
+    pub fn field(&self, key: &str) -> Option<&str> {
+        self.fields.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+}
+
+impl fmt::Display for LogRecord {
+    ///formats the record with the default `TextFormat`, without a trailing newline
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        TextFormat::default().write(f, self)
+    }
+}
+
+///the number the next thread to log is given
+static NEXT_THREAD_ID: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    ///the number of the current thread, given the first time it creates a record
+    static THREAD_ID: Option<u64> = Some(NEXT_THREAD_ID.fetch_add(1, Ordering::Relaxed));
+}
+
+thread_local! {
+    ///the record `log!` builds each event into, reused so logging doesnt allocate once its message has grown
+    static SCRATCH: RefCell<Option<LogRecord>> = const { RefCell::new(None) };
+}
+
+///runs [f] on a record at [level] with [message], reusing this thread's record instead of allocating a new one
+pub(crate) fn with_record<M: fmt::Display, R>(level: Level, message: M, f: impl FnOnce(&LogRecord) -> R) -> R {
+    SCRATCH.with(|scratch| match scratch.try_borrow_mut() {
+        Ok(mut scratch) => {
+            let record = match &mut *scratch {
+                Some(record) => {record.renew(level, message); record}
+                None => scratch.insert(LogRecord::new(level, message)),
+            };
+            f(record)
+        }
+        //an event logged while another is being written (from a Display impl) gets a record of its own
+        Err(_) => f(&LogRecord::new(level, message)),
+    })
+}
+
+///the field the text format stores the thread in, as `name#id`
+pub(crate) const THREAD_FIELD: &str = "thread";
+
+///the field the text format stores the process and record ids in, as `pid:id`
+pub(crate) const RECORD_FIELD: &str = "record";
+
+///the id of the next record created in this process
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+
+/// Error returned when a line isnt a valid record header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseRecordError {
+    pub line: String,
+    pub reason: &'static str,
+}
+
+impl fmt::Display for ParseRecordError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Dynerr: invalid log record ({}): {}", self.reason, self.line)
+    }
+}
+
+impl std::error::Error for ParseRecordError {}
+
+impl std::str::FromStr for LogRecord {
+    type Err = ParseRecordError;
+
+    ///parses a record header. continuation lines must be appended to the message separately
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let fail = |reason| ParseRecordError {line: line.to_string(), reason};
+        let (stamp, rest) = line.split_once(' ').ok_or_else(|| fail("missing timestamp"))?;
+        let timestamp = parse_timestamp(stamp).ok_or_else(|| fail("invalid timestamp"))?;
+        let mut rest = rest.trim_start();
+        if rest.starts_with(|c: char| !c.is_ascii()) {
+            rest = rest.split_once(' ').map_or("", |(_, r)| r).trim_start();
+        }
+        //the level is padded to 5 characters then followed by a space, and the message starts right after
+        let (level, mut rest) = match rest.strip_prefix('[') {
+            Some(bracketed) => {
+                let (level, rest) = bracketed.split_once(']').ok_or_else(|| fail("invalid level"))?;
+                (level, rest.strip_prefix(' ').unwrap_or(rest))
+            }
+            None => {
+                let (level, rest) = rest.split_once(' ').unwrap_or((rest, ""));
+                let padding = rest.len() - rest.trim_start_matches(' ').len();
+                (level, &rest[padding.min(5usize.saturating_sub(level.len()))..])
+            }
+        };
+        let level = level.trim().parse().map_err(|_| fail("invalid level"))?;
+        let mut fields = Vec::new();
+        if let Some(block) = rest.strip_prefix('{') {
+            let (parsed, remaining) = parse_fields(block).ok_or_else(|| fail("invalid fields"))?;
+            fields = parsed;
+            rest = remaining.strip_prefix(' ').unwrap_or(remaining);
+        }
+        let (mut thread, mut thread_id) = (None, None);
+        if let Some(i) = fields.iter().position(|(k, _)| k == THREAD_FIELD) {
+            let (_, value) = fields.remove(i);
+            let (name, id) = value.rsplit_once('#').unwrap_or((&value, ""));
+            thread = Some(name.to_string()).filter(|n| !n.is_empty());
+            thread_id = id.parse().ok();
+        }
+        let (mut pid, mut id) = (None, None);
+        //a `record` field that isnt `pid:id` was added by the user and stays a field
+        if let Some((p, i)) = fields.first().filter(|(k, _)| k == RECORD_FIELD).and_then(|(_, v)| parse_ids(v)) {
+            fields.remove(0);
+            (pid, id) = (Some(p), Some(i));
+        }
+        Ok(Self {timestamp, level, message: rest.to_string(), fields, quantities: Vec::new(), thread, thread_id, pid, id})
+    }
+}
+
+///parses the `pid:id` value of the record field
+fn parse_ids(value: &str) -> Option<(u32, u64)> {
+    let (pid, id) = value.split_once(':')?;
+    Some((pid.parse().ok()?, id.parse().ok()?))
+}
+
+///parses a possibly quoted value, returning it and the unparsed remainder
+fn parse_value(s: &str) -> Option<(String, &str)> {
+    if let Some(quoted) = s.strip_prefix('"') {
+        let mut value = String::new();
+        let mut chars = quoted.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"'     => return Some((value, &quoted[i + 1..])),
+                '\\'    => match chars.next()?.1 {
+                    'n' => value.push('\n'),
+                    'r' => value.push('\r'),
+                    't' => value.push('\t'),
+                    c   => value.push(c),
+                },
+                c       => value.push(c),
+            }
+        }
+        None
+    } else {
+        let end = s.find(|c: char| c.is_whitespace() || matches!(c, '=' | '}')).unwrap_or(s.len());
+        if end == 0 {return None}
+        Some((s[..end].to_string(), &s[end..]))
+    }
+}
+
+///parses the inside of a `{key=value ...}` block, returning the fields and the text after the closing brace
+fn parse_fields(mut s: &str) -> Option<(Vec<(String, String)>, &str)> {
+    let mut fields = Vec::new();
+    loop {
+        s = s.trim_start_matches(' ');
+        if let Some(rest) = s.strip_prefix('}') {return Some((fields, rest))}
+        let (key, rest) = parse_value(s)?;
+        let (value, rest) = parse_value(rest.strip_prefix('=')?)?;
+        fields.push((key, value));
+        s = rest;
+    }
+}
+
+/// Reads the records of a dynerr log file back.
+///
+/// lines that dont start a record are treated as extra lines of the previous records message.\
+/// lines before the first record are reported as `ParseRecordError`s.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() -> DynResult<()> {
+/// clean!("reader.log");
+/// log_warn!("first", "reader.log");
+/// log!("second", "reader.log");
+/// let records = LogReader::open("reader.log")?.collect::<DynResult<Vec<_>>>()?;
+/// assert_eq!(records[0].level, Level::Warn);
+/// assert_eq!(records[1].message, "second");
+///# clean!("reader.log");
+///# Ok(())
+///# }
+/// ```
+#[derive(Debug)]
+pub struct LogReader<R> {
+    lines: io::Lines<BufReader<R>>,
+    pending: Option<LogRecord>,
+}
+
+impl LogReader<File> {
+    ///opens a log file for reading
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Self::new(File::open(path)?))
+    }
+}
+
+impl<R: Read> LogReader<R> {
+    ///reads records from any reader
+    pub fn new(reader: R) -> Self {
+        Self {lines: BufReader::new(reader).lines(), pending: None}
+    }
+}
+
+impl<R: Read> Iterator for LogReader<R> {
+    type Item = DynResult<LogRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.lines.next() {
+                Some(Ok(line)) => line,
+                Some(Err(e)) => return Some(Err(e.into())),
+                None => return self.pending.take().map(Ok),
+            };
+            match line.parse::<LogRecord>() {
+                Ok(record) => {
+                    if let Some(done) = self.pending.replace(record) {return Some(Ok(done))}
+                }
+                Err(e) => match &mut self.pending {
+                    Some(record) => {
+                        record.message.push('\n');
+                        record.message.push_str(&line);
+                    }
+                    None => return Some(Err(e.into())),
+                },
+            }
+        }
+    }
+}
+
+///formats a SystemTime as an RFC 3339 UTC timestamp with milliseconds
+pub(crate) struct Timestamp(pub SystemTime);
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let since = self.0.duration_since(UNIX_EPOCH).unwrap_or_default();
+        let secs = since.as_secs();
+        let (year, month, day) = civil_from_days((secs / 86400) as i64);
+        let rem = secs % 86400;
+        write!(f, "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+            year, month, day, rem / 3600, rem / 60 % 60, rem % 60, since.subsec_millis())
+    }
+}
+
+///parses a timestamp written by `Timestamp`
+fn parse_timestamp(s: &str) -> Option<SystemTime> {
+    let s = s.strip_suffix('Z')?;
+    let (date, time) = s.split_once('T')?;
+    let mut date = date.splitn(3, '-').map(str::parse::<u32>);
+    let (year, month, day) = (date.next()?.ok()?, date.next()?.ok()?, date.next()?.ok()?);
+    let (time, millis) = time.split_once('.').unwrap_or((time, "0"));
+    let mut time = time.splitn(3, ':').map(str::parse::<u64>);
+    let (hour, min, sec) = (time.next()?.ok()?, time.next()?.ok()?, time.next()?.ok()?);
+    let millis: u64 = millis.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || min > 59 || sec > 60 || millis > 999 {return None}
+    let days = u64::try_from(days_from_civil(year as i64, month, day)).ok()?;
+    Some(UNIX_EPOCH + Duration::from_millis(((days * 86400 + hour * 3600 + min * 60 + sec) * 1000) + millis))
+}
+
+///converts days since the unix epoch to a (year, month, day) date
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 {mp + 3} else {mp - 9} as u32;
+    (yoe + era * 400 + (month <= 2) as i64, month, day)
+}
+
+///converts a (year, month, day) date to days since the unix epoch
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 {year - 1} else {year};
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = if month > 2 {month - 3} else {month + 9} as i64;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LevelMarkers;
+
+    #[test]
+    fn round_trip() {
+        let mut record = LogRecord::new(Level::Error, "{not fields} but a message")
+            .with_field("path", "C:\\my files\\save.dat")
+            .with_field("empty", "")
+            .with_field("weird", "a=b \"c\" }");
+        record.timestamp = UNIX_EPOCH + Duration::from_millis(1_760_531_696_789);
+        //the default format leaves out the thread
+        record.thread = None;
+        record.thread_id = None;
+        record.pid = None;
+        record.id = None;
+        let line = record.to_string();
+        assert!(line.starts_with("2025-10-15T12:34:56.789Z ERROR {"));
+        assert_eq!(line.parse::<LogRecord>().unwrap(), record);
+    }
+
+    #[test]
+    fn multi_line() {
+        let text = "garbage\n2025-10-15T12:34:56.789Z INFO  first\n  second line\n2025-10-15T12:34:56.790Z DEBUG next";
+        let records: Vec<_> = LogReader::new(text.as_bytes()).collect();
+        assert!(records[0].is_err());
+        assert_eq!(records[1].as_ref().unwrap().message, "first\n  second line");
+        assert_eq!(records[2].as_ref().unwrap().level, Level::Debug);
+    }
+
+    #[test]
+    fn keeps_leading_spaces() {
+        for markers in [LevelMarkers::Plain, LevelMarkers::Brackets, LevelMarkers::Emoji] {
+            for level in [Level::Error, Level::Info] {
+                for message in ["  indented", " {braced}", "plain", ""] {
+                    let record = LogRecord::new(level, message);
+                    let line = TextFormat::new().markers(markers).format(&record);
+                    let parsed = line.parse::<LogRecord>().unwrap();
+                    assert_eq!((parsed.level, parsed.message.as_str()), (level, message), "{}", line);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn thread() {
+        let record = std::thread::Builder::new().name("loader".to_string())
+            .spawn(|| LogRecord::new(Level::Info, "loaded").with_field("n", 1))
+            .unwrap().join().unwrap();
+        let line = TextFormat::new().thread(true).format(&record);
+        let parsed = line.parse::<LogRecord>().unwrap();
+        assert_eq!(parsed.thread.as_deref(), Some("loader"));
+        assert_eq!(parsed.thread_id, record.thread_id);
+        assert_eq!(parsed.fields, record.fields);
+        let here = LogRecord::new(Level::Info, "here");
+        assert!(here.thread_id.is_some() && here.thread_id != record.thread_id);
+    }
+
+    #[test]
+    fn record_ids() {
+        let (first, second) = (LogRecord::new(Level::Info, "a"), LogRecord::new(Level::Info, "b").with_field("n", 1));
+        assert!(first.id < second.id);
+        let line = TextFormat::new().record_ids(true).thread(true).format(&second);
+        let parsed = line.parse::<LogRecord>().unwrap();
+        assert_eq!((parsed.pid, parsed.id), (Some(std::process::id()), second.id));
+        assert_eq!(parsed.fields, second.fields);
+        assert!(TextFormat::new().record_ids(true).deterministic(true).format(&first).ends_with("INFO  a"));
+        let user = LogRecord::new(Level::Info, "c").with_field("record", "row-7");
+        let parsed = TextFormat::new().format(&user).parse::<LogRecord>().unwrap();
+        assert_eq!((parsed.pid, parsed.id), (None, None));
+        assert_eq!(parsed.fields, user.fields);
+        let both = TextFormat::new().record_ids(true).format(&user).parse::<LogRecord>().unwrap();
+        assert_eq!((both.pid, both.id, both.fields), (user.pid, user.id, user.fields));
+    }
+
+    #[test]
+    fn markers() {
+        let record = LogRecord::new(Level::Warn, "careful").with_field("a", "b");
+        for markers in [LevelMarkers::Plain, LevelMarkers::Brackets, LevelMarkers::Emoji] {
+            let line = TextFormat::new().markers(markers).format(&record);
+            assert_eq!(line.parse::<LogRecord>().unwrap().message, "careful");
+        }
+    }
+}
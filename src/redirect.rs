@@ -0,0 +1,120 @@
+//! Temporary redirection of the default log file.
+
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+///the log file used when no file is supplied and nothing is redirected
+pub const DEFAULT_LOG: &str = "event.log";
+
+thread_local! {
+    ///active redirects for this thread, innermost last
+    static REDIRECTS: RefCell<Vec<(u64, String)>> = const { RefCell::new(Vec::new()) };
+    ///id handed to the next guard created on this thread
+    static NEXT_ID: RefCell<u64> = const { RefCell::new(0) };
+}
+
+/// Returns the log file the macros use when no file is supplied.
+/// 
+/// this is "event.log" unless the current thread has an active `redirect_log` guard.
+pub fn default_log() -> String {
+    REDIRECTS.with(|r| r.borrow().last().map(|(_, path)| path.clone()))
+        .unwrap_or_else(|| DEFAULT_LOG.to_string())
+}
+
+///the log file the current thread is redirected to, if any
+pub(crate) fn redirected_log() -> Option<String> {
+    REDIRECTS.try_with(|r| r.borrow().last().map(|(_, path)| path.clone())).ok().flatten()
+}
+
+/// Routes default-path logging on the current thread to [log_file] until the guard is dropped.
+/// 
+/// redirects nest, the innermost live guard wins.\
+/// other threads and logging with an explicit file are unaffected.
+/// 
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// {
+///     let _guard = redirect_log("debug_session.log");
+///     log!("written to debug_session.log");
+/// }
+/// log!("written to event.log again");
+///# clean!("debug_session.log");
+///# }
+/// ```
+#[must_use = "the redirect ends as soon as the guard is dropped"]
+pub fn redirect_log(log_file: &str) -> RedirectGuard {
+    let id = NEXT_ID.with(|n| {
+        let mut n = n.borrow_mut();
+        *n += 1;
+        *n
+    });
+    REDIRECTS.with(|r| r.borrow_mut().push((id, log_file.to_string())));
+    RedirectGuard {id, _not_send: PhantomData}
+}
+
+/// Ends a redirect started by `redirect_log` when dropped.
+#[derive(Debug)]
+pub struct RedirectGuard {
+    id: u64,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for RedirectGuard {
+    fn drop(&mut self) {
+        let _ = REDIRECTS.try_with(|r| r.borrow_mut().retain(|(id, _)| *id != self.id));
+    }
+}
+
+/// Runs [f] with default-path logging on the current thread sent to a fresh temporary file, then deletes the file.
+/// 
+/// meant for tests, so parallel tests stop sharing "event.log". inside [f], `default_log()` returns the temporary path.\
+/// the file is deleted even if [f] panics. logging with an explicit file is unaffected.
+/// 
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// let lines = with_temp_log(|| {
+///     log!("loaded level 1");
+///     log_warn!("level 1 has no exit");
+///     std::fs::read_to_string(default_log()).unwrap().lines().count()
+/// });
+/// assert_eq!(lines, 2);
+///# }
+/// ```
+pub fn with_temp_log<R, F: FnOnce() -> R>(f: F) -> R {
+    static NEXT_TEMP: AtomicU64 = AtomicU64::new(0);
+    let name = format!("dynerr-{}-{}.log", std::process::id(), NEXT_TEMP.fetch_add(1, Ordering::Relaxed));
+    let path = std::env::temp_dir().join(name).to_string_lossy().into_owned();
+    ///cleans the temporary log up when dropped, even while unwinding
+    struct Cleanup(String);
+    impl Drop for Cleanup {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+            crate::session::untrack(&self.0);
+        }
+    }
+    let _cleanup = Cleanup(path.clone());
+    let _guard = redirect_log(&path);
+    f()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nesting() {
+        let outer = redirect_log("outer.log");
+        let inner = redirect_log("inner.log");
+        assert_eq!(default_log(), "inner.log");
+        std::thread::spawn(|| assert_eq!(default_log(), DEFAULT_LOG)).join().unwrap();
+        drop(outer);
+        assert_eq!(default_log(), "inner.log");
+        drop(inner);
+        assert_eq!(default_log(), DEFAULT_LOG);
+    }
+}
@@ -0,0 +1,188 @@
+//! Named sets of error types whose handlers are checked for completeness at compile time.
+
+/// Declares a named set of error types.
+///
+/// creates a module named after the set holding a type alias for every entry, a `Kind` enum with one variant per entry,
+/// `ALL` (every kind) and `kind_of(&dyn Error)`.\
+/// `match_registered!` uses the set to refuse to compile when a handler is missing, so adding an error type to the set
+/// points out every place that needs to handle it.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+/// use std::num::ParseIntError;
+///
+/// register_errors!(pub ConfigErrors {
+///     Io = std::io::Error,
+///     Parse = ParseIntError,
+/// });
+///
+/// fn describe(e: DynError) -> &'static str {
+///     match_registered!(ConfigErrors, e,
+///         Io {arm _ => "io", _ => "io"},
+///         Parse {arm _ => "parse", _ => "parse"},
+///         _ => "unknown"
+///     )
+/// }
+///# fn main() {
+/// assert_eq!(ConfigErrors::ALL.len(), 2);
+/// let e: DynError = "x".parse::<u32>().unwrap_err().into();
+/// assert_eq!(ConfigErrors::kind_of(&*e), Some(ConfigErrors::Kind::Parse));
+/// assert_eq!(describe(e), "parse");
+///# }
+/// ```
+///
+/// leaving a type out of `match_registered!` is a compile error:
+/// ```rust,compile_fail
+///# use dynerr::*;
+/// register_errors!(ConfigErrors {
+///     Io = std::io::Error,
+///     Parse = std::num::ParseIntError,
+/// });
+///
+/// fn describe(e: DynError) -> &'static str {
+///     match_registered!(ConfigErrors, e,
+///         Io {arm _ => "io", _ => "io"},
+///         _ => "unknown"
+///     )
+/// }
+///# fn main() {}
+/// ```
+#[macro_export]
+macro_rules! register_errors {
+    ($vis:vis $name:ident {$($variant:ident = $ty:ty),+ $(,)?}) => {
+        #[allow(non_snake_case)]
+        $vis mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            $(pub type $variant = $ty;)+
+
+            /// Every type in the set.
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            pub enum Kind {
+                $($variant),+
+            }
+
+            ///every kind in the set, in declaration order
+            pub const ALL: &[Kind] = &[$(Kind::$variant),+];
+
+            ///the kind of [e], if its type is in the set
+            #[allow(dead_code)]
+            pub fn kind_of(e: &(dyn ::std::error::Error + 'static)) -> Option<Kind> {
+                #[allow(unused_imports)]
+                use $crate::__DowncastThrough as _;
+                $(if e.__downcast_through::<$variant>().is_some() {return Some(Kind::$variant)})+
+                None
+            }
+
+            ///the source of a `dynmatch!` over [error] with a `type` block for every type in the set, to paste in and fill out
+            #[allow(dead_code)]
+            pub fn skeleton(error: &str) -> String {
+                $crate::__skeleton_template!([$crate::__skeleton_source] error; $($ty),+)
+            }
+
+            $crate::__register_skeleton!(($) $($variant)+);
+        }
+    };
+}
+
+///defines the macro `dynmatch_skeleton!` expands to for a set, inside the set's module.
+///the `$` is passed in as a token so the macro can declare its own metavariables
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __register_skeleton {
+    (($d:tt) $($variant:ident)+) => {
+        #[allow(unused_macros)]
+        macro_rules! __skeleton {
+            ($d set:ident, $d e:expr) => {
+                $crate::__skeleton_template!([$crate::dynmatch] $d e; $($d set::$variant),+)
+            };
+        }
+        #[allow(unused_imports)]
+        pub(crate) use __skeleton;
+    };
+}
+
+///the arguments of the `dynmatch!` a skeleton is, given to [callback], so `dynmatch_skeleton!` and `skeleton()` cant drift apart
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __skeleton_template {
+    ([$($callback:tt)*] $e:tt; $($ty:ty),+) => {
+        $($callback)*!($e, $(exhaustive type $ty {arm _ => todo!()},)+ _ => todo!())
+    };
+}
+
+///the source of a skeleton, with the name of the error taken from [error] at runtime
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __skeleton_source {
+    ($error:ident, $($blocks:tt)*) => {
+        format!("dynmatch!({}, {})", $error, stringify!($($blocks)*))
+    };
+}
+
+/// Expands to a `dynmatch!` over [e] with a block for every type in a set declared with `register_errors!`,
+/// each handled with `todo!()`, to start handling a set from.
+///
+/// the expansion compiles as is. `Set::skeleton("e")` returns the same `dynmatch!` as source to paste in and fill out,
+/// for editor code actions or build scripts. both are made from one template, the source naming the types as the set declared them.
+/// the source is on one line with the spacing of `stringify!`, so run it through rustfmt.\
+/// only works in the crate that declared the set.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+/// register_errors!(NetErrors {
+///     Io = std::io::Error,
+///     Utf8 = std::str::Utf8Error,
+/// });
+///
+/// fn handle(e: DynError) -> u16 {
+///     dynmatch_skeleton!(NetErrors, e) //panics with "not yet implemented" until filled out
+/// }
+///# fn main() {
+/// //the spacing of the source isnt fixed, so run it through rustfmt before pasting
+/// let unspaced = |source: &str| source.split_whitespace().collect::<String>();
+/// assert_eq!(unspaced(&NetErrors::skeleton("e")), unspaced("
+/// dynmatch!(e,
+///     exhaustive type std::io::Error {arm _ => todo!()},
+///     exhaustive type std::str::Utf8Error {arm _ => todo!()},
+///     _ => todo!()
+/// )"));
+/// assert!(std::panic::catch_unwind(|| handle("unknown".into())).is_err());
+///# }
+/// ```
+#[macro_export]
+macro_rules! dynmatch_skeleton {
+    ($set:ident, $e:expr) => {
+        $set::__skeleton!($set, $e)
+    };
+}
+
+/// `dynmatch!` over a set declared with `register_errors!` that only compiles if every type in the set is handled.
+///
+/// blocks are named by their entry in the set instead of `type T`, and otherwise work like `dynmatch!` type blocks.
+/// see `register_errors!` for an example.
+#[macro_export]
+macro_rules! match_registered {
+    ($registry:ident, $e:expr, $($variant:ident {$($arms:tt)*}),+ , _ => $end:expr $(,)?) => {{
+        //fails to compile with "non-exhaustive patterns" naming the unhandled type
+        match ::std::option::Option::None::<$registry::Kind> {
+            $(::std::option::Option::Some($registry::Kind::$variant))|+ | ::std::option::Option::None => (),
+        }
+        $crate::__match_registered!(($registry, $e) [] $($variant {$($arms)*},)+ _ => $end)
+    }};
+}
+
+///rewrites the blocks of `match_registered!` into `dynmatch!` type blocks
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __match_registered {
+    (($registry:ident, $e:expr) [$($done:tt)*] _ => $end:expr) => {
+        $crate::dynmatch!($e, $($done)* _ => $end)
+    };
+    (($registry:ident, $e:expr) [$($done:tt)*] $variant:ident {$($arms:tt)*}, $($rest:tt)*) => {
+        $crate::__match_registered!(($registry, $e) [$($done)* type $registry::$variant {$($arms)*},] $($rest)*)
+    };
+}
@@ -0,0 +1,65 @@
+//! The registries that remember what was registered for each error type.
+
+use std::any::TypeId;
+use std::sync::{RwLock, RwLockReadGuard};
+
+///values registered under a key, by default the `TypeId` of the error type they were registered for, in the order they were registered
+pub(crate) struct Registry<V, K = TypeId> {
+    entries: RwLock<Vec<(K, V)>>,
+}
+
+impl<V, K: PartialEq> Registry<V, K> {
+    ///an empty registry
+    pub(crate) const fn new() -> Self {
+        Self {entries: RwLock::new(Vec::new())}
+    }
+
+    ///registers [value] under [key], replacing the value registered under it before in place
+    pub(crate) fn insert(&self, key: K, value: V) {
+        let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+        match entries.iter_mut().find(|(k, _)| *k == key) {
+            Some(entry) => entry.1 = value,
+            None => entries.push((key, value)),
+        }
+    }
+
+    ///every key and value, in the order they were first registered
+    pub(crate) fn read(&self) -> RwLockReadGuard<'_, Vec<(K, V)>> {
+        self.entries.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    ///the first value [f] returns something for
+    pub(crate) fn find_map<R>(&self, f: impl FnMut(&V) -> Option<R>) -> Option<R> {
+        self.read().iter().map(|(_, v)| v).find_map(f)
+    }
+
+    ///removes every value
+    pub(crate) fn clear(&self) {
+        self.entries.write().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+}
+
+impl<V> Registry<V> {
+    ///registers [value] for the type [T], replacing the value registered for it before
+    pub(crate) fn register<T: 'static>(&self, value: V) {
+        self.insert(TypeId::of::<T>(), value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaces_in_place() {
+        let registry: Registry<&str> = Registry::new();
+        registry.register::<u8>("a");
+        registry.register::<u16>("b");
+        registry.register::<u8>("c");
+        let values: Vec<&str> = registry.read().iter().map(|(_, v)| *v).collect();
+        assert_eq!(values, ["c", "b"]);
+        assert_eq!(registry.find_map(|v| (*v == "b").then_some(2)), Some(2));
+        registry.clear();
+        assert!(registry.read().is_empty());
+    }
+}
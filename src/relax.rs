@@ -0,0 +1,191 @@
+//! Conversions between `DynError` and the other common boxed error types.
+//!
+//! `relax!` loosens `Box<dyn Error + Send + Sync>`, `Box<dyn Error + Send>` and `Arc<dyn Error>` results into `DynResult`s.\
+//! `strengthen!` goes the other way. since a `DynError` may not be `Send`, it can only keep the text of the error.\
+//! `SharedDynError` is a cheaply cloneable error for reporting one error to several consumers.
+
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::DynError;
+
+/// Converts a boxed or shared error into a `DynError`. used by `relax!`
+///
+/// errors behind an `Arc` are still seen by `dynmatch!`.
+pub trait Relax {
+    ///the error as a `DynError`
+    fn relax(self) -> DynError;
+}
+
+impl Relax for DynError {
+    fn relax(self) -> DynError {
+        self
+    }
+}
+
+impl Relax for Box<dyn Error + Send> {
+    fn relax(self) -> DynError {
+        self
+    }
+}
+
+impl Relax for Box<dyn Error + Send + Sync> {
+    fn relax(self) -> DynError {
+        self
+    }
+}
+
+impl Relax for Arc<dyn Error> {
+    fn relax(self) -> DynError {
+        Box::new(self)
+    }
+}
+
+impl Relax for Arc<dyn Error + Send + Sync> {
+    fn relax(self) -> DynError {
+        Box::new(self)
+    }
+}
+
+/// Turns the error of a result with a related boxed error type into a `DynError`.
+///
+/// accepts `Box<dyn Error + Send + Sync>`, `Box<dyn Error + Send>`, `Arc<dyn Error>` and `Arc<dyn Error + Send + Sync>` errors.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# use std::sync::Arc;
+/// fn worker() -> Result<u32, Box<dyn std::error::Error + Send + Sync>> {
+///     Ok("12".parse()?)
+/// }
+/// fn cached() -> Result<u32, Arc<dyn std::error::Error + Send + Sync>> {
+///     Err(Arc::new(std::io::Error::from(std::io::ErrorKind::NotFound)))
+/// }
+/// fn run() -> DynResult<u32> {
+///     let i = relax!(worker())?;
+///     Ok(i + relax!(cached())?)
+/// }
+///# fn main() {
+/// let found = dynmatch!(run().unwrap_err(),
+///     type std::io::Error {
+///         arm e if e.kind() == std::io::ErrorKind::NotFound => true,
+///         _ => false
+///     },
+///     _ => false
+/// );
+/// assert!(found);
+///# }
+/// ```
+#[macro_export]
+macro_rules! relax {
+    ($x:expr) => {
+        $x.map_err($crate::Relax::relax)
+    };
+}
+
+/// Turns the error of a `DynResult` into a `Box<dyn Error + Send + Sync>`.
+///
+/// the original error cant cross threads, so it is replaced with a `DetachedError` holding its text and the text of its sources.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// let result: DynResult<u32> = "x".parse::<u32>().map_err(DynError::from);
+/// let sendable = strengthen!(result);
+/// let message = std::thread::spawn(move || sendable.unwrap_err().to_string()).join().unwrap();
+/// assert_eq!(message, "invalid digit found in string");
+///# }
+/// ```
+#[macro_export]
+macro_rules! strengthen {
+    ($x:expr) => {
+        $x.map_err(|e| -> ::std::boxed::Box<dyn ::std::error::Error + Send + Sync> {
+            ::std::boxed::Box::new($crate::DetachedError::new(&*e))
+        })
+    };
+}
+
+/// A thread safe copy of the text of an error and its sources. made by `strengthen!`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetachedError {
+    message: String,
+    debug: String,
+    source: Option<Box<DetachedError>>,
+}
+
+impl DetachedError {
+    ///copies the Display and Debug text of [e] and its sources
+    pub fn new(e: &(dyn Error + 'static)) -> Self {
+        Self {
+            message: e.to_string(),
+            debug: format!("{:?}", e),
+            source: e.source().map(|s| Box::new(Self::new(s))),
+        }
+    }
+
+    ///the Debug text of the original error
+    pub fn debug(&self) -> &str {
+        &self.debug
+    }
+}
+
+impl fmt::Display for DetachedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for DetachedError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source.as_deref().map(|s| s as &(dyn Error + 'static))
+    }
+}
+
+/// A reference counted error that can be cloned and sent between threads.
+/// 
+/// lets one error go to several consumers (log, metrics, response) without formatting it again.\
+/// `dynmatch!` matches the error inside directly, and `relax!` turns it back into a `DynError`.
+/// 
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// let e: Box<dyn std::error::Error + Send + Sync> = Box::new(std::io::Error::from(std::io::ErrorKind::TimedOut));
+/// let shared = e.share();
+/// let for_metrics = shared.clone();
+/// std::thread::spawn(move || log_warn!(for_metrics, "test.log")).join().unwrap();
+/// let timed_out = dynmatch!(shared,
+///     type std::io::Error {
+///         arm e if e.kind() == std::io::ErrorKind::TimedOut => true,
+///         _ => false
+///     },
+///     _ => false
+/// );
+/// assert!(timed_out);
+///# }
+/// ```
+pub type SharedDynError = Arc<dyn Error + Send + Sync>;
+
+/// Converts an error into a `SharedDynError`.
+pub trait Share {
+    ///the error as a `SharedDynError`
+    fn share(self) -> SharedDynError;
+}
+
+impl Share for Box<dyn Error + Send + Sync> {
+    fn share(self) -> SharedDynError {
+        Arc::from(self)
+    }
+}
+
+impl Share for DynError {
+    ///a `DynError` may not be `Send`, so unless it came from a `SharedDynError` only its text is kept, see `DetachedError`
+    fn share(self) -> SharedDynError {
+        match self.downcast_ref::<SharedDynError>() {
+            Some(shared) => shared.clone(),
+            None => Arc::new(DetachedError::new(&*self)),
+        }
+    }
+}
@@ -0,0 +1,78 @@
+//! Per thread stacks of "what was i doing" markers.
+//! 
+//! the active stack is logged by `logged_panic!` and the panic hook, giving a cheap trail that works in release builds.
+
+use std::cell::RefCell;
+use std::marker::PhantomData;
+
+thread_local! {
+    ///active scopes for this thread, outermost first
+    static SCOPES: RefCell<Vec<(u64, String)>> = const { RefCell::new(Vec::new()) };
+    ///id handed to the next scope entered on this thread
+    static NEXT_ID: RefCell<u64> = const { RefCell::new(0) };
+}
+
+/// Pushes [description] onto the current thread's scope stack until the guard is dropped.
+/// 
+/// not meant to be used on its own. use `scope!` instead
+#[must_use = "the scope ends as soon as the guard is dropped"]
+pub fn enter_scope(description: String) -> ScopeGuard {
+    let id = NEXT_ID.with(|n| {
+        let mut n = n.borrow_mut();
+        *n += 1;
+        *n
+    });
+    SCOPES.with(|s| s.borrow_mut().push((id, description)));
+    ScopeGuard {id, _not_send: PhantomData}
+}
+
+/// Returns the current thread's active scopes, outermost first.
+pub fn current_scopes() -> Vec<String> {
+    SCOPES.try_with(|s| s.borrow().iter().map(|(_, d)| d.clone()).collect())
+        .unwrap_or_default()
+}
+
+///the active scopes joined into a "while doing X > while doing Y" trail
+pub(crate) fn scope_trail() -> Option<String> {
+    let scopes = current_scopes();
+    if scopes.is_empty() {None}
+    else {Some(scopes.join(" > "))}
+}
+
+/// Pops its scope off the stack when dropped.
+#[derive(Debug)]
+pub struct ScopeGuard {
+    id: u64,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        let _ = SCOPES.try_with(|s| s.borrow_mut().retain(|(id, _)| *id != self.id));
+    }
+}
+
+/// Marks what the current thread is doing until the returned guard is dropped.
+/// 
+/// takes the same arguments as `format!`.\
+/// `logged_panic!` and the panic hook log the active scopes as a `scope` field.
+/// 
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// let name = "forest";
+/// let _level = scope!("parsing level {}", name);
+/// for row in 0..2 {
+///     let _row = scope!("reading row {}", row);
+///     assert_eq!(current_scopes().len(), 2);
+/// }
+/// assert_eq!(current_scopes(), vec!["parsing level forest"]);
+///# }
+/// ```
+#[macro_export]
+macro_rules! scope {
+    ($($arg:tt)*) => {
+        $crate::enter_scope(format!($($arg)*))
+    };
+}
@@ -0,0 +1,212 @@
+//! Keeps track of the log files written during this run so they can be cleaned up together.
+
+use std::collections::BTreeSet;
+use std::fs::{read_dir, remove_file};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+
+///every log file written to during this run
+static WRITTEN: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+///remembers that [log_file] was written to
+pub(crate) fn track(log_file: &str) {
+    let mut written = WRITTEN.lock().unwrap_or_else(|e| e.into_inner());
+    if !written.contains(log_file) {
+        written.insert(log_file.to_string());
+    }
+}
+
+///forgets [log_file] after it was cleaned
+pub(crate) fn untrack(log_file: &str) {
+    WRITTEN.lock().unwrap_or_else(|e| e.into_inner()).remove(log_file);
+}
+
+///forgets every tracked log file that is the file at [path], however its path was written
+fn untrack_path(path: &Path) {
+    let path = resolve(path);
+    WRITTEN.lock().unwrap_or_else(|e| e.into_inner()).retain(|tracked| resolve(Path::new(tracked)) != path);
+}
+
+///[path] made absolute against the working directory, without any `.` in it
+fn resolve(path: &Path) -> PathBuf {
+    let path = match path.is_relative() {
+        true => std::env::current_dir().unwrap_or_default().join(path),
+        false => path.to_path_buf(),
+    };
+    path.components().filter(|c| *c != Component::CurDir).collect()
+}
+
+/// Returns every log file written to during this run that hasnt been cleaned since.
+pub fn session_logs() -> Vec<String> {
+    WRITTEN.lock().unwrap_or_else(|e| e.into_inner()).iter().cloned().collect()
+}
+
+/// Deletes every log file written to during this run.
+///
+/// not meant to be used on its own. use `clean_session!` instead
+pub fn clean_session() {
+    crate::clean_log(session_logs())
+}
+
+/// Deletes every log file written to during this run, the default log and every configured tee.
+///
+/// not meant to be used on its own. use `clean_all!` instead
+pub fn clean_all() {
+    let mut logs = session_logs();
+    logs.push(crate::default_log());
+    logs.extend(crate::config::config().tees.iter().map(|t| t.path.clone()));
+    crate::clean_log(logs)
+}
+
+/// Deletes the files in the log directory whose name matches [pattern] and returns their paths.
+///
+/// `*` matches any run of characters and `?` any single character. only the file name part of the pattern can contain them.\
+/// panics if the pattern is absolute or uses `..`, so it can never reach outside the configured log directory.\
+/// not meant to be used on its own. use `clean_matching!` instead
+pub fn clean_matching(pattern: &str) -> Vec<PathBuf> {
+    let pattern = Path::new(pattern);
+    if pattern.components().any(|c| !matches!(c, Component::Normal(_) | Component::CurDir)) {
+        panic!("Dynerr: refusing to clean {} as it could leave the log directory", pattern.display())
+    }
+    let name = pattern.file_name().and_then(|n| n.to_str())
+        .unwrap_or_else(|| panic!("Dynerr: invalid clean pattern: {}", pattern.display()));
+    let dir = crate::config::config().log_dir.join(pattern.parent().unwrap_or_else(|| Path::new("")));
+    let entries = match read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Vec::new(),
+        Err(e) => panic!("Dynerr: Error cleaning file: {}", e),
+    };
+    let mut removed = Vec::new();
+    for entry in entries.flatten() {
+        let is_file = entry.file_type().map(|t| t.is_file()).unwrap_or(false);
+        if !is_file || !entry.file_name().to_str().is_some_and(|n| matches_glob(name, n)) {continue}
+        let path = entry.path();
+        remove_file(&path).unwrap_or_else(|e| panic!("Dynerr: Error cleaning file: {}", e));
+        untrack_path(&path);
+        removed.push(path);
+    }
+    removed
+}
+
+///whether [name] matches the glob [pattern]
+fn matches_glob(pattern: &str, name: &str) -> bool {
+    let (pattern, name): (Vec<char>, Vec<char>) = (pattern.chars().collect(), name.chars().collect());
+    let (mut p, mut n) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((sp, sn)) => {
+                    p = sp + 1;
+                    n = sn + 1;
+                    star = Some((sp, sn + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Deletes every log file written to during this run.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// log!("scratch", "session_a.log");
+/// log!("scratch", "session_b.log");
+/// clean_session!();
+/// assert!(!std::path::Path::new("session_a.log").exists());
+///# }
+/// ```
+#[macro_export]
+macro_rules! clean_session {
+    () => {
+        $crate::clean_session()
+    };
+}
+
+/// Deletes every log file written to during this run, along with the default log and every configured tee.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// log!("scratch", "all_a.log");
+/// clean_all!();
+/// assert!(!std::path::Path::new("all_a.log").exists());
+///# }
+/// ```
+#[macro_export]
+macro_rules! clean_all {
+    () => {
+        $crate::clean_all()
+    };
+}
+
+/// Deletes the files in the log directory matching a glob and returns their paths.
+///
+/// the log directory is set with `LoggerBuilder::log_dir` and defaults to the working directory.\
+/// `*` and `?` are only allowed in the file name. panics on patterns that could leave the log directory.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// std::fs::create_dir_all("glob_logs").unwrap();
+/// log!("first", "glob_logs/run-1.log");
+/// log!("second", "glob_logs/run-2.log");
+/// log!("kept", "glob_logs/other.txt");
+/// assert_eq!(clean_matching!("glob_logs/run-?.log").len(), 2);
+/// assert!(std::path::Path::new("glob_logs/other.txt").exists());
+/// assert!(!session_logs().contains(&"glob_logs/run-1.log".to_string()));
+///# clean!("glob_logs/other.txt");
+///# std::fs::remove_dir("glob_logs").unwrap();
+///# }
+/// ```
+///
+/// ```rust,should_panic
+///# use dynerr::*;
+///# fn main() {
+/// clean_matching!("../*.log");
+///# }
+/// ```
+#[macro_export]
+macro_rules! clean_matching {
+    ($pattern:expr) => {
+        $crate::clean_matching($pattern)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob() {
+        assert!(matches_glob("*.log", "event.log"));
+        assert!(matches_glob("run-?.log", "run-1.log"));
+        assert!(matches_glob("*-*.log", "a-b-c.log"));
+        assert!(!matches_glob("*.log", "event.log.1"));
+        assert!(!matches_glob("run-?.log", "run-10.log"));
+    }
+
+    #[test]
+    fn untracks_however_written() {
+        std::fs::write("untracks_however_written.log", "").unwrap();
+        track("./untracks_however_written.log");
+        track("untracks_however_written.log");
+        let removed = clean_matching("untracks_however_written.log");
+        assert_eq!(removed.len(), 1);
+        assert!(!session_logs().iter().any(|l| l.ends_with("untracks_however_written.log")));
+    }
+}
@@ -0,0 +1,66 @@
+//! One call setups with sensible settings for common kinds of programs.
+
+use crate::{Level, Logger, TextFormat};
+
+///the file `setup_service_logging` sends Error records to
+pub const SERVICE_CRASH_LOG: &str = "crash.log";
+///the file `setup_service_logging` marks crashes in, for `previous_crash`
+pub const SERVICE_CRASH_MARKER: &str = "crash.marker";
+
+/// Sets up logging for a command line tool and installs the panic hook.
+///
+/// every record goes to the log file as usual, and warnings and errors are also printed to stderr
+/// so the user sees them without opening the log.\
+/// to change a setting, start from `Logger::builder().stderr_level(Level::Warn)` and call `install_panic_hook` yourself.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// setup_basic_cli_logging();
+/// log_warn!("no config file found, using the defaults", "test.log"); //also printed to stderr
+///# init();
+///# }
+/// ```
+pub fn setup_basic_cli_logging() {
+    Logger::builder()
+        .stderr_level(Level::Warn)
+        .init();
+    crate::install_panic_hook();
+}
+
+/// Sets up logging for a long running service and installs the panic hook.
+///
+/// the settings are:
+/// - records below `Level::Info` arent written, and errors are also printed to stderr
+/// - Error records and logged panics go to `SERVICE_CRASH_LOG` instead, so the fatal records are in one small file
+/// - email addresses, bearer tokens and AWS keys are redacted
+/// - every record is numbered, so a panic message names the records logged before it
+/// - crashes leave a marker at `SERVICE_CRASH_MARKER` for `previous_crash` to find on the next start
+///
+/// to change a setting, make the same `Logger::builder()` calls with your change and call `install_panic_hook` yourself.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// setup_service_logging();
+/// if let Some(crash) = previous_crash() {
+///     log_warn!(format!("restarted after a crash: {}", crash.message), "test.log");
+/// }
+/// log_info!("listening on port 8080", "test.log");
+///# clean!([SERVICE_CRASH_LOG, SERVICE_CRASH_MARKER]);
+///# init();
+///# }
+/// ```
+pub fn setup_service_logging() {
+    Logger::builder()
+        .file_level(Level::Info)
+        .stderr_level(Level::Error)
+        .crash_log(SERVICE_CRASH_LOG)
+        .redact_secrets()
+        .text_format(TextFormat::new().record_ids(true))
+        .crash_marker(SERVICE_CRASH_MARKER)
+        .init();
+    crate::install_panic_hook();
+}
@@ -0,0 +1,121 @@
+//! Severity classification for error types.
+//! 
+//! implement `ErrorSeverity` on your error then `register_severity::<YourError>()` once at startup.\
+//! unregistered types are treated as `Severity::Recoverable`.
+
+use std::error::Error;
+
+use crate::registry::Registry;
+
+/// How bad an error is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Severity {
+    ///the program cant continue
+    Fatal,
+    ///the operation failed but the program can carry on
+    #[default]
+    Recoverable,
+    ///not worth mentioning
+    Ignorable,
+}
+
+/// Implemented by error types that know their own severity.
+/// 
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# use std::{fmt, error};
+/// #[derive(Debug)]
+/// enum SaveError {
+///     Corrupt,
+///     Busy,
+/// }
+///# impl fmt::Display for SaveError {
+///#     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///#         write!(f, "{:?}", self)
+///#     }
+///# }
+///# impl error::Error for SaveError {}
+/// impl ErrorSeverity for SaveError {
+///     fn severity(&self) -> Severity {
+///         match self {
+///             SaveError::Corrupt  => Severity::Fatal,
+///             SaveError::Busy     => Severity::Recoverable,
+///         }
+///     }
+/// }
+///# fn main() {
+/// register_severity::<SaveError>();
+/// let e: DynError = Box::new(SaveError::Corrupt);
+/// assert_eq!(severity(&*e), Severity::Fatal);
+///# }
+/// ```
+pub trait ErrorSeverity {
+    ///the severity of this error. defaults to `Severity::Recoverable`
+    fn severity(&self) -> Severity {
+        Severity::Recoverable
+    }
+}
+
+///a downcasting lookup for one registered type
+type Classifier = fn(&(dyn Error + 'static)) -> Option<Severity>;
+
+///every registered classifier
+static REGISTRY: Registry<Classifier> = Registry::new();
+
+///downcasts to [T] and asks it for its severity
+fn classify<T: Error + ErrorSeverity + 'static>(e: &(dyn Error + 'static)) -> Option<Severity> {
+    use crate::__DowncastThrough;
+    e.__downcast_through::<T>().map(ErrorSeverity::severity)
+}
+
+/// Registers [T] so `severity()` can see its `ErrorSeverity` impl through a `DynError`.
+/// 
+/// registering the same type twice has no effect.
+pub fn register_severity<T: Error + ErrorSeverity + 'static>() {
+    REGISTRY.register::<T>(classify::<T>);
+}
+
+/// Returns the severity of any error.
+/// 
+/// uses the configured `KindPolicy` of the errors kind, then the `ErrorSeverity` impl of registered types
+/// (including the types in `errors`) and `Severity::Recoverable` for everything else.\
+/// useful in `dynmatch!` guards.
+/// 
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// let e: DynError = Box::new(std::io::Error::from(std::io::ErrorKind::NotFound));
+/// let retry = dynmatch!(e,
+///     type std::io::Error {
+///         arm _ if severity(&*e) == Severity::Fatal => false,
+///         _ => true
+///     },
+///     _ => false
+/// );
+/// assert!(retry);
+///# }
+/// ```
+pub fn severity(e: &(dyn Error + 'static)) -> Severity {
+    if let Some(policy) = crate::kind_policy(e) {return policy.into()}
+    REGISTRY.find_map(|classify| classify(e))
+        .or_else(|| crate::errors::severity(e))
+        .unwrap_or_default()
+}
+
+/// Handles a failed `check!(x, or: default)`.
+/// 
+/// fails like a plain `check!` on `Severity::Fatal`, logs a warning on `Severity::Recoverable` and stays silent on `Severity::Ignorable`.\
+/// not meant to be used on its own. use `check!` instead
+#[track_caller]
+pub fn check_recover<L: crate::LogFiles>(e: crate::DynError, log_file: L) {
+    match severity(&*e) {
+        Severity::Fatal         => crate::check_failed_hint(e, None, log_file),
+        Severity::Recoverable   => {
+            crate::hook::report(&*e);
+            crate::dedup::log_at_call_site(&*e, crate::Level::Warn, log_file);
+        }
+        Severity::Ignorable     => crate::hook::report(&*e),
+    }
+}
@@ -0,0 +1,115 @@
+//! Changing the file log level at runtime with signals.
+//!
+//! after `install()`, SIGUSR1 raises the level records are written to log files at to at least `Level::Debug`
+//! and SIGUSR2 restores the level it had before. each change is logged to `default_log()` at `Level::Warn`.
+//!
+//! only available on unix with the `signals` feature, which fails to compile on unix targets whose signal numbers it doesnt know.
+//!
+//!# Example
+//! ```rust
+//!# use dynerr::*;
+//!# use std::os::raw::c_int;
+//!# extern "C" {fn raise(signum: c_int) -> c_int;}
+//!# fn main() {
+//! Logger::builder().file_level(Level::Warn).init();
+//! signals::install();
+//! //kill -USR1 <pid> from a shell
+//!# clean!("signals.log");
+//!# unsafe {raise(signals::SIGUSR1)};
+//!# std::thread::sleep(std::time::Duration::from_millis(500));
+//!# log_debug!("now written", "signals.log");
+//!# let text = std::fs::read_to_string("signals.log").unwrap();
+//!# assert!(text.contains("now written"));
+//!# clean!("signals.log");
+//!# }
+//! ```
+
+use std::os::raw::c_int;
+use std::sync::Once;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use crate::Level;
+
+//the signal numbers differ between platforms and, on linux, between architectures.
+//only the targets checked against their C headers are supported
+#[cfg(all(any(target_os = "linux", target_os = "android"), not(any(target_arch = "mips", target_arch = "mips64", target_arch = "mips32r6", target_arch = "mips64r6", target_arch = "sparc", target_arch = "sparc64"))))]
+mod numbers {
+    pub const SIGUSR1: super::c_int = 10;
+    pub const SIGUSR2: super::c_int = 12;
+}
+#[cfg(any(all(any(target_os = "linux", target_os = "android"), any(target_arch = "mips", target_arch = "mips64", target_arch = "mips32r6", target_arch = "mips64r6")), target_os = "solaris", target_os = "illumos"))]
+mod numbers {
+    pub const SIGUSR1: super::c_int = 16;
+    pub const SIGUSR2: super::c_int = 17;
+}
+#[cfg(any(
+    all(any(target_os = "linux", target_os = "android"), any(target_arch = "sparc", target_arch = "sparc64")),
+    target_os = "macos", target_os = "ios", target_os = "freebsd", target_os = "netbsd", target_os = "openbsd", target_os = "dragonfly",
+))]
+mod numbers {
+    pub const SIGUSR1: super::c_int = 30;
+    pub const SIGUSR2: super::c_int = 31;
+}
+#[cfg(not(any(
+    target_os = "linux", target_os = "android", target_os = "solaris", target_os = "illumos",
+    target_os = "macos", target_os = "ios", target_os = "freebsd", target_os = "netbsd", target_os = "openbsd", target_os = "dragonfly",
+)))]
+compile_error!("Dynerr: the `signals` feature doesnt know the SIGUSR1 and SIGUSR2 numbers of this target");
+
+///the number of SIGUSR1 on this target
+pub const SIGUSR1: c_int = numbers::SIGUSR1;
+///the number of SIGUSR2 on this target
+pub const SIGUSR2: c_int = numbers::SIGUSR2;
+
+extern "C" {
+    fn signal(signum: c_int, handler: extern "C" fn(c_int)) -> usize;
+}
+
+///the last signal received and not handled yet, or 0
+static PENDING: AtomicI32 = AtomicI32::new(0);
+
+///only stores the signal, as nothing else is safe inside a handler
+extern "C" fn on_signal(signum: c_int) {
+    PENDING.store(signum, Ordering::SeqCst);
+}
+
+/// Installs the SIGUSR1 and SIGUSR2 handlers and the thread that applies them.
+///
+/// signals are picked up within a tenth of a second. installing more than once has no effect.
+pub fn install() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        unsafe {
+            signal(SIGUSR1, on_signal);
+            signal(SIGUSR2, on_signal);
+        }
+        thread::Builder::new()
+            .name("dynerr-signals".to_string())
+            .spawn(watch)
+            .expect("Dynerr: failed to spawn signal thread");
+    });
+}
+
+///applies received signals until the process exits
+fn watch() {
+    let mut restore: Option<Level> = None;
+    loop {
+        thread::sleep(Duration::from_millis(100));
+        let (from, to) = match PENDING.swap(0, Ordering::SeqCst) {
+            SIGUSR1 => {
+                let current = crate::config::config().file_level;
+                restore.get_or_insert(current);
+                (current, current.max(Level::Debug))
+            }
+            SIGUSR2 => match restore.take() {
+                Some(level) => (crate::config::config().file_level, level),
+                None => continue,
+            },
+            _ => continue,
+        };
+        crate::config::update(|c| c.file_level = to);
+        crate::log_level(format!("log level changed from {} to {} by signal", from, to), Level::Warn, crate::default_log());
+    }
+}
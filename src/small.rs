@@ -0,0 +1,201 @@
+//! An error type that stores small errors without allocating, for paths that reject a lot of input.
+
+use std::error::Error;
+use std::fmt;
+use std::mem::{self, ManuallyDrop, MaybeUninit};
+use std::ops::Deref;
+
+use crate::DynError;
+
+///the storage small errors are kept in, three words like a `String`
+type Inline = MaybeUninit<[usize; 3]>;
+
+///the functions that work with the error stored inline, made for each error type
+struct Vtable {
+    as_dyn: unsafe fn(*const ()) -> *const (dyn Error + 'static),
+    into_box: unsafe fn(*const ()) -> DynError,
+    drop: unsafe fn(*mut ()),
+}
+
+///provides the vtable of [Self] as a constant so references to it are `'static`
+trait HasVtable: Error + Sized + 'static {
+    const VTABLE: Vtable = Vtable {
+        as_dyn: |data| data as *const Self,
+        into_box: |data| Box::new(unsafe {(data as *const Self).read()}),
+        drop: |data| unsafe {(data as *mut Self).drop_in_place()},
+    };
+}
+
+impl<E: Error + 'static> HasVtable for E {}
+
+///where the error is kept
+enum Repr {
+    Inline(Inline, &'static Vtable),
+    Boxed(DynError),
+}
+
+/// A `DynError` that keeps errors of up to three words inline instead of allocating a box for them.
+///
+/// most parse and validation errors, like a `ParseIntError`, an `io::Error` or an enum of positions, fit.
+/// larger errors are boxed as usual.\
+/// it derefs to `dyn Error`, so `dynmatch!` and `downcast_ref` work on it, `?` converts any error into it,
+/// and `?` or `into_dyn_error` turns it into a `DynError` when it leaves the hot path.
+/// `dynerr!` raises errors straight into it, without running the `register_middleware` functions.\
+/// run `cargo bench --bench small_error` to compare it with `DynError`.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+/// fn parse_digit(c: char) -> SmallResult<u32> {
+///     match c.to_digit(10) {
+///         Some(d) => Ok(d),
+///         None => dynerr!(errors::InvalidInput::new("digit", "not a digit")), //boxed, its too big to fit
+///     }
+/// }
+///
+/// fn parse_number(text: &str) -> SmallResult<u32> {
+///     Ok(text.parse()?) //a ParseIntError, kept inline
+/// }
+///# fn main() {
+/// let e = parse_number("x").unwrap_err();
+/// assert!(e.is_inline());
+/// assert!(e.downcast_ref::<std::num::ParseIntError>().is_some());
+/// assert!(!parse_digit('x').unwrap_err().is_inline());
+/// let e: DynError = e.into();
+/// assert!(e.downcast_ref::<std::num::ParseIntError>().is_some());
+///# }
+/// ```
+pub struct SmallDynError(Repr);
+
+/// A result that keeps small errors inline. see `SmallDynError`
+pub type SmallResult<T> = Result<T, SmallDynError>;
+
+impl SmallDynError {
+    ///stores [e] inline if it fits, and in a box otherwise
+    pub fn new<E: Error + 'static>(e: E) -> Self {
+        if mem::size_of::<E>() > mem::size_of::<Inline>() || mem::align_of::<E>() > mem::align_of::<Inline>() {
+            return Self(Repr::Boxed(Box::new(e)))
+        }
+        let mut data = Inline::uninit();
+        unsafe {(data.as_mut_ptr() as *mut E).write(e)};
+        Self(Repr::Inline(data, &<E as HasVtable>::VTABLE))
+    }
+
+    ///whether the error is stored inline rather than boxed
+    pub fn is_inline(&self) -> bool {
+        matches!(self.0, Repr::Inline(..))
+    }
+
+    ///moves the error into a `DynError`, boxing it if it was inline
+    pub fn into_dyn_error(self) -> DynError {
+        let this = ManuallyDrop::new(self);
+        match &this.0 {
+            //the error is read out exactly once and [this] is never dropped, so it isnt dropped twice
+            Repr::Inline(data, vtable) => unsafe {(vtable.into_box)(data.as_ptr() as *const ())},
+            Repr::Boxed(e) => unsafe {(e as *const DynError).read()},
+        }
+    }
+}
+
+impl Deref for SmallDynError {
+    type Target = dyn Error + 'static;
+
+    fn deref(&self) -> &Self::Target {
+        match &self.0 {
+            Repr::Inline(data, vtable) => unsafe {&*(vtable.as_dyn)(data.as_ptr() as *const ())},
+            Repr::Boxed(e) => &**e,
+        }
+    }
+}
+
+impl Drop for SmallDynError {
+    fn drop(&mut self) {
+        if let Repr::Inline(data, vtable) = &mut self.0 {
+            unsafe {(vtable.drop)(data.as_mut_ptr() as *mut ())}
+        }
+    }
+}
+
+impl fmt::Debug for SmallDynError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl fmt::Display for SmallDynError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+impl<E: Error + 'static> From<E> for SmallDynError {
+    fn from(e: E) -> Self {
+        Self::new(e)
+    }
+}
+
+impl From<SmallDynError> for DynError {
+    fn from(e: SmallDynError) -> Self {
+        e.into_dyn_error()
+    }
+}
+
+impl<E: Error + 'static> crate::__Raise<E> for SmallDynError {
+    fn __raise(e: E) -> Self {
+        Self::new(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[derive(Debug)]
+    struct Counted(#[allow(dead_code)] Rc<()>);
+
+    impl fmt::Display for Counted {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "counted")
+        }
+    }
+
+    impl Error for Counted {}
+
+    #[test]
+    fn drops_inline_errors_once() {
+        let count = Rc::new(());
+        let e = SmallDynError::new(Counted(count.clone()));
+        assert!(e.is_inline() && Rc::strong_count(&count) == 2);
+        drop(e);
+        assert_eq!(Rc::strong_count(&count), 1);
+        let e = SmallDynError::new(Counted(count.clone())).into_dyn_error();
+        assert_eq!(Rc::strong_count(&count), 2);
+        assert_eq!(e.to_string(), "counted");
+        drop(e);
+        assert_eq!(Rc::strong_count(&count), 1);
+    }
+
+    #[derive(Debug)]
+    #[repr(align(64))]
+    struct Aligned(u8);
+
+    impl fmt::Display for Aligned {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "aligned {}", self.0)
+        }
+    }
+
+    impl Error for Aligned {}
+
+    #[test]
+    fn boxes_what_doesnt_fit() {
+        let aligned = SmallDynError::new(Aligned(3));
+        assert!(!aligned.is_inline());
+        assert_eq!(aligned.downcast_ref::<Aligned>().map(|a| a.0), Some(3));
+        let large = SmallDynError::new(crate::errors::InvalidInput::new("digit", "not a digit"));
+        assert!(!large.is_inline() && large.downcast_ref::<crate::errors::InvalidInput>().is_some());
+        let unit = SmallDynError::new(fmt::Error);
+        assert!(unit.is_inline() && unit.into_dyn_error().is::<fmt::Error>());
+    }
+}
@@ -0,0 +1,55 @@
+//! A startup banner describing the running program.
+
+use crate::{Level, LogFiles, LogRecord};
+
+/// Logs a record describing the program and its environment.
+///
+/// not meant to be used on its own. use `log_startup_info!` instead
+pub fn log_startup_info<L: LogFiles>(app: &str, version: &str, env_vars: &[&str], log_file: L) {
+    let cwd = std::env::current_dir().map(|d| d.display().to_string()).unwrap_or_else(|e| format!("unknown ({})", e));
+    let mut record = LogRecord::new(Level::Info, format_args!("started {} {}", app, version))
+        .with_field("app", app)
+        .with_field("version", version)
+        .with_field("dynerr", env!("CARGO_PKG_VERSION"))
+        .with_field("os", std::env::consts::OS)
+        .with_field("arch", std::env::consts::ARCH)
+        .with_field("args", format_args!("{:?}", std::env::args().collect::<Vec<_>>()))
+        .with_field("cwd", cwd);
+    for var in env_vars {
+        if let Ok(value) = std::env::var(var) {
+            record = record.with_field(format_args!("env.{}", var), value);
+        }
+    }
+    crate::write_record(&record, log_file);
+}
+
+/// Logs a startup banner with the app version, OS, architecture, arguments and working directory.
+///
+/// the app name and version come from the calling crate's Cargo.toml.\
+/// `env: [..]` adds the listed environment variables as `env.NAME` fields, leaving out unset ones.\
+/// If no file supplied then defaults to `default_log()` (normally "event.log").
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// log_startup_info!();
+/// log_startup_info!("test.log");
+/// log_startup_info!("test.log", env: ["RUST_LOG", "HOME"]);
+///# }
+/// ```
+#[macro_export]
+macro_rules! log_startup_info {
+    () => {
+        $crate::log_startup_info!(&$crate::default_log(), env: [])
+    };
+    (env: [$($var:expr),* $(,)?]) => {
+        $crate::log_startup_info!(&$crate::default_log(), env: [$($var),*])
+    };
+    ($log:expr) => {
+        $crate::log_startup_info!($log, env: [])
+    };
+    ($log:expr, env: [$($var:expr),* $(,)?]) => {
+        $crate::log_startup_info(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"), &[$($var),*], $log)
+    };
+}
@@ -0,0 +1,69 @@
+//! Logging how long a scope took.
+
+use std::time::Instant;
+
+use crate::{Elapsed, Level, LogRecord};
+
+/// Logs how long it lived when dropped. created by `time_scope!`
+#[derive(Debug)]
+#[must_use = "the scope is timed until the guard is dropped"]
+pub struct TimeScope {
+    name: String,
+    log_file: String,
+    start: Instant,
+}
+
+impl TimeScope {
+    /// Starts timing [name], to be logged to [log_file].
+    ///
+    /// not meant to be used on its own. use `time_scope!` instead
+    pub fn start<N: Into<String>>(name: N, log_file: &str) -> Self {
+        Self {name: name.into(), log_file: log_file.to_string(), start: Instant::now()}
+    }
+
+    ///how long the scope has been running
+    pub fn elapsed(&self) -> std::time::Duration {
+        self.start.elapsed()
+    }
+}
+
+impl Drop for TimeScope {
+    fn drop(&mut self) {
+        let elapsed = self.start.elapsed();
+        crate::summary::time(&self.name, elapsed);
+        let record = match std::thread::panicking() {
+            true => LogRecord::new(Level::Warn, format_args!("{} panicked", self.name)),
+            false => LogRecord::new(Level::Debug, format_args!("{} finished", self.name)),
+        };
+        crate::write_record(&record.with_quantity("took", Elapsed(elapsed)), self.log_file.as_str());
+    }
+}
+
+/// Logs how long the rest of the scope takes, when the returned guard is dropped.
+///
+/// the time is logged at `Level::Debug` in a `took` field, however the scope ends, by early return or `?`.
+/// if it ends by panicking the record is at `Level::Warn` instead. the time is also added to the `summary!` report.\
+/// If no file supplied then defaults to `default_log()` (normally "event.log").
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+/// fn load_world(path: &str) -> DynResult<Vec<u8>> {
+///     let _t = time_scope!("load_world", "test.log");
+///     let bytes = std::fs::read(path)?; //logs "load_world finished" even when this returns early
+///     Ok(bytes)
+/// }
+///# fn main() {
+/// assert!(load_world("no_such_world").is_err());
+///# assert!(recent_events().contains(&"load_world finished".to_string()));
+///# }
+/// ```
+#[macro_export]
+macro_rules! time_scope {
+    ($name:expr) => {
+        $crate::TimeScope::start($name, &$crate::default_log())
+    };
+    ($name:expr, $log:expr) => {
+        $crate::TimeScope::start($name, $log)
+    };
+}
@@ -0,0 +1,141 @@
+//! Counters and timings gathered during a run, written out as one end-of-run report by `summary!`.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::{Level, LogFiles, LogRecord};
+
+///errors and warnings logged so far
+static ERRORS: AtomicU64 = AtomicU64::new(0);
+static WARNINGS: AtomicU64 = AtomicU64::new(0);
+///extra attempts made by `check!` retry policies
+static RETRIES: AtomicU64 = AtomicU64::new(0);
+
+///what the report shows besides the built in counters
+struct Entries {
+    counters: BTreeMap<String, u64>,
+    timings: BTreeMap<String, (Duration, u64)>,
+    checkpoints: Vec<(String, Duration)>,
+}
+
+static ENTRIES: Mutex<Entries> = Mutex::new(Entries {counters: BTreeMap::new(), timings: BTreeMap::new(), checkpoints: Vec::new()});
+
+///when the run started, as far as the summary is concerned
+fn start() -> Instant {
+    static START: OnceLock<Instant> = OnceLock::new();
+    *START.get_or_init(Instant::now)
+}
+
+///counts a written record towards the error and warning totals
+pub(crate) fn observe(level: Level) {
+    start();
+    match level {
+        Level::Error => ERRORS.fetch_add(1, Ordering::Relaxed),
+        Level::Warn  => WARNINGS.fetch_add(1, Ordering::Relaxed),
+        _ => return,
+    };
+}
+
+/// Counts a retry made by a `check!` policy.
+///
+/// not meant to be used on its own. used by `check!`
+#[doc(hidden)]
+pub fn __count_retry() {
+    RETRIES.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Adds [by] to the counter [name] shown in the summary.
+pub fn count(name: &str, by: u64) {
+    let mut entries = ENTRIES.lock().unwrap_or_else(|e| e.into_inner());
+    *entries.counters.entry(name.to_string()).or_insert(0) += by;
+}
+
+/// Adds [duration] to the timing [name] shown in the summary, which reports the total and how many were added.
+pub fn time(name: &str, duration: Duration) {
+    let mut entries = ENTRIES.lock().unwrap_or_else(|e| e.into_inner());
+    let (total, times) = entries.timings.entry(name.to_string()).or_insert((Duration::ZERO, 0));
+    *total += duration;
+    *times += 1;
+}
+
+/// Notes that the run reached [name], shown in the summary with the time since the run started.
+pub fn checkpoint(name: &str) {
+    let elapsed = start().elapsed();
+    ENTRIES.lock().unwrap_or_else(|e| e.into_inner()).checkpoints.push((name.to_string(), elapsed));
+}
+
+/// Formats the end-of-run report.
+///
+/// the first line is the verdict: `run succeeded` if no errors were logged, otherwise `run failed`.
+pub fn report() -> String {
+    let errors = ERRORS.load(Ordering::Relaxed);
+    let verdict = if errors == 0 {"succeeded"} else {"failed"};
+    let mut text = format!("run {} after {:.1?}", verdict, start().elapsed());
+    let _ = write!(text, "\n  errors: {}\n  warnings: {}\n  retries: {}",
+        errors, WARNINGS.load(Ordering::Relaxed), RETRIES.load(Ordering::Relaxed));
+    let entries = ENTRIES.lock().unwrap_or_else(|e| e.into_inner());
+    for (name, value) in &entries.counters {
+        let _ = write!(text, "\n  {}: {}", name, value);
+    }
+    for (name, (total, times)) in &entries.timings {
+        let _ = write!(text, "\n  {}: {:.1?} over {}", name, total, times);
+    }
+    for (name, at) in &entries.checkpoints {
+        let _ = write!(text, "\n  reached {} at {:.1?}", name, at);
+    }
+    text
+}
+
+/// Writes the report to [log_file] at `Level::Info`, and to stdout if [print] is set, then returns it.
+///
+/// not meant to be used on its own. use `summary!` instead
+pub fn write_summary<L: LogFiles>(log_file: L, print: bool) -> String {
+    let text = report();
+    crate::write_record(&LogRecord::new(Level::Info, &text), log_file);
+    if print {
+        println!("{}", text);
+    }
+    text
+}
+
+/// Writes an end-of-run report of everything counted during the run.
+///
+/// the report starts with a verdict, then lists the errors and warnings logged, the retries `check!` made,
+/// and everything added with `summary::count`, `summary::time` and `summary::checkpoint`.\
+/// `summary!(file, print)` also prints it to stdout. returns the report.\
+/// If no file supplied then defaults to `default_log()` (normally "event.log").
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# use std::time::Duration;
+///# fn main() {
+/// for row in ["1", "2", "x"] {
+///     match row.parse::<u32>() {
+///         Ok(_) => summary::count("rows imported", 1),
+///         Err(e) => {log_warn!(e, "test.log");}
+///     }
+/// }
+/// summary::time("upload", Duration::from_millis(250));
+/// summary::checkpoint("import done");
+/// let report = summary!("test.log");
+/// assert!(report.starts_with("run succeeded"));
+/// assert!(report.contains("\n  warnings: 1\n"));
+/// assert!(report.contains("\n  rows imported: 2\n"));
+///# }
+/// ```
+#[macro_export]
+macro_rules! summary {
+    () => {
+        $crate::summary!(&$crate::default_log())
+    };
+    ($log:expr, print) => {
+        $crate::summary::write_summary($log, true)
+    };
+    ($log:expr) => {
+        $crate::summary::write_summary($log, false)
+    };
+}
@@ -0,0 +1,290 @@
+//! Giving up on work that takes too long, returning `errors::Timeout` through `DynResult`.
+
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use crate::errors::Timeout;
+use crate::{DynResult, PanicError};
+
+/// Runs [work] on its own thread and returns its value, or `errors::Timeout` if it didnt finish within [limit].
+///
+/// a panic in [work] is returned as a `PanicError`.
+/// work that times out is left running on its thread, since threads cant be stopped from outside.\
+/// not meant to be used on its own. use `with_timeout!` instead
+pub fn __with_timeout<T, F>(work: F, limit: Duration, operation: &str) -> DynResult<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let started = Instant::now();
+    let (sender, receiver) = mpsc::sync_channel(1);
+    let handle = std::thread::Builder::new()
+        .name(operation.to_string())
+        .spawn(move || {let _ = sender.send(work());})?;
+    match receiver.recv_timeout(limit) {
+        Ok(value) => Ok(value),
+        Err(mpsc::RecvTimeoutError::Timeout) => Err(Box::new(Timeout::new(operation, started.elapsed()))),
+        Err(mpsc::RecvTimeoutError::Disconnected) => match handle.join() {
+            Err(payload) => Err(Box::new(PanicError::new(payload, Some(operation)))),
+            Ok(()) => Err(Box::new(Timeout::new(operation, started.elapsed()))),
+        },
+    }
+}
+
+#[cfg(feature = "async")]
+pub use self::future::TimeoutFuture;
+
+#[cfg(feature = "async")]
+mod future {
+    use std::cmp::Ordering;
+    use std::collections::BinaryHeap;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::{Arc, Condvar, Mutex, Once};
+    use std::task::{Context, Poll, Waker};
+    use std::time::{Duration, Instant};
+
+    use crate::errors::Timeout;
+    use crate::DynResult;
+
+    ///the waker of a pending `TimeoutFuture`, emptied once the future no longer needs waking
+    type Slot = Arc<Mutex<Option<Waker>>>;
+
+    ///a slot to wake at a time
+    struct Deadline {
+        at: Instant,
+        slot: Slot,
+    }
+
+    //ordered so the earliest deadline is at the top of the heap
+    impl Ord for Deadline {
+        fn cmp(&self, other: &Self) -> Ordering {
+            other.at.cmp(&self.at)
+        }
+    }
+
+    impl PartialOrd for Deadline {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl PartialEq for Deadline {
+        fn eq(&self, other: &Self) -> bool {
+            self.at == other.at
+        }
+    }
+
+    impl Eq for Deadline {}
+
+    ///the deadlines of every pending `TimeoutFuture`, waited on by the one timer thread
+    static DEADLINES: Mutex<BinaryHeap<Deadline>> = Mutex::new(BinaryHeap::new());
+    ///notified when a deadline is added, in case it is earlier than the one being waited for
+    static ADDED: Condvar = Condvar::new();
+    ///starts the timer thread
+    static TIMER: Once = Once::new();
+
+    ///wakes the waker in [slot] at [at]
+    fn schedule(at: Instant, slot: Slot) {
+        TIMER.call_once(|| {
+            let _ = std::thread::Builder::new().name("dynerr timeout timer".to_string()).spawn(run_timer);
+        });
+        DEADLINES.lock().unwrap_or_else(|e| e.into_inner()).push(Deadline {at, slot});
+        ADDED.notify_one();
+    }
+
+    ///wakes each slot once its deadline passes, sleeping until the earliest one
+    fn run_timer() {
+        let mut deadlines = DEADLINES.lock().unwrap_or_else(|e| e.into_inner());
+        loop {
+            let now = Instant::now();
+            deadlines = match deadlines.peek().map(|d| d.at) {
+                None => ADDED.wait(deadlines).unwrap_or_else(|e| e.into_inner()),
+                Some(at) if at > now => ADDED.wait_timeout(deadlines, at - now).unwrap_or_else(|e| e.into_inner()).0,
+                Some(_) => {
+                    let due = deadlines.pop().map(|d| d.slot);
+                    drop(deadlines);
+                    let waker = due.and_then(|slot| slot.lock().unwrap_or_else(|e| e.into_inner()).take());
+                    if let Some(waker) = waker {waker.wake()}
+                    DEADLINES.lock().unwrap_or_else(|e| e.into_inner())
+                }
+            };
+        }
+    }
+
+    /// A future that resolves to the value of another, or to `errors::Timeout` if it wasnt ready within a limit. made by `with_timeout!(future: ..)`
+    ///
+    /// the limit counts from when it was made. one timer thread shared by every `TimeoutFuture` wakes the task at the limit,
+    /// so it works on any executor without a timer of its own.
+    ///
+    ///# Example
+    /// ```rust
+    ///# use dynerr::*;
+    ///# use std::future::Future;
+    ///# use std::sync::Arc;
+    ///# use std::task::{Context, Poll, Wake};
+    ///# struct Unpark(std::thread::Thread);
+    ///# impl Wake for Unpark {fn wake(self: Arc<Self>) {self.0.unpark()}}
+    ///# fn block_on<F: Future>(future: F) -> F::Output {
+    ///#     let mut future = Box::pin(future);
+    ///#     let waker = Arc::new(Unpark(std::thread::current())).into();
+    ///#     loop {
+    ///#         if let Poll::Ready(v) = future.as_mut().poll(&mut Context::from_waker(&waker)) {return v}
+    ///#         std::thread::park();
+    ///#     }
+    ///# }
+    /// async fn fetch() -> DynResult<u32> {
+    ///     let never = std::future::pending::<u32>();
+    ///     let value = with_timeout!(future: never, 20ms, "fetch").await?;
+    ///     Ok(value)
+    /// }
+    ///# fn main() {
+    /// let e = block_on(fetch()).unwrap_err();
+    /// assert!(e.to_string().starts_with("fetch timed out after"));
+    /// assert_eq!(block_on(with_timeout!(future: async {4}, 1s)).unwrap(), 4);
+    ///# }
+    /// ```
+    pub struct TimeoutFuture<F> {
+        future: Pin<Box<F>>,
+        operation: String,
+        started: Instant,
+        limit: Duration,
+        slot: Option<Slot>,
+    }
+
+    impl<F: Future> TimeoutFuture<F> {
+        ///[future] named [operation], given up on after [limit]
+        pub fn new<S: Into<String>>(future: F, limit: Duration, operation: S) -> Self {
+            Self {future: Box::pin(future), operation: operation.into(), started: Instant::now(), limit, slot: None}
+        }
+    }
+
+    impl<F> TimeoutFuture<F> {
+        ///empties the slot so the timer drops the waker instead of waking a finished task
+        fn finish(&mut self) {
+            if let Some(slot) = self.slot.take() {
+                slot.lock().unwrap_or_else(|e| e.into_inner()).take();
+            }
+        }
+    }
+
+    impl<F: Future> Future for TimeoutFuture<F> {
+        type Output = DynResult<F::Output>;
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+            if let Poll::Ready(value) = self.future.as_mut().poll(cx) {
+                self.finish();
+                return Poll::Ready(Ok(value))
+            }
+            let elapsed = self.started.elapsed();
+            if elapsed >= self.limit {
+                self.finish();
+                return Poll::Ready(Err(Box::new(Timeout::new(self.operation.as_str(), elapsed))))
+            }
+            match &self.slot {
+                Some(slot) => *slot.lock().unwrap_or_else(|e| e.into_inner()) = Some(cx.waker().clone()),
+                None => {
+                    let slot = Arc::new(Mutex::new(Some(cx.waker().clone())));
+                    schedule(self.started + self.limit, Arc::clone(&slot));
+                    self.slot = Some(slot);
+                }
+            }
+            Poll::Pending
+        }
+    }
+
+    impl<F> Drop for TimeoutFuture<F> {
+        fn drop(&mut self) {
+            self.finish();
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::task::Wake;
+
+        ///counts how often it was woken
+        struct Count(AtomicUsize);
+
+        impl Wake for Count {
+            fn wake(self: Arc<Self>) {
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        #[test]
+        fn wakes_once_and_drops_wakers() {
+            let (woken, dropped) = (Arc::new(Count(AtomicUsize::new(0))), Arc::new(Count(AtomicUsize::new(0))));
+            let (woken_waker, dropped_waker) = (Waker::from(Arc::clone(&woken)), Waker::from(Arc::clone(&dropped)));
+            let mut pending = Box::pin(TimeoutFuture::new(std::future::pending::<()>(), Duration::from_millis(20), "pending"));
+            let mut abandoned = Box::pin(TimeoutFuture::new(std::future::pending::<()>(), Duration::from_millis(20), "abandoned"));
+            assert!(pending.as_mut().poll(&mut Context::from_waker(&woken_waker)).is_pending());
+            assert!(abandoned.as_mut().poll(&mut Context::from_waker(&dropped_waker)).is_pending());
+            drop((abandoned, dropped_waker));
+            std::thread::sleep(Duration::from_millis(100));
+            assert_eq!((woken.0.load(Ordering::Relaxed), dropped.0.load(Ordering::Relaxed)), (1, 0));
+            assert_eq!(Arc::strong_count(&dropped), 1);
+            assert!(pending.as_mut().poll(&mut Context::from_waker(&woken_waker)).is_ready());
+            assert_eq!(Arc::strong_count(&woken), 2);
+        }
+    }
+}
+
+/// Returns the value of a closure, or `errors::Timeout` if it didnt finish within a limit, as a `DynResult`.
+///
+/// the closure runs on its own thread and is left running if it times out. a panic in it is returned as a `PanicError`.\
+/// with the `async` feature, `with_timeout!(future: f, limit)` does the same for a future, returning a `TimeoutFuture` to await.\
+/// the limit is either a literal with a unit (`ns`, `us`, `ms`, `s` or `m`), checked at compile time, or a `Duration`.
+/// the operation named in the error defaults to "operation".
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# use std::time::Duration;
+/// fn fetch(delay: u64) -> DynResult<u32> {
+///     let value = with_timeout!(move || {std::thread::sleep(Duration::from_millis(delay)); 5}, 50ms, "fetch")?;
+///     Ok(value)
+/// }
+///# fn main() {
+/// assert_eq!(fetch(0).unwrap(), 5);
+/// let e = fetch(500).unwrap_err();
+/// assert!(e.to_string().starts_with("fetch timed out after"));
+/// assert_eq!(error_kind(&*e), Some("timeout"));
+/// let waited = dynmatch!(e,
+///     type errors::Timeout {
+///         arm t => t.elapsed,
+///         _ => Duration::default()
+///     },
+///     _ => Duration::default()
+/// );
+/// assert!(waited >= Duration::from_millis(50));
+/// assert!(with_timeout!(|| 1, Duration::from_secs(1)).is_ok());
+///# }
+/// ```
+#[macro_export]
+macro_rules! with_timeout {
+    (future: $future:expr, $limit:literal $(, $operation:expr)?) => {{
+        const LIMIT: ::std::time::Duration = $crate::__parse_duration(stringify!($limit));
+        $crate::TimeoutFuture::new($future, LIMIT, $crate::__timeout_operation!($($operation)?))
+    }};
+    (future: $future:expr, $limit:expr $(, $operation:expr)?) => {
+        $crate::TimeoutFuture::new($future, $limit, $crate::__timeout_operation!($($operation)?))
+    };
+    ($work:expr, $limit:literal $(, $operation:expr)?) => {{
+        const LIMIT: ::std::time::Duration = $crate::__parse_duration(stringify!($limit));
+        $crate::__with_timeout($work, LIMIT, $crate::__timeout_operation!($($operation)?))
+    }};
+    ($work:expr, $limit:expr $(, $operation:expr)?) => {
+        $crate::__with_timeout($work, $limit, $crate::__timeout_operation!($($operation)?))
+    };
+}
+
+///the operation named by a `with_timeout!` error
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __timeout_operation {
+    () => {"operation"};
+    ($operation:expr) => {$operation};
+}
@@ -0,0 +1,103 @@
+//! A `DynResult` whose error remembers where `?` picked it up.
+
+use std::error::Error;
+use std::fmt;
+use std::panic::Location;
+
+use crate::DynError;
+
+/// An alias for result that uses `TracedError`. a drop in replacement for `DynResult` that records where errors came from.
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+/// fn port(text: &str) -> Traced<u16> {
+///     Ok(text.parse()?) //this line is recorded
+/// }
+///# fn main() {
+/// let e = port("http").unwrap_err();
+/// assert_eq!(e.frames().len(), 1);
+/// assert!(e.to_string().starts_with("invalid digit found in string\n    at "));
+/// log_error!(e, "test.log"); //logs the error followed by the file, line and column of the `?`
+///# }
+/// ```
+pub type Traced<T> = Result<T, TracedError>;
+
+/// An error along with the places it was propagated through with `?`.
+///
+/// every `?` that turns an error into a `TracedError` records its location.
+/// a `DynError` isnt an `Error` itself, so `DynResult`s need `.map_err(TracedError::new)` before the `?`.\
+/// `?` on a `Traced` in a function that also returns `Traced` passes the error on unchanged, as stable rust cant run code there.
+/// use `.map_err(TracedError::trace)` to record those hops too.\
+/// Display prints the error followed by one `at file:line:column` line per frame, oldest first.
+pub struct TracedError {
+    error: DynError,
+    frames: Vec<&'static Location<'static>>,
+}
+
+impl TracedError {
+    ///wraps [error] and records the caller as its first frame
+    #[track_caller]
+    pub fn new<E: Into<DynError>>(error: E) -> Self {
+        Self {error: error.into(), frames: vec![Location::caller()]}
+    }
+
+    ///the places the error passed through, oldest first
+    pub fn frames(&self) -> &[&'static Location<'static>] {
+        &self.frames
+    }
+
+    ///the wrapped error
+    pub fn error(&self) -> &(dyn Error + 'static) {
+        &*self.error
+    }
+
+    ///unwraps the error, dropping its frames
+    pub fn into_inner(self) -> DynError {
+        self.error
+    }
+
+    /// Records the caller as another frame.
+    ///
+    ///# Example
+    /// ```rust
+    ///# use dynerr::*;
+    /// fn parse(text: &str) -> Traced<u16> {
+    ///     Ok(text.parse()?)
+    /// }
+    /// fn load(text: &str) -> Traced<u16> {
+    ///     parse(text).map_err(TracedError::trace)
+    /// }
+    ///# fn main() {
+    /// assert_eq!(load("x").unwrap_err().frames().len(), 2);
+    ///# }
+    /// ```
+    #[track_caller]
+    pub fn trace(mut self) -> Self {
+        self.frames.push(Location::caller());
+        self
+    }
+}
+
+impl<E: Error + 'static> From<E> for TracedError {
+    #[track_caller]
+    fn from(error: E) -> Self {
+        Self::new(error)
+    }
+}
+
+impl fmt::Display for TracedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.error)?;
+        for frame in &self.frames {
+            write!(f, "\n    at {}", frame)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for TracedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TracedError").field("error", &self.error).field("frames", &self.frames).finish()
+    }
+}
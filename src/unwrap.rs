@@ -0,0 +1,95 @@
+//! Logged replacements for `unwrap()` and `expect()`.
+
+use std::fmt;
+
+use crate::LogFiles;
+
+/// Implemented by the types `lunwrap!` and `lexpect!` work on.
+///
+/// not meant to be used on its own. use `lunwrap!` or `lexpect!` instead
+pub trait LoggedUnwrap {
+    type Output;
+
+    ///returns the value or does a logged panic with [message] (or a default one) followed by [location]
+    fn logged_unwrap<L: LogFiles>(self, message: Option<&str>, location: fmt::Arguments, log_file: L) -> Self::Output;
+}
+
+impl<T, E: fmt::Debug> LoggedUnwrap for Result<T, E> {
+    type Output = T;
+
+    #[track_caller]
+    fn logged_unwrap<L: LogFiles>(self, message: Option<&str>, location: fmt::Arguments, log_file: L) -> T {
+        match self {
+            Ok(v) => v,
+            Err(e) => {
+                let message = message.unwrap_or("called `lunwrap!()` on an `Err` value");
+                crate::log_and_panic(format_args!("{}: {:?} at {}", message, e, location), log_file)
+            }
+        }
+    }
+}
+
+impl<T> LoggedUnwrap for Option<T> {
+    type Output = T;
+
+    #[track_caller]
+    fn logged_unwrap<L: LogFiles>(self, message: Option<&str>, location: fmt::Arguments, log_file: L) -> T {
+        match self {
+            Some(v) => v,
+            None => {
+                let message = message.unwrap_or("called `lunwrap!()` on a `None` value");
+                crate::log_and_panic(format_args!("{} at {}", message, location), log_file)
+            }
+        }
+    }
+}
+
+/// Logged `.unwrap()` for any `Result` or `Option`.
+///
+/// logs the same message `unwrap()` would panic with, plus the call site, then panics.\
+/// If no file supplied then defaults to `default_log()` (normally "event.log").
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// let i: u32 = lunwrap!("5".parse::<u32>());
+/// let j = lunwrap!(Some(6), "test.log");
+/// assert_eq!(i + j, 11);
+/// let result = std::panic::catch_unwind(|| lunwrap!("x".parse::<u32>(), "test.log"));
+/// assert!(result.is_err());
+///# }
+/// ```
+#[macro_export]
+macro_rules! lunwrap {
+    ($x:expr) => {
+        $crate::lunwrap!($x, &$crate::default_log())
+    };
+    ($x:expr, $log:expr) => {
+        $crate::LoggedUnwrap::logged_unwrap($x, None, format_args!("{}:{}", file!(), line!()), $log)
+    };
+}
+
+/// Logged `.expect(msg)` for any `Result` or `Option`.
+///
+/// logs [msg] like `expect()` would panic with, plus the call site, then panics.\
+/// If no file supplied then defaults to `default_log()` (normally "event.log").
+///
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// let width: u32 = lexpect!("80".parse::<u32>(), "width must be a number");
+/// let height = lexpect!(Some(24), "height missing", "test.log");
+/// assert_eq!(width * height, 1920);
+///# }
+/// ```
+#[macro_export]
+macro_rules! lexpect {
+    ($x:expr, $msg:expr) => {
+        $crate::lexpect!($x, $msg, &$crate::default_log())
+    };
+    ($x:expr, $msg:expr, $log:expr) => {
+        $crate::LoggedUnwrap::logged_unwrap($x, Some($msg), format_args!("{}:{}", file!(), line!()), $log)
+    };
+}
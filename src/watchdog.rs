@@ -0,0 +1,134 @@
+//! A watchdog that logs when the process stops making progress.
+//! 
+//! call `heartbeat()` from your main loop and `Watchdog::new(timeout).start()` once at startup.\
+//! if no heartbeat arrives within the timeout a `Level::Warn` record is logged, so hangs that never panic still leave a trace.
+
+use std::sync::Mutex;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+///when the last heartbeat was received
+static LAST_BEAT: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Tells every running watchdog that the process is still alive.
+pub fn heartbeat() {
+    *LAST_BEAT.lock().unwrap_or_else(|e| e.into_inner()) = Some(Instant::now());
+}
+
+///returns how long ago the last heartbeat was, starting the clock if there was none
+fn since_last_beat() -> Duration {
+    let mut last = LAST_BEAT.lock().unwrap_or_else(|e| e.into_inner());
+    last.get_or_insert_with(Instant::now).elapsed()
+}
+
+/// Configures and starts a background watchdog thread.
+/// 
+/// If no log file supplied then defaults to the `default_log()` of the thread that created it.
+/// 
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# use std::time::Duration;
+///# fn main() {
+/// let _watchdog = watchdog::Watchdog::new(Duration::from_secs(5))
+///     .log_file("test.log")
+///     .dump_recent(true)
+///     .start();
+/// for _ in 0..3 {
+///     watchdog::heartbeat();
+///     //do a frame of work
+/// }
+///# }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Watchdog {
+    timeout: Duration,
+    log_file: String,
+    dump_recent: bool,
+}
+
+impl Watchdog {
+    ///creates a watchdog that warns after [timeout] without a heartbeat
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            log_file: crate::default_log(),
+            dump_recent: false,
+        }
+    }
+
+    ///sets the file warnings are logged to
+    pub fn log_file(mut self, log_file: &str) -> Self {
+        self.log_file = log_file.to_string();
+        self
+    }
+
+    ///also logs the contents of `recent_events()` when a hang is detected
+    pub fn dump_recent(mut self, dump: bool) -> Self {
+        self.dump_recent = dump;
+        self
+    }
+
+    /// Spawns the watchdog thread.
+    /// 
+    /// the thread stops when the returned guard is dropped.\
+    /// a hang is only reported once until the next heartbeat arrives.
+    pub fn start(self) -> WatchdogGuard {
+        heartbeat();
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+        let poll = (self.timeout / 4).max(Duration::from_millis(1));
+        thread::Builder::new()
+            .name("dynerr-watchdog".to_string())
+            .spawn(move || {
+                let mut reported = false;
+                while !thread_stop.load(Ordering::Relaxed) {
+                    thread::sleep(poll);
+                    let silent = since_last_beat();
+                    if silent < self.timeout {reported = false}
+                    else if !reported {
+                        reported = true;
+                        self.report(silent);
+                    }
+                }
+            })
+            .unwrap_or_else(|e| panic!("Dynerr: Error spawning watchdog thread: {}", e));
+        WatchdogGuard {stop}
+    }
+
+    ///logs the hang warning and optionally the recent events
+    fn report(&self, silent: Duration) {
+        crate::log_level(format!("Dynerr: Watchdog: no heartbeat for {:?} (timeout {:?})", silent, self.timeout), crate::Level::Warn, &self.log_file);
+        if self.dump_recent {
+            crate::writer::without_remembering(|| for event in crate::recent_events() {
+                crate::log_level(format!("Dynerr: Watchdog: recent: {}", event), crate::Level::Warn, &self.log_file);
+            });
+        }
+    }
+}
+
+/// Stops the watchdog thread when dropped.
+#[derive(Debug)]
+pub struct WatchdogGuard {
+    stop: Arc<AtomicBool>,
+}
+
+impl Drop for WatchdogGuard {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dump_isnt_remembered() {
+        crate::log("dump_isnt_remembered", "test.log");
+        Watchdog::new(Duration::from_secs(1)).log_file("test.log").dump_recent(true).report(Duration::from_secs(2));
+        assert!(!crate::recent_events().iter().any(|e| e.contains("recent: dump_isnt_remembered")));
+    }
+}
@@ -0,0 +1,645 @@
+//! Writes records to their log files.
+
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::fs::OpenOptions;
+use std::io::{self, prelude::*};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use crate::{CsvFormat, Encoding, JsonFormat, Level, LogFiles, LogRecord, LogfmtFormat};
+use crate::config::{self, CapAction, Config};
+
+///appends a formatted record to every file in [log_files], failing over to the fallbacks for any that cant be written to
+pub(crate) fn write_record<L: LogFiles>(record: &LogRecord, log_files: L) {
+    crate::deferred::flush();
+    if STOPPED.load(Ordering::Relaxed) {return}
+    if !record.level.is_compiled_in() {return}
+    let redacted = redact(record);
+    let record = &*redacted;
+    let file_level = config::config().file_level_for(log_files.target());
+    let (single, many);
+    let files: &[&str] = match log_files.__single_log_file() {
+        Some(log_file) => {single = [log_file]; &single}
+        None => {many = log_files.log_files(); &many}
+    };
+    write_to(record, files, file_level, &mut |log_file, result| match result {
+        Ok(()) => recover(log_file, file_level),
+        Err(e) => fail_over(record, log_file, e),
+    });
+    write_stderr(record);
+    publish(record);
+    crate::summary::observe(record.level);
+    hold_pre_init(record);
+}
+
+///appends every record in [records] to every file in [log_files] and any matching tees with a single write to each,
+///so records logged by other threads cant land between them. audited logs are written a record at a time
+pub(crate) fn write_batch<L: LogFiles>(records: &[LogRecord], log_files: L) {
+    crate::deferred::flush();
+    if STOPPED.load(Ordering::Relaxed) {return}
+    let records: Vec<Cow<'_, LogRecord>> = records.iter().filter(|r| r.level.is_compiled_in()).map(redact).collect();
+    if records.is_empty() {return}
+    let file_level = config::config().file_level_for(log_files.target());
+    let (results, tees) = append_batch(&records, &log_files.log_files(), file_level);
+    for (log_file, included, result) in results {
+        match result {
+            Ok(()) => recover(&log_file, file_level),
+            Err(e) => for i in included {
+                fail_over(&records[i], &log_file, io::Error::new(e.kind(), e.to_string()));
+            },
+        }
+    }
+    tees_written(tees);
+    for record in &records {
+        write_stderr(record);
+        publish(record);
+        crate::summary::observe(record.level);
+        hold_pre_init(record);
+    }
+}
+
+///appends the records at [file_level] or more severe to each of [log_files] or the crash log, and the ones each tee wants to it,
+///a block per file. returns the records and result of every log file and the crash log, then the result of every tee
+#[allow(clippy::type_complexity)]
+fn append_batch(records: &[Cow<'_, LogRecord>], log_files: &[&str], file_level: Level) -> (Vec<(String, Vec<usize>, io::Result<()>)>, Vec<(String, io::Result<()>)>) {
+    let config = config::config();
+    let lines: Vec<String> = records.iter().map(|r| config.text_format.format(r) + "\n").collect();
+    let mut destinations: Vec<(&str, Vec<usize>)> = Vec::new();
+    for log_file in log_files.iter().copied() {
+        for (i, record) in records.iter().enumerate().filter(|(_, r)| r.level <= file_level) {
+            let destination = config.destination(record, log_file);
+            match destinations.iter_mut().find(|(path, _)| *path == destination) {
+                Some((_, included)) => if !included.contains(&i) {included.push(i)},
+                None => destinations.push((destination, vec![i])),
+            }
+        }
+    }
+    let block = |path: &str, batch: &[usize]| {
+        let text: String = batch.iter().map(|i| lines[*i].as_str()).collect();
+        append_block(&config, &batch.iter().map(|i| &*records[*i]).collect::<Vec<_>>(), &text, path)
+    };
+    let tees = config.tees.iter()
+        .map(|tee| {
+            let written = destinations.iter().find(|(path, _)| *path == tee.path).map(|(_, batch)| batch.as_slice()).unwrap_or_default();
+            let batch: Vec<usize> = (0..records.len()).filter(|i| !written.contains(i) && (tee.filter)(&records[*i])).collect();
+            (tee, batch)
+        })
+        .filter(|(_, batch)| !batch.is_empty())
+        .map(|(tee, batch)| (tee.path.clone(), batch))
+        .collect::<Vec<_>>();
+    let results = destinations.into_iter()
+        .map(|(path, mut batch)| {
+            batch.sort_unstable();
+            let result = block(path, &batch);
+            (path.to_string(), batch, result)
+        })
+        .collect();
+    let tees = tees.into_iter().map(|(path, batch)| {
+        let result = block(&path, &batch);
+        (path, result)
+    }).collect();
+    for (record, line) in records.iter().zip(&lines) {
+        let line = &line[..line.len() - 1];
+        crate::capture::observe(line);
+        remember(&record.message, line);
+    }
+    (results, tees)
+}
+
+///[record] after the configured redactors ran over it
+fn redact(record: &LogRecord) -> Cow<'_, LogRecord> {
+    config::config().redactors.apply(record)
+}
+
+thread_local! {
+    ///the buffer records are formatted into, reused so formatting doesnt allocate once it has grown
+    static LINE: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+///appends a formatted record to [log_file] and any matching tees without panicking
+pub(crate) fn try_write_record(record: &LogRecord, log_file: &str) -> io::Result<()> {
+    crate::deferred::flush();
+    if STOPPED.load(Ordering::Relaxed) {return Ok(())}
+    if !record.level.is_compiled_in() {return Ok(())}
+    let redacted = redact(record);
+    let record = &*redacted;
+    let file_level = config::config().file_level;
+    let mut result = Ok(());
+    write_to(record, &[log_file], file_level, &mut |_, r| if let Err(e) = r {result = Err(e)});
+    write_stderr(record);
+    publish(record);
+    crate::summary::observe(record.level);
+    hold_pre_init(record);
+    result
+}
+
+///appends a formatted record to each of [log_files] it is at [file_level] or more severe for, and to any matching tees.
+///[written] gets every log file or crash log that failed, or that works again after failing over.
+///a tee that cant be written to fails over on its own
+fn write_to(record: &LogRecord, log_files: &[&str], file_level: Level, written: &mut dyn FnMut(&str, io::Result<()>)) {
+    LINE.with(|buffer| match buffer.try_borrow_mut() {
+        Ok(mut buffer) => write_line(record, log_files, file_level, &mut buffer, written),
+        //a record logged while formatting another one (from a Display impl) gets its own buffer
+        Err(_) => write_line(record, log_files, file_level, &mut String::new(), written),
+    })
+}
+
+///formats [record] into [buffer] then writes it out, once to each destination.
+///only failures and files that failed over before are kept for [written], so a record written without trouble doesnt allocate
+fn write_line<'a>(record: &LogRecord, log_files: &[&'a str], file_level: Level, buffer: &mut String, written: &mut dyn FnMut(&str, io::Result<()>)) {
+    let config = config::config();
+    buffer.clear();
+    if config.text_format.write(buffer, record).is_err() {
+        drop(config);
+        for log_file in log_files {
+            written(log_file, Err(io::Error::other("Error formatting log record")));
+        }
+        return
+    }
+    buffer.push('\n');
+    let written_to = |path: &str| record.level <= file_level && log_files.iter().any(|f| config.destination(record, f) == path);
+    let mut results: Vec<(Cow<'a, str>, io::Result<()>)> = Vec::new();
+    if record.level <= file_level {
+        for (i, log_file) in log_files.iter().copied().enumerate() {
+            let destination = config.destination(record, log_file);
+            if log_files[..i].iter().any(|f| config.destination(record, f) == destination) {continue}
+            let result = append(&config, record, buffer, destination);
+            if result.is_err() || is_failed_over(destination) {
+                let destination = match destination == log_file {
+                    true => Cow::Borrowed(log_file),
+                    false => Cow::Owned(destination.to_string()),
+                };
+                results.push((destination, result));
+            }
+        }
+    }
+    let mut tees = Vec::new();
+    for tee in config.tees.iter().filter(|t| !written_to(&t.path) && (t.filter)(record)) {
+        let result = append(&config, record, buffer, &tee.path);
+        if result.is_err() || is_failed_over(&tee.path) {
+            tees.push((tee.path.clone(), result));
+        }
+    }
+    let line = &buffer[..buffer.len() - 1];
+    crate::capture::observe(line);
+    remember(&record.message, line);
+    drop(config);
+    for (destination, result) in results {
+        written(&destination, result);
+    }
+    tees_written(tees);
+}
+
+///reports the tees in [results] that couldnt be written to, and the ones that work again
+fn tees_written(results: Vec<(String, io::Result<()>)>) {
+    for (tee, result) in results {
+        match result {
+            Ok(()) => recover(&tee, Level::Info),
+            Err(e) => fail_tee(&tee, e),
+        }
+    }
+}
+
+///prints [record] to stderr if it passes the stderr threshold
+fn write_stderr(record: &LogRecord) {
+    let config = config::config();
+    if config.stderr_level.is_some_and(|level| record.level <= level) {
+        let _ = writeln!(io::stderr().lock(), "{}", config.text_format.format(record));
+    }
+}
+
+///appends [line], which ends in a newline, to [log_file], syncing it if the durability setting asks for it
+fn append(config: &Config, record: &LogRecord, line: &str, log_file: &str) -> io::Result<()> {
+    append_block(config, &[record], line, log_file)
+}
+
+///appends [records] to [log_file] in one write. [text] holds them in the text format, each ending in a newline,
+///and is written as is unless the file has another `Encoding`. audited logs are written a record at a time
+fn append_block(config: &Config, records: &[&LogRecord], text: &str, log_file: &str) -> io::Result<()> {
+    let context = |step: &str, e: io::Error| io::Error::new(e.kind(), format!("Error {} log: {}", step, e));
+    let mut options = OpenOptions::new();
+    options.append(true).create(true);
+    #[cfg(unix)]
+    if let Some(mode) = config.file_mode {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(mode);
+    }
+    let mut file = options.open(log_file).map_err(|e| context("opening", e))?;
+    #[cfg(unix)]
+    if let Some(mode) = config.file_mode {
+        check_mode(&file, mode).map_err(|e| context("opening", e))?;
+    }
+    crate::session::track(log_file);
+    let notice;
+    let notice_line;
+    let (records, text) = match config.size_cap {
+        Some((cap, action)) => match reach_cap(log_file, cap, file.metadata().map_err(|e| context("opening", e))?.len() + text.len() as u64) {
+            Cap::Under => (records, text),
+            Cap::Reached if action == CapAction::Stop => {
+                notice = LogRecord::new(Level::Error, format!("Dynerr: {} reached its size cap of {} bytes, logging stopped", log_file, cap))
+                    .with_field("log_file", log_file);
+                notice_line = config.text_format.format(&notice) + "\n";
+                STOPPED.store(true, Ordering::Relaxed);
+                (&[&notice][..], notice_line.as_str())
+            }
+            _ => return Ok(()),
+        },
+        None => (records, text),
+    };
+    let encode = |format: &dyn Fn(&LogRecord) -> String| records.iter().map(|r| format(r) + "\n").collect::<String>();
+    let encoded;
+    let text = match config.encoding_for(log_file) {
+        Encoding::Text => text,
+        Encoding::Json => {encoded = encode(&|r| JsonFormat::new().format(r)); &encoded}
+        Encoding::Logfmt => {encoded = encode(&|r| LogfmtFormat::new().format(r)); &encoded}
+        Encoding::InternedJson => {
+            let fresh = file.metadata().map_err(|e| context("opening", e))?.len() == 0;
+            encoded = crate::intern::encode(records, log_file, fresh);
+            &encoded
+        }
+        Encoding::Csv => {
+            let header = match file.metadata().map_err(|e| context("opening", e))?.len() {
+                0 => format!("{}\n", CsvFormat::HEADER),
+                _ => String::new(),
+            };
+            encoded = header + &encode(&|r| CsvFormat::new().format(r));
+            &encoded
+        }
+    };
+    let mut write = |text: &str| file.write_all(config.line_ending.apply(text).as_bytes());
+    let written = match config.audited.iter().any(|a| a == log_file) {
+        true => records.iter().try_for_each(|record| crate::audit::append_chained(config, record, log_file, &mut write)),
+        false => write(text),
+    };
+    if written.is_err() && config.encoding_for(log_file) == Encoding::InternedJson {
+        //the definitions may not have made it into the file, so the next record defines its strings again
+        crate::intern::forget(log_file);
+    }
+    written.map_err(|e| context("appending to", e))?;
+    if records.iter().any(|r| config.durability.should_sync(r.level)) {
+        file.sync_all().map_err(|e| context("syncing", e))?;
+    }
+    Ok(())
+}
+
+///set once a log file reaches its size cap with `CapAction::Stop`, after which nothing is logged until the next `init()`
+static STOPPED: AtomicBool = AtomicBool::new(false);
+
+///the log files that reached the size cap
+static CAPPED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+///where a log file stands against the size cap
+enum Cap {
+    Under,
+    ///reached by the write being made
+    Reached,
+    ///reached by an earlier write
+    Over,
+}
+
+///whether writing to [log_file] would take it to [size] bytes, past [cap]
+fn reach_cap(log_file: &str, cap: u64, size: u64) -> Cap {
+    let mut capped = CAPPED.lock().unwrap_or_else(|e| e.into_inner());
+    match capped.iter().any(|c| c == log_file) {
+        true => Cap::Over,
+        false if size <= cap => Cap::Under,
+        false => {
+            capped.push(log_file.to_string());
+            Cap::Reached
+        }
+    }
+}
+
+///forgets which log files reached the size cap and starts logging again, so a new configuration gets a fresh cap
+pub(crate) fn reset_cap() {
+    CAPPED.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    STOPPED.store(false, Ordering::Relaxed);
+}
+
+/// Returns the log files that reached the `LoggerBuilder::size_cap` and are no longer written to, until `init()` is called again.
+pub fn capped_logs() -> Vec<String> {
+    CAPPED.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+///the log files that couldnt be written to the last time a record was logged to them
+static FAILED_OVER: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+///writes [record], which couldnt be written to [log_file] because of [error], to the first fallback that works or stderr.
+///the first record to fail over from [log_file] is preceded by an error record saying why
+fn fail_over(record: &LogRecord, log_file: &str, error: io::Error) {
+    let notice = failure_notice(log_file, &error, "writing its records to the fallbacks");
+    let config = config::config();
+    let records: Vec<&LogRecord> = notice.iter().chain(Some(record)).collect();
+    let written = config.fallbacks.iter()
+        .filter(|fallback| *fallback != log_file)
+        .any(|fallback| records.iter().all(|record| {
+            let line = config.text_format.format(record) + "\n";
+            append(&config, record, &line, fallback).is_ok()
+        }));
+    if !written {
+        let mut stderr = io::stderr().lock();
+        for record in records.iter().filter(|r| config.stderr_level.is_none_or(|level| r.level > level)) {
+            let _ = writeln!(stderr, "{}", config.text_format.format(record));
+        }
+    }
+    drop(config);
+    if let Some(notice) = notice {
+        write_stderr(&notice);
+        publish(&notice);
+    }
+}
+
+///reports that the tee at [tee] couldnt be written to because of [error], the first time it happens.
+///the record was still written to its log file, so it isnt written anywhere else
+fn fail_tee(tee: &str, error: io::Error) {
+    if let Some(notice) = failure_notice(tee, &error, "skipping it") {
+        write_stderr(&notice);
+        publish(&notice);
+    }
+}
+
+///the error record saying why [log_file] couldnt be written to, or None if it already failed over
+fn failure_notice(log_file: &str, error: &io::Error, action: &str) -> Option<LogRecord> {
+    let mut failed = FAILED_OVER.lock().unwrap_or_else(|e| e.into_inner());
+    if failed.iter().any(|f| f == log_file) {return None}
+    failed.push(log_file.to_string());
+    let message = format!("Dynerr: couldnt write to {} ({}), {}", log_file, error, action);
+    Some(LogRecord::new(Level::Error, message).with_field("log_file", log_file))
+}
+
+///whether [log_file] couldnt be written to the last time
+fn is_failed_over(log_file: &str) -> bool {
+    FAILED_OVER.lock().unwrap_or_else(|e| e.into_inner()).iter().any(|f| f == log_file)
+}
+
+///writes a record saying [log_file] works again to it, if it had failed over
+fn recover(log_file: &str, file_level: Level) {
+    {
+        let mut failed = FAILED_OVER.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(index) = failed.iter().position(|f| f == log_file) else {return};
+        failed.remove(index);
+    }
+    let notice = LogRecord::new(Level::Info, format!("Dynerr: writing to {} again", log_file));
+    write_to(&notice, &[log_file], file_level, &mut |_, _| {});
+}
+
+/// Returns the log files that couldnt be written to the last time something was logged to them.
+/// 
+/// their records are going to the `LoggerBuilder::fallback` logs or stderr instead.
+/// a tee that cant be written to is listed too, and skipped since its records are still in their log files.
+pub fn failed_over_logs() -> Vec<String> {
+    FAILED_OVER.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+///fails if [file] grants permissions outside [mode]
+#[cfg(unix)]
+fn check_mode(file: &std::fs::File, mode: u32) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let actual = file.metadata()?.permissions().mode() & 0o777;
+    match actual & !mode {
+        0 => Ok(()),
+        _ => Err(io::Error::new(io::ErrorKind::PermissionDenied, format!("permissions {:o} are looser than {:o}", actual, mode))),
+    }
+}
+
+///how many records logged before `init()` are kept to replay
+const PRE_INIT_CAPACITY: usize = 256;
+
+///the first PRE_INIT_CAPACITY records logged before `init()`, and how many were logged in total
+static PRE_INIT: Mutex<(Vec<LogRecord>, usize)> = Mutex::new((Vec::new(), 0));
+
+///keeps [record] to replay once `init()` is called, if it hasnt been yet.
+///once PRE_INIT_CAPACITY are kept the rest are only counted, so a program that never calls `init()` stops copying records
+fn hold_pre_init(record: &LogRecord) {
+    if config::is_initialized() {return}
+    let mut held = PRE_INIT.lock().unwrap_or_else(|e| e.into_inner());
+    if held.0.len() < PRE_INIT_CAPACITY {
+        held.0.push(record.clone());
+    }
+    held.1 += 1;
+}
+
+///warns that records were logged before `init()` then writes them to the tees and stderr it configured
+pub(crate) fn replay_pre_init() {
+    let (records, count) = std::mem::take(&mut *PRE_INIT.lock().unwrap_or_else(|e| e.into_inner()));
+    if count == 0 {return}
+    let warning = format!("Dynerr: {} record(s) were logged before init() with the default configuration", count);
+    write_record(&LogRecord::new(Level::Warn, warning), crate::default_log());
+    for record in &records {
+        let config = config::config();
+        let mut line = config.text_format.format(record);
+        line.push('\n');
+        for tee in config.tees.iter().filter(|t| (t.filter)(record)) {
+            let _ = append(&config, record, &line, &tee.path);
+        }
+        drop(config);
+        write_stderr(record);
+    }
+}
+
+///how many events `recent_events` keeps
+const RECENT_CAPACITY: usize = 64;
+
+///ring buffer of the last RECENT_CAPACITY logged events and their formatted lines
+static RECENT: Mutex<VecDeque<(String, String)>> = Mutex::new(VecDeque::new());
+
+thread_local! {
+    ///set while writing records that are already in the recent events ring buffer
+    static DUMPING: Cell<bool> = const { Cell::new(false) };
+}
+
+///runs [dump] without pushing what it logs into the recent events ring buffer, so dumping it doesnt refill it
+pub(crate) fn without_remembering<T>(dump: impl FnOnce() -> T) -> T {
+    let dumping = DUMPING.with(|d| d.replace(true));
+    let value = dump();
+    DUMPING.with(|d| d.set(dumping));
+    value
+}
+
+///pushes an event into the recent events ring buffer, reusing the strings of the oldest entry once its full
+fn remember(event: &str, line: &str) {
+    if DUMPING.with(Cell::get) {return}
+    let mut recent = RECENT.lock().unwrap_or_else(|e| e.into_inner());
+    let (mut old_event, mut old_line) = match recent.len() {
+        RECENT_CAPACITY => recent.pop_front().unwrap_or_default(),
+        _ => Default::default(),
+    };
+    old_event.clear();
+    old_event.push_str(event);
+    old_line.clear();
+    old_line.push_str(line);
+    recent.push_back((old_event, old_line));
+}
+
+///the formatted lines of the recent events, oldest first
+pub(crate) fn recent_lines() -> Vec<String> {
+    RECENT.lock().unwrap_or_else(|e| e.into_inner()).iter().map(|(_, line)| line.clone()).collect()
+}
+
+/// Returns the most recently logged events, oldest first.
+/// 
+/// only the last 64 events are kept.
+/// 
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// log!("remember me", "test.log");
+/// assert!(recent_events().contains(&"remember me".to_string()));
+///# }
+/// ```
+pub fn recent_events() -> Vec<String> {
+    RECENT.lock().unwrap_or_else(|e| e.into_inner()).iter().map(|(event, _)| event.clone()).collect()
+}
+
+///every live subscription
+static SUBSCRIBERS: Mutex<Vec<Sender<LogRecord>>> = Mutex::new(Vec::new());
+
+///sends [record] to every subscriber, forgetting the ones whose receiver was dropped
+fn publish(record: &LogRecord) {
+    let mut subscribers = SUBSCRIBERS.lock().unwrap_or_else(|e| e.into_inner());
+    subscribers.retain(|s| s.send(record.clone()).is_ok());
+}
+
+/// Returns a channel that receives a copy of every record logged from now on, whichever file it was written to.
+/// 
+/// records are buffered until received. drop the receiver to unsubscribe.
+/// 
+///# Example
+/// ```rust
+///# use dynerr::*;
+///# fn main() {
+/// let console = subscribe();
+/// log_warn!("low health", "test.log");
+/// let record = console.recv().unwrap();
+/// assert_eq!(record.level, Level::Warn);
+/// assert_eq!(record.message, "low health");
+///# }
+/// ```
+pub fn subscribe() -> Receiver<LogRecord> {
+    let (sender, receiver) = mpsc::channel();
+    SUBSCRIBERS.lock().unwrap_or_else(|e| e.into_inner()).push(sender);
+    receiver
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Logger;
+    use std::alloc::{GlobalAlloc, Layout, System};
+
+    ///the system allocator, counting the allocations of each thread
+    struct Counting;
+
+    thread_local! {
+        static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    unsafe impl GlobalAlloc for Counting {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let _ = ALLOCATIONS.try_with(|a| a.set(a.get() + 1));
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: Counting = Counting;
+
+    ///a path in the temp dir for [name] with no file at it
+    fn temp_log(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("dynerr_writer_{}_{}.log", std::process::id(), name));
+        let _ = std::fs::remove_file(&path);
+        path.to_string_lossy().into_owned()
+    }
+
+    ///how many lines of [path] contain [text]
+    fn count(path: &str, text: &str) -> usize {
+        std::fs::read_to_string(path).unwrap_or_default().lines().filter(|l| l.contains(text)).count()
+    }
+
+    #[test]
+    fn tees_once_per_record() {
+        let _config = config::TEST_CONFIG.lock().unwrap_or_else(|e| e.into_inner());
+        let (a, b, tee) = (temp_log("a"), temp_log("b"), temp_log("tee"));
+        Logger::builder().tee(&tee, |r| r.message.starts_with("tees_once")).init();
+        write_record(&LogRecord::new(Level::Info, "tees_once_per_record"), [&a, &b]);
+        write_batch(&[LogRecord::new(Level::Info, "tees_once_per_batch")], [&a, &b]);
+        crate::init();
+        for message in ["tees_once_per_record", "tees_once_per_batch"] {
+            assert_eq!((count(&a, message), count(&b, message), count(&tee, message)), (1, 1, 1));
+            assert_eq!(recent_lines().iter().filter(|l| l.contains(message)).count(), 1);
+        }
+    }
+
+    #[test]
+    fn crash_log_once_per_record() {
+        let _config = config::TEST_CONFIG.lock().unwrap_or_else(|e| e.into_inner());
+        let (a, b, crash) = (temp_log("crash_a"), temp_log("crash_b"), temp_log("crash"));
+        Logger::builder().crash_log(&crash).init();
+        write_record(&LogRecord::new(Level::Error, "crash_log_once_per_record"), [&a, &b]);
+        write_batch(&[LogRecord::new(Level::Error, "crash_log_once_per_batch")], [&a, &b]);
+        crate::init();
+        for message in ["crash_log_once_per_record", "crash_log_once_per_batch"] {
+            assert_eq!((count(&a, message), count(&b, message), count(&crash, message)), (0, 0, 1));
+        }
+    }
+
+    #[test]
+    fn tee_failure_skips_only_the_tee() {
+        let _config = config::TEST_CONFIG.lock().unwrap_or_else(|e| e.into_inner());
+        let (log, tee) = (temp_log("tee_failure"), temp_log("working_tee"));
+        let missing = std::env::temp_dir().join("dynerr_missing_dir").join("tee.log").to_string_lossy().into_owned();
+        Logger::builder()
+            .tee(&missing, |r| r.message == "tee_failure_skips_only_the_tee")
+            .tee(&tee, |r| r.message == "tee_failure_skips_only_the_tee")
+            .init();
+        write_record(&LogRecord::new(Level::Info, "tee_failure_skips_only_the_tee"), &log);
+        crate::init();
+        assert_eq!((count(&log, "tee_failure"), count(&tee, "tee_failure")), (1, 1));
+        assert!(failed_over_logs().contains(&missing));
+        assert!(!failed_over_logs().contains(&log));
+    }
+
+    #[test]
+    fn writing_doesnt_allocate() {
+        let _config = config::TEST_CONFIG.lock().unwrap_or_else(|e| e.into_inner());
+        crate::init();
+        let log = temp_log("allocations");
+        //an empty message is the shortest line, so the slots of the recent events ring never have to grow
+        for _ in 0..=RECENT_CAPACITY {
+            crate::log!("", log.as_str());
+        }
+        let before = ALLOCATIONS.with(Cell::get);
+        crate::log!("", log.as_str());
+        assert_eq!(ALLOCATIONS.with(Cell::get) - before, 0);
+    }
+
+    #[test]
+    fn init_lifts_the_size_cap() {
+        let _config = config::TEST_CONFIG.lock().unwrap_or_else(|e| e.into_inner());
+        let (capped, fresh) = (temp_log("capped"), temp_log("after_cap"));
+        Logger::builder().size_cap(64, CapAction::Stop).init();
+        while capped_logs().is_empty() {
+            write_record(&LogRecord::new(Level::Info, "init_lifts_the_size_cap"), &capped);
+        }
+        write_record(&LogRecord::new(Level::Info, "logged while stopped"), &fresh);
+        assert_eq!(count(&fresh, "logged while stopped"), 0);
+        crate::init();
+        assert!(capped_logs().is_empty());
+        write_record(&LogRecord::new(Level::Info, "logged after init"), &fresh);
+        write_record(&LogRecord::new(Level::Info, "logged after init"), &capped);
+        assert_eq!((count(&fresh, "logged after init"), count(&capped, "logged after init")), (1, 1));
+        Logger::builder().size_cap(64, CapAction::Stop).init();
+        write_record(&LogRecord::new(Level::Info, "already past the cap"), &capped);
+        crate::init();
+        assert_eq!(count(&capped, "already past the cap"), 0);
+        assert_eq!(count(&capped, "reached its size cap"), 2);
+    }
+}